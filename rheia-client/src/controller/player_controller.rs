@@ -17,6 +17,7 @@ use crate::world::worlds_manager::WorldsManager;
 use common::blocks::block_info::BlockFace;
 use common::chunks::chunk_data::BlockDataInfo;
 use common::chunks::rotation::Rotation;
+use common::{PLAYER_GRAVITY, PLAYER_HEIGHT, PLAYER_JUMP_SPEED, PLAYER_MASS, PLAYER_MOVEMENT_SPEED, PLAYER_RADIUS};
 use godot::classes::input::MouseMode;
 use godot::classes::Input;
 use godot::global::{deg_to_rad, lerp_angle};
@@ -27,19 +28,22 @@ use physics::physics::{IPhysicsCharacterController, IPhysicsCollider, IPhysicsCo
 use physics::{PhysicsCharacterController, PhysicsCollider, PhysicsColliderBuilder, QueryFilter};
 
 const TURN_SPEED: f64 = 6.0;
-const MOVEMENT_SPEED: f32 = 4.0;
+const MOVEMENT_SPEED: f32 = PLAYER_MOVEMENT_SPEED;
 
-const CHARACTER_GRAVITY: f32 = -10.0;
-const JUMP_SPEED: f32 = 8.0;
+const CHARACTER_GRAVITY: f32 = PLAYER_GRAVITY;
+const JUMP_SPEED: f32 = PLAYER_JUMP_SPEED;
 const SNAP_TO_GROUND: f32 = 0.1;
 
+// Seconds between the movement messages; the server limits their rate
+const MOVE_SEND_INTERVAL: f64 = 1.0 / 20.0;
+
 pub(crate) const CAMERA_DISTANCE: f32 = 2.5;
 pub(crate) const CONTROLLER_CAMERA_OFFSET_RIGHT: f32 = 0.45;
 pub(crate) const CONTROLLER_CAMERA_OFFSET_VERTICAL: f32 = CONTROLLER_HEIGHT * 0.95;
 
-const CONTROLLER_HEIGHT: f32 = 1.8;
-const CONTROLLER_RADIUS: f32 = 0.4;
-const CONTROLLER_MASS: f32 = 3.0;
+const CONTROLLER_HEIGHT: f32 = PLAYER_HEIGHT;
+const CONTROLLER_RADIUS: f32 = PLAYER_RADIUS;
+const CONTROLLER_MASS: f32 = PLAYER_MASS;
 
 #[derive(GodotClass)]
 #[class(no_init, base=Node3D)]
@@ -53,6 +57,8 @@ pub struct PlayerController {
 
     controls: Gd<Controls>,
     cache_movement: Option<Gd<EntityMovement>>,
+    sent_movement: Option<Gd<EntityMovement>>,
+    move_send_timer: f64,
 
     // Physics
     collider: PhysicsCollider,
@@ -103,6 +109,8 @@ impl PlayerController {
 
             controls,
             cache_movement: None,
+            sent_movement: None,
+            move_send_timer: 0.0,
 
             character_controller: PhysicsCharacterController::create(Some(CONTROLLER_MASS), Some(SNAP_TO_GROUND)),
             collider,
@@ -142,7 +150,7 @@ impl PlayerController {
                 }
                 None => {
                     let components = vec![EntityNetworkComponent::Skin(Some(skin))];
-                    let mut entity = Gd::<Entity>::from_init_fn(|base| Entity::create(base, components));
+                    let mut entity = Gd::<Entity>::from_init_fn(|base| Entity::create(base, components, None));
                     self.base_mut().add_child(&entity);
                     let entity_visible = match self.camera_mode {
                         CameraMode::FirstPerson => false,
@@ -415,6 +423,7 @@ impl PlayerController {
         ));
 
         self.update_cache_movement();
+        self.send_movement(delta);
 
         let elapsed = now.elapsed();
         #[cfg(debug_assertions)]
@@ -434,14 +443,6 @@ impl PlayerController {
         });
 
         if self.cache_movement.is_none() || *new_movement.bind() != *self.cache_movement.as_ref().unwrap().bind() {
-            let new_chunk = if let Some(old) = self.cache_movement.as_ref() {
-                let c1 = old.bind().get_position().to_chunk_position();
-                let c2 = new_movement.bind().get_position().to_chunk_position();
-                c1 != c2
-            } else {
-                false
-            };
-
             let movement = if let Some(old) = self.cache_movement.as_ref() {
                 *new_movement.bind().get_position() - *old.bind().get_position()
             } else {
@@ -450,12 +451,34 @@ impl PlayerController {
             if let Some(entity) = self.entity.as_mut() {
                 entity.bind_mut().handle_movement(movement);
             }
-
-            self.signals().player_move().emit(&new_movement, new_chunk);
             self.cache_movement = Some(new_movement);
         }
     }
 
+    /// Movement is sent with the fixed rate instead of every frame
+    fn send_movement(&mut self, delta: f64) {
+        self.move_send_timer += delta;
+        if self.move_send_timer < MOVE_SEND_INTERVAL {
+            return;
+        }
+        self.move_send_timer = 0.0;
+
+        let Some(new_movement) = self.cache_movement.clone() else {
+            return;
+        };
+        let new_chunk = match self.sent_movement.as_ref() {
+            Some(old) if *old.bind() == *new_movement.bind() => return,
+            Some(old) => {
+                let c1 = old.bind().get_position().to_chunk_position();
+                let c2 = new_movement.bind().get_position().to_chunk_position();
+                c1 != c2
+            }
+            None => false,
+        };
+        self.signals().player_move().emit(&new_movement, new_chunk);
+        self.sent_movement = Some(new_movement);
+    }
+
     pub fn set_blocks(&mut self, worlds_manager: &WorldsManager) {
         let block_storage_lock = worlds_manager.get_block_storage_lock();
 
@@ -6,6 +6,8 @@ use network::{
     messages::{NetworkEntitySkin, NetworkEntityTag},
 };
 
+use crate::scenes::components::block_mesh_storage::BlockMeshStorage;
+
 use super::entity::Entity;
 
 #[derive(GodotClass)]
@@ -34,13 +36,14 @@ impl EntitiesManager {
         position: Vector3,
         rotation: Rotation,
         components: Vec<EntityNetworkComponent>,
+        block_mesh_storage: Option<&BlockMeshStorage>,
     ) {
         if self.entities.contains_key(&id) {
             log::error!(target: "entities", "Tried to spawn existing entity id:{}", id);
             return;
         }
 
-        let mut entity = Gd::<Entity>::from_init_fn(|base| Entity::create(base, components));
+        let mut entity = Gd::<Entity>::from_init_fn(|base| Entity::create(base, components, block_mesh_storage));
         self.base_mut().add_child(&entity);
 
         self.entities.insert(id, entity.clone());
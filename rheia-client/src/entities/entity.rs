@@ -5,10 +5,13 @@ use network::{
     messages::{NetworkEntitySkin, NetworkEntityTag},
 };
 
+use crate::scenes::components::block_mesh_storage::BlockMeshStorage;
+
 use super::{entity_tag::EntityTag, enums::generic_animations::GenericAnimations, generic_skin::GenericSkin};
 
 enum EntitySkinContainer {
     Generic(Gd<GenericSkin>),
+    Block(Gd<Node3D>),
 }
 
 #[derive(GodotClass)]
@@ -24,7 +27,12 @@ pub struct Entity {
 }
 
 impl Entity {
-    pub fn create(base: Base<Node3D>, components: Vec<EntityNetworkComponent>) -> Self {
+    /// Block skin without block_mesh_storage stays empty
+    pub fn create(
+        base: Base<Node3D>,
+        components: Vec<EntityNetworkComponent>,
+        block_mesh_storage: Option<&BlockMeshStorage>,
+    ) -> Self {
         let mut skin: Option<NetworkEntitySkin> = None;
         let mut tag: Option<Gd<EntityTag>> = None;
         for component in components {
@@ -46,6 +54,13 @@ impl Entity {
             NetworkEntitySkin::Fixed(_) => {
                 todo!()
             }
+            NetworkEntitySkin::Block(block_id) => match block_mesh_storage {
+                Some(block_mesh_storage) => EntitySkinContainer::Block(block_mesh_storage.get_mesh(&block_id)),
+                None => {
+                    log::error!(target: "entities", "Block skin #{} can't be created without the block meshes", block_id);
+                    EntitySkinContainer::Block(Node3D::new_alloc())
+                }
+            },
         };
         Self {
             base,
@@ -59,6 +74,7 @@ impl Entity {
     pub fn get_current_animation(&self) -> String {
         match &self.skin {
             EntitySkinContainer::Generic(skin) => skin.bind().get_current_animation(),
+            EntitySkinContainer::Block(_) => String::new(),
         }
     }
 
@@ -123,6 +139,7 @@ impl Entity {
     pub fn trigger_animation(&mut self, animation: GenericAnimations) {
         match &mut self.skin {
             EntitySkinContainer::Generic(skin) => skin.bind_mut().trigger_animation(animation),
+            EntitySkinContainer::Block(_) => (),
         }
     }
 
@@ -135,6 +152,7 @@ impl Entity {
         // let movement = position - e.get_position();
         match &mut self.skin {
             EntitySkinContainer::Generic(skin) => skin.bind_mut().handle_movement(movement),
+            EntitySkinContainer::Block(_) => (),
         }
     }
 }
@@ -145,6 +163,7 @@ impl INode3D for Entity {
         let mut base = self.base_mut().clone();
         match &self.skin {
             EntitySkinContainer::Generic(skin) => base.add_child(skin),
+            EntitySkinContainer::Block(mesh) => base.add_child(mesh),
         }
         if let Some(tag) = self.tag.as_ref() {
             base.add_child(tag);
@@ -68,6 +68,7 @@ pub fn handle_network_events(main: &mut MainScene) -> Result<NetworkInfo, String
                     version: VERSION.to_string(),
                     architecture: Engine::singleton().get_architecture_name().to_string(),
                     rendering_device: device_name,
+                    render_distance: main.get_render_distance(),
                 };
                 log::info!(target: "network", "Server allowed connection");
                 network.send_message(NetworkMessageType::ReliableOrdered, &connection_info);
@@ -225,6 +226,17 @@ pub fn handle_network_events(main: &mut MainScene) -> Result<NetworkInfo, String
                     }
                 }
             }
+            ServerMessages::PlayerTeleport { world_slug, position } => {
+                let mut worlds_manager = main.get_worlds_manager_mut();
+                let Some(_world) = get_world_mut(&mut worlds_manager, world_slug) else {
+                    continue;
+                };
+                let Some(player_controller) = worlds_manager.get_player_controller_mut().as_mut() else {
+                    log::error!(target: "network", "network tried to teleport with non existing world");
+                    continue;
+                };
+                player_controller.bind_mut().set_position(position.to_godot());
+            }
             ServerMessages::ChunkSectionInfoEncoded { .. } => {
                 panic!("ChunkSectionInfoEncoded must be decoded");
             }
@@ -233,6 +245,35 @@ pub fn handle_network_events(main: &mut MainScene) -> Result<NetworkInfo, String
                 chunk_position,
                 sections,
             } => {
+                // The server resends the whole chunk after bulk changes
+                let resent = {
+                    let worlds_manager = main.get_wm().bind();
+                    let Some(world) = get_world(&worlds_manager, world_slug.clone()) else {
+                        continue;
+                    };
+                    let changes = world
+                        .bind()
+                        .get_chunk_map()
+                        .get_resent_chunk_changes(&chunk_position, &sections);
+                    match changes {
+                        Some(changes) => {
+                            let block_storage = worlds_manager.get_block_storage();
+                            let resource_manager = main.get_resource_manager();
+                            let resources_storage = resource_manager.get_resources_storage();
+                            let w = world.bind();
+                            for (position, new_block_info) in changes {
+                                w.edit_block(position, &block_storage, new_block_info, &*resources_storage)
+                                    .unwrap();
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                };
+                if resent {
+                    continue;
+                }
+
                 let mut worlds_manager = main.get_worlds_manager_mut();
 
                 let center = match worlds_manager.get_player_controller() {
@@ -263,12 +304,20 @@ pub fn handle_network_events(main: &mut MainScene) -> Result<NetworkInfo, String
                 components,
             } => {
                 let mut worlds_manager = main.get_worlds_manager_mut();
+                let block_mesh_storage = worlds_manager.get_block_mesh_storage().cloned();
                 let Some(world) = get_world_mut(&mut worlds_manager, world_slug) else {
                     continue;
                 };
                 let mut w = world.bind_mut();
                 let mut entities_manager = w.get_entities_manager_mut();
-                entities_manager.create_entity(id, position.to_godot(), rotation, components);
+                let block_mesh_storage = block_mesh_storage.as_ref().map(|s| s.bind());
+                entities_manager.create_entity(
+                    id,
+                    position.to_godot(),
+                    rotation,
+                    components,
+                    block_mesh_storage.as_deref(),
+                );
             }
             ServerMessages::UpdateEntityComponent {
                 world_slug,
@@ -326,6 +375,41 @@ pub fn handle_network_events(main: &mut MainScene) -> Result<NetworkInfo, String
                     .edit_block(position, &block_storage, new_block_info, &*resources_storage)
                     .unwrap();
             }
+            ServerMessages::EditBlocks { world_slug, changes } => {
+                let worlds_manager = main.get_wm().bind();
+                let Some(world) = get_world(&worlds_manager, world_slug) else {
+                    continue;
+                };
+                let block_storage = worlds_manager.get_block_storage();
+                let resource_manager = main.get_resource_manager();
+                let resources_storage = resource_manager.get_resources_storage();
+                let w = world.bind();
+                for (position, new_block_info) in changes {
+                    w.edit_block(position, &block_storage, new_block_info, &*resources_storage)
+                        .unwrap();
+                }
+            }
+            ServerMessages::WorldTime { world_slug, world_time } => {
+                let mut worlds_manager = main.get_worlds_manager_mut();
+                let Some(world) = get_world_mut(&mut worlds_manager, world_slug) else {
+                    continue;
+                };
+                world.bind_mut().set_world_time(world_time);
+            }
+            ServerMessages::WorldWeather { world_slug, weather } => {
+                let mut worlds_manager = main.get_worlds_manager_mut();
+                let Some(world) = get_world_mut(&mut worlds_manager, world_slug) else {
+                    continue;
+                };
+                world.bind_mut().set_weather(weather);
+            }
+            ServerMessages::WorldBorder { world_slug, border } => {
+                let mut worlds_manager = main.get_worlds_manager_mut();
+                let Some(world) = get_world_mut(&mut worlds_manager, world_slug) else {
+                    continue;
+                };
+                world.bind_mut().set_border(border);
+            }
         }
     }
 
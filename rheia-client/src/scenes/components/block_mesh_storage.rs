@@ -49,7 +49,7 @@ impl BlockMeshStorage {
             BlockContent::Texture { .. } => {
                 let block_info = BlockDataInfo::create(block_id, None);
                 let bordered_chunk_data = generate_single_block(&block_type, &block_info);
-                let geometry = generate_chunk_geometry(texture_mapper, &bordered_chunk_data, &block_storage);
+                let geometry = generate_chunk_geometry(texture_mapper, &bordered_chunk_data, None, &block_storage);
 
                 let mut mesh = MeshInstance3D::new_alloc();
                 mesh.set_name("Block mesh");
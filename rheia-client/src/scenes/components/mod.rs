@@ -1,3 +1,5 @@
 pub mod block_icon;
 pub mod block_menu;
 pub mod block_mesh_storage;
+pub mod weather_particles;
+pub mod world_border_wall;
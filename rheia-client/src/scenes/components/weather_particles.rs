@@ -0,0 +1,88 @@
+use common::world_weather::{Weather, WeatherType};
+use godot::{
+    classes::{
+        BoxMesh, CpuParticles3D, ICpuParticles3D, OrmMaterial3D,
+        base_material_3d::ShadingMode,
+        cpu_particles_3d::{EmissionShape, Parameter},
+    },
+    prelude::*,
+};
+
+const MAX_PARTICLES: f32 = 4000.0;
+
+// Particles are emitted above the player
+const EMISSION_HEIGHT: f32 = 14.0;
+const EMISSION_EXTENTS: Vector3 = Vector3::new(20.0, 1.0, 20.0);
+
+/// Rain and snow around the player
+#[derive(GodotClass)]
+#[class(init, base=CpuParticles3D)]
+pub struct WeatherParticles {
+    base: Base<CpuParticles3D>,
+    weather: Option<Weather>,
+}
+
+impl WeatherParticles {
+    fn set_particle(&mut self, size: Vector3, color: Color, velocity: f32, lifetime: f64) {
+        let mut material = OrmMaterial3D::new_gd();
+        material.set_shading_mode(ShadingMode::UNSHADED);
+        material.set_albedo(color);
+
+        let mut mesh = BoxMesh::new_gd();
+        mesh.set_size(size);
+        mesh.set_material(&material);
+
+        let mut base = self.base_mut();
+        base.set_mesh(&mesh);
+        base.set_param_min(Parameter::INITIAL_LINEAR_VELOCITY, velocity);
+        base.set_param_max(Parameter::INITIAL_LINEAR_VELOCITY, velocity * 1.2);
+        base.set_lifetime(lifetime);
+    }
+
+    pub fn update_weather(&mut self, weather: &Weather) {
+        if self.weather.as_ref() == Some(weather) {
+            return;
+        }
+        self.weather = Some(weather.clone());
+
+        match weather.get_weather_type() {
+            WeatherType::Clear => {
+                self.base_mut().set_emitting(false);
+                return;
+            }
+            WeatherType::Rain => {
+                self.set_particle(Vector3::new(0.02, 0.5, 0.02), Color::from_rgb(0.6, 0.7, 0.9), 18.0, 1.0)
+            }
+            WeatherType::Storm => {
+                self.set_particle(Vector3::new(0.02, 0.7, 0.02), Color::from_rgb(0.5, 0.6, 0.8), 26.0, 0.7)
+            }
+            WeatherType::Snow => {
+                self.set_particle(Vector3::new(0.08, 0.08, 0.08), Color::from_rgb(1.0, 1.0, 1.0), 2.0, 7.0)
+            }
+        }
+
+        let amount = (MAX_PARTICLES * weather.get_intensity()).max(1.0) as i32;
+        let mut base = self.base_mut();
+        base.set_amount(amount);
+        base.set_emitting(true);
+    }
+
+    pub fn follow(&mut self, position: Vector3) {
+        self.base_mut()
+            .set_global_position(position + Vector3::UP * EMISSION_HEIGHT);
+    }
+}
+
+#[godot_api]
+impl ICpuParticles3D for WeatherParticles {
+    fn ready(&mut self) {
+        let mut base = self.base_mut();
+        base.set_emitting(false);
+        base.set_use_local_coordinates(false);
+        base.set_emission_shape(EmissionShape::BOX);
+        base.set_emission_box_extents(EMISSION_EXTENTS);
+        base.set_direction(Vector3::DOWN);
+        base.set_spread(5.0);
+        base.set_gravity(Vector3::ZERO);
+    }
+}
@@ -0,0 +1,81 @@
+use common::{
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+    world_border::{WorldBorder, WorldBorderShape},
+};
+use godot::{
+    classes::{
+        CylinderMesh, IMeshInstance3D, MeshInstance3D, StandardMaterial3D,
+        base_material_3d::{CullMode, ShadingMode, Transparency},
+        geometry_instance_3d::ShadowCastingSetting,
+    },
+    prelude::*,
+};
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
+
+const WALL_HEIGHT: f32 = (VERTICAL_SECTIONS * CHUNK_SIZE as usize) as f32;
+const CIRCLE_SEGMENTS: i32 = 128;
+
+/// Transparent wall along the world border
+#[derive(GodotClass)]
+#[class(init, base=MeshInstance3D)]
+pub struct WorldBorderWall {
+    base: Base<MeshInstance3D>,
+    mesh: Option<Gd<CylinderMesh>>,
+    border: Option<WorldBorder>,
+}
+
+impl WorldBorderWall {
+    pub fn update_border(&mut self, border: Option<&WorldBorder>) {
+        if self.border.as_ref() == border {
+            return;
+        }
+        self.border = border.cloned();
+
+        let Some(border) = border else {
+            self.base_mut().set_visible(false);
+            return;
+        };
+        let (x, z) = border.get_center();
+
+        // Square is the cylinder with four sides turned along the axes
+        let (radius, segments, angle) = match border.get_shape() {
+            WorldBorderShape::Circle => (border.get_size(), CIRCLE_SEGMENTS, 0.0),
+            WorldBorderShape::Square => (border.get_size() * SQRT_2, 4, FRAC_PI_4),
+        };
+        let Some(mut mesh) = self.mesh.clone() else {
+            return;
+        };
+        mesh.set_top_radius(radius);
+        mesh.set_bottom_radius(radius);
+        mesh.set_radial_segments(segments);
+
+        let mut base = self.base_mut();
+        base.set_position(Vector3::new(x, WALL_HEIGHT / 2.0, z));
+        base.set_rotation(Vector3::new(0.0, angle, 0.0));
+        base.set_visible(true);
+    }
+}
+
+#[godot_api]
+impl IMeshInstance3D for WorldBorderWall {
+    fn ready(&mut self) {
+        let mut material = StandardMaterial3D::new_gd();
+        material.set_shading_mode(ShadingMode::UNSHADED);
+        material.set_transparency(Transparency::ALPHA);
+        material.set_cull_mode(CullMode::DISABLED);
+        material.set_albedo(Color::from_rgba(0.2, 0.5, 1.0, 0.25));
+
+        let mut mesh = CylinderMesh::new_gd();
+        mesh.set_height(WALL_HEIGHT);
+        mesh.set_rings(0);
+        mesh.set_cap_top(false);
+        mesh.set_cap_bottom(false);
+        mesh.set_material(&material);
+        self.mesh = Some(mesh.clone());
+
+        let mut base = self.base_mut();
+        base.set_mesh(&mesh);
+        base.set_cast_shadows_setting(ShadowCastingSetting::OFF);
+        base.set_visible(false);
+    }
+}
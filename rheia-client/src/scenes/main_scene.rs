@@ -8,6 +8,8 @@ use crate::debug::debug_info::DebugInfo;
 use crate::logger::CONSOLE_LOGGER;
 use crate::network::client::NetworkContainer;
 use crate::network::events::handle_network_events;
+use crate::scenes::components::weather_particles::WeatherParticles;
+use crate::scenes::components::world_border_wall::WorldBorderWall;
 use crate::scenes::text_screen::TextScreen;
 use crate::utils::settings::GameSettings;
 use crate::utils::world_generator::generate_chunks;
@@ -17,9 +19,11 @@ use crate::{LOG_LEVEL, MAX_THREADS};
 use common::blocks::block_info::generate_block_id_map;
 use common::chunks::chunk_data::BlockIndexType;
 use common::world_generator::default::WorldGeneratorSettings;
+use common::world_weather::{Weather, WeatherType};
 use godot::classes::file_access::ModeFlags;
 use godot::classes::input::MouseMode;
-use godot::classes::{Engine, FileAccess, Input, WorldEnvironment};
+use godot::classes::light_3d::Param as LightParam;
+use godot::classes::{DirectionalLight3D, Engine, FileAccess, Input, WorldEnvironment};
 use godot::prelude::*;
 use network::messages::{ClientMessages, NetworkMessageType};
 use std::cell::RefCell;
@@ -33,6 +37,30 @@ pub const DEFAULT_THEME_PATH: &str = "res://assets/gui/default_theme.tres";
 
 pub type ResourceManagerType = Rc<RefCell<ResourceManager>>;
 
+// Direction of the sun path around the vertical axis
+const SUN_YAW: f32 = 0.3;
+
+fn get_sky_color(daylight: f32, weather: &Weather) -> Color {
+    let day = Color::from_rgb(0.484863, 0.694348, 0.996564);
+    let night = Color::from_rgb(0.02, 0.03, 0.08);
+
+    // Clouds make the sky darker and grey
+    let overcast = match weather.get_weather_type() {
+        WeatherType::Clear => 0.0,
+        WeatherType::Rain | WeatherType::Snow => 0.5 * weather.get_intensity(),
+        WeatherType::Storm => 0.8 * weather.get_intensity(),
+    };
+    let r = night.r + (day.r - night.r) * daylight;
+    let g = night.g + (day.g - night.g) * daylight;
+    let b = night.b + (day.b - night.b) * daylight;
+    let grey = (r + g + b) / 3.0 * (1.0 - overcast * 0.5);
+    Color::from_rgb(
+        r + (grey - r) * overcast,
+        g + (grey - g) * overcast,
+        b + (grey - b) * overcast,
+    )
+}
+
 #[derive(GodotClass)]
 #[class(init, tool, base=Node)]
 pub struct MainScene {
@@ -78,6 +106,12 @@ pub struct MainScene {
 
     #[export]
     worlde_environment: Option<Gd<WorldEnvironment>>,
+
+    #[export]
+    sun: Option<Gd<DirectionalLight3D>>,
+
+    weather_particles: Option<Gd<WeatherParticles>>,
+    border_wall: Option<Gd<WorldBorderWall>>,
 }
 
 impl MainScene {
@@ -102,6 +136,10 @@ impl MainScene {
         self.login.as_ref().unwrap()
     }
 
+    pub fn get_render_distance(&self) -> u16 {
+        self.game_settings.as_ref().unwrap().borrow().get_render_distance()
+    }
+
     pub fn get_text_screen_mut(&mut self) -> GdMut<'_, TextScreen> {
         self.text_screen.bind_mut()
     }
@@ -122,6 +160,62 @@ impl MainScene {
         self.worlds_manager.as_mut().unwrap().bind_mut()
     }
 
+    /// Sun angle, sky colour, fog and precipitation
+    /// follow the clock and the weather of the current world
+    fn update_sky(&mut self) {
+        // Keeps the scene lighting untouched in the editor
+        if Engine::singleton().is_editor_hint() {
+            return;
+        }
+        let (world_time, weather, player_position) = {
+            let wm = self.get_wm().bind();
+            let Some(world) = wm.get_world() else {
+                return;
+            };
+            let player_position = wm.get_player_controller().as_ref().map(|p| p.bind().get_position());
+            let w = world.bind();
+            (w.get_world_time().clone(), w.get_weather().clone(), player_position)
+        };
+        let daylight = world_time.get_daylight();
+
+        if let Some(sun) = self.sun.as_mut() {
+            sun.set_rotation(Vector3::new(-world_time.get_sun_angle(), SUN_YAW, 0.0));
+            sun.set_param(LightParam::ENERGY, daylight * (1.0 - weather.get_intensity() * 0.7));
+        }
+        if let Some(worlde_environment) = self.worlde_environment.as_mut() {
+            let mut environment = worlde_environment.get_environment().unwrap();
+            let sky_color = get_sky_color(daylight, &weather);
+            environment.set_bg_color(sky_color);
+            environment.set_ambient_light_energy(0.2 + daylight * 0.8);
+
+            let fog_density = match weather.get_weather_type() {
+                WeatherType::Clear => 0.0,
+                WeatherType::Rain | WeatherType::Storm => 0.02 * weather.get_intensity(),
+                WeatherType::Snow => 0.04 * weather.get_intensity(),
+            };
+            environment.set_fog_enabled(fog_density > 0.0);
+            environment.set_fog_density(fog_density);
+            environment.set_fog_light_color(sky_color);
+        }
+        if let Some(weather_particles) = self.weather_particles.as_mut() {
+            let mut weather_particles = weather_particles.bind_mut();
+            weather_particles.update_weather(&weather);
+            if let Some(position) = player_position {
+                weather_particles.follow(position);
+            }
+        }
+    }
+
+    /// Border of the current world; hidden if the world is infinite
+    fn update_border_wall(&mut self) {
+        let Some(border_wall) = self.border_wall.as_mut() else {
+            return;
+        };
+        let wm = self.worlds_manager.as_ref().unwrap().bind();
+        let border = wm.get_world().and_then(|w| w.bind().get_border().cloned());
+        border_wall.bind_mut().update_border(border.as_ref());
+    }
+
     fn connect_to_server(&mut self) {
         let ip = self.ip_port.as_ref().expect("init_data is not called");
 
@@ -269,6 +363,25 @@ impl MainScene {
                     log::info!(target: "main", "&aSetting FPS changed to &2{}", settings.fps);
                     return;
                 }
+                "render_distance" => {
+                    let value = match command.get_arg::<u16, _>("value") {
+                        Ok(c) => c,
+                        Err(e) => {
+                            log::error!(target: "main", "&cSetting value error: {}", e);
+                            return;
+                        }
+                    };
+                    settings.render_distance = Some(value);
+                    settings.save().unwrap();
+
+                    // The server clamps the distance by its own limit
+                    if let Some(network) = self.network.as_ref() {
+                        let message = ClientMessages::ChangeRenderDistance { render_distance: value };
+                        network.send_message(NetworkMessageType::ReliableOrdered, &message);
+                    }
+                    log::info!(target: "main", "&aSetting render distance changed to &2{}", value);
+                    return;
+                }
                 _ => {
                     log::error!(target: "main", "&cSetting type \"{}\" not found", setting_type.as_str());
                     return;
@@ -414,6 +527,14 @@ impl INode for MainScene {
             self.base_mut().add_child(&text_screen);
             self.text_screen.bind_mut().toggle(true);
 
+            let weather_particles = WeatherParticles::new_alloc();
+            self.base_mut().add_child(&weather_particles);
+            self.weather_particles = Some(weather_particles);
+
+            let border_wall = WorldBorderWall::new_alloc();
+            self.base_mut().add_child(&border_wall);
+            self.border_wall = Some(border_wall);
+
             Input::singleton().set_mouse_mode(MouseMode::CAPTURED);
 
             self.connect_to_server();
@@ -456,6 +577,9 @@ impl INode for MainScene {
             self.debug_info.bind_mut().update_debug(wm, network_info);
         }
 
+        self.update_sky();
+        self.update_border_wall();
+
         if !Engine::singleton().is_editor_hint() {
             let input = Input::singleton();
             if input.is_action_just_pressed(&ControllerActions::ToggleConsole.to_string()) {
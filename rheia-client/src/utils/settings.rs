@@ -4,6 +4,8 @@ use std::{
     path::PathBuf,
 };
 
+pub const DEFAULT_RENDER_DISTANCE: u16 = 12;
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
 pub struct GameSettings {
     pub ip_port_direct_connect: Option<String>,
@@ -14,6 +16,9 @@ pub struct GameSettings {
 
     #[serde(default)]
     pub fps: u16,
+
+    // Chunks render radius requested from the server
+    pub render_distance: Option<u16>,
 }
 
 impl GameSettings {
@@ -44,6 +49,10 @@ impl GameSettings {
         Ok(settings)
     }
 
+    pub fn get_render_distance(&self) -> u16 {
+        self.render_distance.unwrap_or(DEFAULT_RENDER_DISTANCE)
+    }
+
     pub fn save(&self) -> Result<(), String> {
         let mut path = match GameSettings::get_game_data_path() {
             Ok(p) => p,
@@ -1,5 +1,6 @@
 use common::blocks::block_type::{BlockContent, BlockType};
 use common::chunks::chunk_data::BlockIndexType;
+use common::chunks::light_propagation::LightBlocks;
 use common::default_blocks::generate_default_blocks;
 use std::collections::BTreeMap;
 
@@ -8,6 +9,7 @@ use crate::client_scripts::resource_manager::ResourceStorage;
 pub struct BlockStorage {
     blocks: BTreeMap<String, BlockType>,
     block_id_map: BTreeMap<BlockIndexType, String>,
+    light_blocks: LightBlocks,
 }
 
 impl Default for BlockStorage {
@@ -15,6 +17,7 @@ impl Default for BlockStorage {
         let mut block_storage = Self {
             blocks: Default::default(),
             block_id_map: Default::default(),
+            light_blocks: Default::default(),
         };
 
         let default_blocks = match generate_default_blocks() {
@@ -41,6 +44,22 @@ impl BlockStorage {
 
     pub fn set_block_id_map(&mut self, block_id_map: BTreeMap<BlockIndexType, String>) {
         self.block_id_map = block_id_map;
+        self.update_light_blocks();
+    }
+
+    /// Light properties of all blocks by their id
+    pub fn get_light_blocks(&self) -> &LightBlocks {
+        &self.light_blocks
+    }
+
+    fn update_light_blocks(&mut self) {
+        let mut light_blocks = LightBlocks::default();
+        for (block_id, block_slug) in self.block_id_map.iter() {
+            if let Some(block_type) = self.blocks.get(block_slug) {
+                light_blocks.add(*block_id, block_type);
+            }
+        }
+        self.light_blocks = light_blocks;
     }
 
     pub fn get_block_id(&self, slug: &String) -> Option<BlockIndexType> {
@@ -147,6 +166,7 @@ impl BlockStorage {
             }
             self.blocks.insert(block_type.get_slug().clone(), block_type.clone());
         }
+        self.update_light_blocks();
         return Ok(());
     }
 }
@@ -5,6 +5,7 @@ use common::{
     chunks::{
         block_position::ChunkBlockPosition,
         chunk_data::{BlockDataInfo, ChunkSectionData},
+        chunk_light::MAX_LIGHT,
     },
 };
 use ndshape::ConstShape;
@@ -13,7 +14,7 @@ use crate::world::block_storage::BlockStorage;
 
 use super::{
     chunk_column::ColumnDataLockType,
-    chunk_section::{ChunkBordersShape, ChunkColliderDataBordered},
+    chunk_section::{ChunkBordersShape, ChunkColliderDataBordered, ChunkLightBordered},
     near_chunk_data::NearChunksData,
 };
 
@@ -130,6 +131,69 @@ pub fn format_chunk_data_with_boundaries(
     return Ok((b_chunk, mesh_count));
 }
 
+/// Light levels of the section with one block boundary;
+/// corners of the boundary are left dark
+///
+/// Columns are locked one by one, because the light update locks them all for writing
+pub fn format_light_with_boundaries(
+    chunks_near: &NearChunksData,
+    chunk_data: &ColumnDataLockType,
+    y: usize,
+) -> ChunkLightBordered {
+    let mut b_light = [0; ChunkBordersShape::SIZE as usize];
+
+    let columns = [
+        Some(chunk_data),
+        chunks_near.forward.as_ref(),
+        chunks_near.behind.as_ref(),
+        chunks_near.left.as_ref(),
+        chunks_near.right.as_ref(),
+    ];
+    let size = CHUNK_SIZE as i32;
+    for (column_index, column) in columns.iter().enumerate() {
+        let Some(column) = column else {
+            continue;
+        };
+        let column = column.read();
+
+        for i in 0_u32..ChunkBordersShape::SIZE {
+            let [x, section_y, z] = ChunkBordersShape::delinearize(i).map(|v| v as i32 - 1);
+            let outside = [x, section_y, z].iter().filter(|v| **v < 0 || **v >= size).count();
+            if outside > 1 {
+                continue;
+            }
+
+            let index = match (x, z) {
+                (-1, _) => 1,
+                (x, _) if x == size => 2,
+                (_, -1) => 3,
+                (_, z) if z == size => 4,
+                _ => 0,
+            };
+            if index != column_index {
+                continue;
+            }
+
+            let section = y as i32 + section_y.div_euclid(size);
+            if section >= VERTICAL_SECTIONS as i32 {
+                // Above the world is the open sky
+                b_light[i as usize] = MAX_LIGHT;
+                continue;
+            }
+            if section < 0 {
+                continue;
+            }
+            let pos = ChunkBlockPosition::new(
+                x.rem_euclid(size) as u8,
+                section_y.rem_euclid(size) as u8,
+                z.rem_euclid(size) as u8,
+            );
+            b_light[i as usize] = column.get_light().get_level(section as u32, &pos);
+        }
+    }
+    b_light
+}
+
 type BondaryType<'a> = ArrayVec<(i8, i32, Option<Box<ChunkSectionData>>), 6>;
 
 fn get_boundaries_chunks<'a>(
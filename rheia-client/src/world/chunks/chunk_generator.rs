@@ -7,8 +7,10 @@ use crate::{
 };
 
 use super::{
-    chunk_data_formatter::format_chunk_data_with_boundaries, chunks_map::ChunkLock,
-    mesh::mesh_generator::generate_chunk_geometry, near_chunk_data::NearChunksData,
+    chunk_data_formatter::{format_chunk_data_with_boundaries, format_light_with_boundaries},
+    chunks_map::ChunkLock,
+    mesh::mesh_generator::generate_chunk_geometry,
+    near_chunk_data::NearChunksData,
 };
 use common::VERTICAL_SECTIONS;
 use flume::Sender;
@@ -40,8 +42,13 @@ pub(crate) fn generate_chunk(
                 format_chunk_data_with_boundaries(Some(&chunks_near), &data, &*block_storage.read(), y).unwrap();
 
             if mesh_count > 0 {
-                let geometry =
-                    generate_chunk_geometry(&texture_mapper.read(), &bordered_chunk_data, &block_storage.read());
+                let light = format_light_with_boundaries(&chunks_near, &data, y);
+                let geometry = generate_chunk_geometry(
+                    &texture_mapper.read(),
+                    &bordered_chunk_data,
+                    Some(&light),
+                    &block_storage.read(),
+                );
 
                 let mut chunk_section = chunk_column.read().get_chunk_section(&y);
                 chunk_section.bind_mut().set_new_geometry(geometry);
@@ -28,6 +28,9 @@ pub type ChunkBordersShape = ConstShape3u32<CHUNK_SIZE_BOUNDARY, CHUNK_SIZE_BOUN
 //pub type ChunkData = [BlockInfo; ChunkShape::SIZE as usize];
 pub type ChunkColliderDataBordered = [ChunkColliderInfo; ChunkBordersShape::SIZE as usize];
 
+/// Light levels of the blocks in the same layout as ChunkColliderDataBordered
+pub type ChunkLightBordered = [u8; ChunkBordersShape::SIZE as usize];
+
 /// One chunk section
 /// Contains mesh and data of the chunk section blocks
 #[derive(GodotClass)]
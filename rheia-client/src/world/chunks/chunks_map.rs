@@ -14,18 +14,19 @@ use common::{
         block_position::{BlockPosition, BlockPositionTrait},
         chunk_data::{BlockDataInfo, ChunkData},
         chunk_position::ChunkPosition,
+        light_propagation::{LightArea, LightBlocks},
     },
     CHUNK_SIZE, VERTICAL_SECTIONS,
 };
 use godot::prelude::*;
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockWriteGuard};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use super::{
     chunk_column::{ChunkColumn, ColumnDataLockType},
-    chunk_data_formatter::format_chunk_data_with_boundaries,
+    chunk_data_formatter::{format_chunk_data_with_boundaries, format_light_with_boundaries},
     chunk_generator::generate_chunk,
     mesh::mesh_generator::generate_chunk_geometry,
     near_chunk_data::NearChunksData,
@@ -92,7 +93,13 @@ impl ChunkMap {
     }
 
     /// Create chunk column and send it to render queue
-    pub fn create_chunk_column(&mut self, center: ChunkPosition, chunk_position: ChunkPosition, sections: ChunkData) {
+    pub fn create_chunk_column(
+        &mut self,
+        center: ChunkPosition,
+        chunk_position: ChunkPosition,
+        sections: ChunkData,
+        block_storage: &BlockStorage,
+    ) {
         if self.chunks.contains_key(&chunk_position) {
             log::error!(
                 target: "chunk_map",
@@ -102,10 +109,22 @@ impl ChunkMap {
             return;
         }
 
+        let light_computed = sections.get_light().is_computed();
         let chunk_column = ChunkColumn::create(chunk_position, sections);
         self.chunks
             .insert(chunk_position.clone(), Arc::new(RwLock::new(chunk_column)));
 
+        // Light of the neighbours is merged only on the client side;
+        // locally generated chunks come without the light at all
+        self.update_light(
+            &chunk_position,
+            block_storage.get_light_blocks(),
+            |area| match light_computed {
+                true => area.spread_borders(&chunk_position),
+                false => area.light_column(&chunk_position),
+            },
+        );
+
         if self.sended_chunks.borrow().contains(&chunk_position) {
             panic!("sended_chunks already have chunk");
         }
@@ -200,6 +219,27 @@ impl ChunkMap {
         }
     }
 
+    /// Returns changed blocks if the chunk was already received
+    ///
+    /// Data of the chunk which is not spawned yet is just replaced
+    pub fn get_resent_chunk_changes(
+        &self,
+        chunk_position: &ChunkPosition,
+        sections: &ChunkData,
+    ) -> Option<Vec<(BlockPosition, Option<BlockDataInfo>)>> {
+        let chunk_column = self.chunks.get(chunk_position)?.read();
+        if !chunk_column.is_loaded() {
+            *chunk_column.get_data_lock().write() = sections.clone();
+            return Some(Default::default());
+        }
+        let changes = chunk_column.get_data_lock().read().get_changes(sections);
+        let changes = changes
+            .into_iter()
+            .map(|(section, block, info)| (BlockPosition::from_chunk_position(chunk_position, &section, &block), info))
+            .collect();
+        Some(changes)
+    }
+
     pub fn unload_chunk(&mut self, chunk_position: ChunkPosition) {
         let mut unloaded = false;
         if let Some(chunk_column) = self.chunks.remove(&chunk_position) {
@@ -254,6 +294,11 @@ impl ChunkMap {
         chunk_column
             .write()
             .change_block_info(section, &block_position, new_block_info.clone());
+        self.update_light(
+            &position.get_chunk_position(),
+            block_storage.get_light_blocks(),
+            |area| area.update_block(&position),
+        );
 
         if let Some(new_block_info) = new_block_info {
             let Some(new_block_type) = block_storage.get(&new_block_info.get_id()) else {
@@ -296,6 +341,43 @@ impl ChunkMap {
         Ok(())
     }
 
+    /// Runs the light propagation over the chunk and the chunks around it
+    ///
+    /// Spawned sections with the changed light are sent to the update queue
+    fn update_light<F: FnOnce(&mut LightArea)>(
+        &self,
+        chunk_position: &ChunkPosition,
+        light_blocks: &LightBlocks,
+        f: F,
+    ) {
+        let mut columns: Vec<(ChunkPosition, ColumnDataLockType, bool)> = Default::default();
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let position = ChunkPosition::new(chunk_position.x + x, chunk_position.z + z);
+                if let Some(chunk_column) = self.chunks.get(&position) {
+                    let c = chunk_column.read();
+                    columns.push((position, c.get_data_lock().clone(), c.is_loaded()));
+                }
+            }
+        }
+
+        let mut data: Vec<RwLockWriteGuard<ChunkData>> = columns.iter().map(|(_, d, _)| d.write()).collect();
+        let mut area = LightArea::new(light_blocks);
+        for ((position, _, _), d) in columns.iter().zip(data.iter_mut()) {
+            area.add_column(position.clone(), &mut **d);
+        }
+        f(&mut area);
+
+        for (position, section) in area.get_changed().iter() {
+            let loaded = columns.iter().any(|(p, _, loaded)| p == position && *loaded);
+            if loaded {
+                self.chunks_to_update
+                    .borrow_mut()
+                    .insert((position.clone(), *section as usize));
+            }
+        }
+    }
+
     /// Sent to the update queue
     fn send_to_update_chunk_mesh(&self, position: &BlockPosition) {
         let (section, block_position) = position.get_block_position();
@@ -364,7 +446,8 @@ impl ChunkMap {
             let (bordered_chunk_data, _mesh_count) =
                 format_chunk_data_with_boundaries(Some(&chunks_near), &data, &block_storage, y.clone()).unwrap();
 
-            let geometry = generate_chunk_geometry(&texture_mapper, &bordered_chunk_data, &block_storage);
+            let light = format_light_with_boundaries(&chunks_near, &data, y.clone());
+            let geometry = generate_chunk_geometry(&texture_mapper, &bordered_chunk_data, Some(&light), &block_storage);
 
             let mut chunk_section = c.get_chunk_section(y);
             chunk_section.bind_mut().set_new_geometry(geometry);
@@ -3,13 +3,13 @@ use crate::{
     utils::{bridge::IntoNetworkVector, textures::texture_mapper::TextureMapper},
     world::{
         block_storage::BlockStorage,
-        chunks::chunk_section::{ChunkBordersShape, ChunkColliderDataBordered},
+        chunks::chunk_section::{ChunkBordersShape, ChunkColliderDataBordered, ChunkLightBordered},
     },
 };
 use common::{
-    CHUNK_SIZE,
+    CHUNK_SIZE, CHUNK_SIZE_BOUNDARY,
     blocks::{chunk_collider_info::ChunkColliderInfo, voxel_visibility::VoxelVisibility},
-    chunks::{chunk_data::BlockDataInfo, position::Vector3 as NetworkVector3},
+    chunks::{chunk_data::BlockDataInfo, chunk_light::MAX_LIGHT, position::Vector3 as NetworkVector3},
     utils::block_mesh::{
         QuadBuffer, RIGHT_HANDED_Y_UP_CONFIG, UnorientedQuad,
         buffer::UnitQuadBuffer,
@@ -23,7 +23,10 @@ use godot::{
         mesh::{ArrayType, PrimitiveType},
     },
     obj::{EngineEnum, NewGd},
-    prelude::{Array, Gd, PackedInt32Array, PackedVector2Array, PackedVector3Array, Variant, Vector2, Vector3},
+    prelude::{
+        Array, Color, Gd, PackedColorArray, PackedInt32Array, PackedVector2Array, PackedVector3Array, Variant, Vector2,
+        Vector3,
+    },
 };
 use ndshape::ConstShape;
 use physics::{PhysicsColliderBuilder, physics::IPhysicsColliderBuilder};
@@ -67,6 +70,48 @@ pub fn _generate_buffer_greedy(chunk_collider_data: &ChunkColliderDataBordered)
     buffer.quads
 }
 
+// Each light level is darker than the next one by this factor
+const LIGHT_FALLOFF: f32 = 0.8;
+
+/// Smooth light of the quad corner: the average of the blocks around the corner in front of the face
+fn get_corner_light(
+    chunk_collider_data: &ChunkColliderDataBordered,
+    light: &ChunkLightBordered,
+    block: [u32; 3],
+    normal: [i32; 3],
+    corner: [u32; 3],
+) -> f32 {
+    let front = block.map(|v| v as i32);
+    let front = [front[0] + normal[0], front[1] + normal[1], front[2] + normal[2]];
+
+    let (mut sum, mut count) = (0_u32, 0_u32);
+    for side in [[0, 0], [0, 1], [1, 0], [1, 1]] {
+        let mut tangent = side.iter();
+        let cell = [0, 1, 2].map(|axis| match normal[axis] {
+            0 => corner[axis] as i32 - 1 + tangent.next().unwrap(),
+            _ => front[axis],
+        });
+        if cell.iter().any(|v| *v < 0 || *v >= CHUNK_SIZE_BOUNDARY as i32) {
+            continue;
+        }
+        let index = ChunkBordersShape::linearize(cell.map(|v| v as u32)) as usize;
+        if *chunk_collider_data[index].get_voxel_visibility() == VoxelVisibility::Opaque {
+            continue;
+        }
+        sum += light[index] as u32;
+        count += 1;
+    }
+    if count == 0 {
+        return light[ChunkBordersShape::linearize(front.map(|v| v as u32)) as usize] as f32;
+    }
+    sum as f32 / count as f32
+}
+
+fn get_light_color(level: f32) -> Color {
+    let brightness = LIGHT_FALLOFF.powf(MAX_LIGHT as f32 - level);
+    Color::from_rgb(brightness, brightness, brightness)
+}
+
 pub struct Geometry {
     pub mesh_ist: Gd<ArrayMesh>,
     pub collider_builder: Option<PhysicsColliderBuilder>,
@@ -75,9 +120,11 @@ pub struct Geometry {
 unsafe impl Send for Geometry {}
 unsafe impl Sync for Geometry {}
 
+/// Light is baked into the vertex colors; without the light blocks are fully lit
 pub fn generate_chunk_geometry(
     texture_mapper: &TextureMapper,
     chunk_collider_data: &ChunkColliderDataBordered,
+    light: Option<&ChunkLightBordered>,
     block_storage: &BlockStorage,
 ) -> Geometry {
     // let chunk_collider_data = &_get_test_sphere(8.0, BlockInfo::create(1, None));
@@ -91,6 +138,7 @@ pub fn generate_chunk_geometry(
     let mut verts = PackedVector3Array::new();
     let mut normals = PackedVector3Array::new();
     let mut uvs = PackedVector2Array::new();
+    let mut colors = PackedColorArray::new();
 
     let steep = 0.03125;
     let uv_scale = Vector2::new(steep, steep);
@@ -112,22 +160,42 @@ pub fn generate_chunk_geometry(
             collider_indices.push([i[0] as u32, i[1] as u32, i[2] as u32]);
             collider_indices.push([i[3] as u32, i[4] as u32, i[5] as u32]);
 
+            let block_info = quad
+                .block_info
+                .expect("GENERATE_CHUNK_GEOMETRY block info is not found");
+
+            // Fluids with level have lowered top
+            let top_y = quad.minimum[1] + 1;
+            let height = block_info.get_height();
+
             let voxel_size = 1.0;
-            let v = face.quad_corners(&quad.clone().into(), true).map(|c| {
+            let corners = face.quad_corners(&quad.clone().into(), true);
+            let n = face.signed_normal();
+            for c in corners.iter() {
+                let level = match light {
+                    Some(light) => {
+                        get_corner_light(chunk_collider_data, light, quad.minimum, n.to_array(), c.to_array())
+                    }
+                    None => MAX_LIGHT as f32,
+                };
+                colors.push(get_light_color(level));
+            }
+
+            let v = corners.map(|c| {
+                let y = match c.y == top_y {
+                    true => c.y as f32 - 1.0 + height,
+                    false => c.y as f32,
+                };
                 // magic: Offset -1 because of chunk mesh one block boundary
-                let vert_pos = Vector3::new(c.x as f32, c.y as f32, c.z as f32) - Vector3::new(1.0, 1.0, 1.0);
+                let vert_pos = Vector3::new(c.x as f32, y, c.z as f32) - Vector3::new(1.0, 1.0, 1.0);
 
                 collider_verts.push(vert_pos.to_network());
                 vert_pos * voxel_size
             });
             verts.extend(v);
 
-            let n = face.signed_normal();
             normals.extend([Vector3::new(n.x as f32, n.y as f32, n.z as f32); 4]);
 
-            let block_info = quad
-                .block_info
-                .expect("GENERATE_CHUNK_GEOMETRY block info is not found");
             let block_type = block_storage
                 .get(&block_info.get_id())
                 .expect("GENERATE_CHUNK_GEOMETRY block type is not found");
@@ -151,6 +219,7 @@ pub fn generate_chunk_geometry(
     arrays.set(ArrayType::VERTEX.ord() as usize, &Variant::from(verts));
     arrays.set(ArrayType::NORMAL.ord() as usize, &Variant::from(normals));
     arrays.set(ArrayType::TEX_UV.ord() as usize, &Variant::from(uvs));
+    arrays.set(ArrayType::COLOR.ord() as usize, &Variant::from(colors));
 
     let mut collider_builder: Option<PhysicsColliderBuilder> = None;
 
@@ -13,6 +13,9 @@ use common::chunks::{
     chunk_data::{BlockDataInfo, ChunkData},
     chunk_position::ChunkPosition,
 };
+use common::world_border::WorldBorder;
+use common::world_time::WorldTime;
+use common::world_weather::Weather;
 use godot::{classes::Material, prelude::*};
 
 /// Godot world
@@ -37,6 +40,11 @@ pub struct WorldManager {
     texture_mapper: TextureMapperType,
     material: Gd<Material>,
     block_storage: BlockStorageType,
+
+    // Advanced locally between the server syncs
+    world_time: WorldTime,
+    weather: Weather,
+    border: Option<WorldBorder>,
 }
 
 impl WorldManager {
@@ -63,6 +71,9 @@ impl WorldManager {
             texture_mapper,
             material,
             block_storage,
+            world_time: Default::default(),
+            weather: Default::default(),
+            border: None,
         }
     }
 
@@ -82,6 +93,30 @@ impl WorldManager {
         &self.slug
     }
 
+    pub fn get_world_time(&self) -> &WorldTime {
+        &self.world_time
+    }
+
+    pub fn set_world_time(&mut self, world_time: WorldTime) {
+        self.world_time = world_time;
+    }
+
+    pub fn get_weather(&self) -> &Weather {
+        &self.weather
+    }
+
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.weather = weather;
+    }
+
+    pub fn get_border(&self) -> Option<&WorldBorder> {
+        self.border.as_ref()
+    }
+
+    pub fn set_border(&mut self, border: Option<WorldBorder>) {
+        self.border = border;
+    }
+
     pub fn get_chunks_count(&self) -> usize {
         self.chunk_map.bind().get_chunks_count()
     }
@@ -94,7 +129,7 @@ impl WorldManager {
     pub fn recieve_chunk(&mut self, center: ChunkPosition, chunk_position: ChunkPosition, data: ChunkData) {
         self.chunk_map
             .bind_mut()
-            .create_chunk_column(center, chunk_position, data);
+            .create_chunk_column(center, chunk_position, data, &self.block_storage.read());
     }
 
     /// Recieve chunk unloaded from network
@@ -134,12 +169,17 @@ impl WorldManager {
         }
     }
 
-    pub fn custom_process(&mut self, _delta: f64, resource_manager: &ResourceManager) {
+    pub fn custom_process(&mut self, delta: f64, resource_manager: &ResourceManager) {
         #[cfg(feature = "trace")]
         let _span = tracy_client::span!("world_manager");
 
         let now = std::time::Instant::now();
 
+        self.world_time.advance(std::time::Duration::from_secs_f64(delta));
+        if let Some(border) = self.border.as_mut() {
+            border.advance(std::time::Duration::from_secs_f64(delta));
+        }
+
         let mut map = self.chunk_map.bind_mut();
 
         let to_load_now = std::time::Instant::now();
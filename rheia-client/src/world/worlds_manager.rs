@@ -1,5 +1,6 @@
 use common::chunks::block_position::{BlockPosition, BlockPositionTrait};
 use godot::classes::StandardMaterial3D;
+use godot::classes::base_material_3d::Flags;
 use godot::prelude::*;
 use godot::{classes::Material, prelude::Gd};
 use parking_lot::RwLock;
@@ -53,6 +54,9 @@ impl WorldsManager {
             .terrain_material
             .as_mut()
             .expect("Terrain StandardMaterial3D is not set");
+
+        // Chunks light is baked into the vertex colors
+        material_3d.set_flag(Flags::ALBEDO_FROM_VERTEX_COLOR, true);
         match texture_mapper.build(&*block_storage, resources_storage, &mut material_3d) {
             Ok(i) => i,
             Err(e) => return Err(e),
@@ -171,15 +175,17 @@ impl WorldsManager {
         let mut base = self.base_mut().clone();
 
         let world_slug;
-        if let Some(world) = self.world.as_mut().take() {
+        if let Some(mut world) = self.world.take() {
             world_slug = world.bind().get_slug().clone();
             base.remove_child(&world.clone());
+            world.queue_free();
         } else {
             panic!("destroy_world: world is not exists");
         }
 
-        if let Some(player_controller) = self.player_controller.as_mut().take() {
+        if let Some(mut player_controller) = self.player_controller.take() {
             base.remove_child(&player_controller.clone());
+            player_controller.queue_free();
         }
         log::info!(target: "world", "World \"{}\" destroyed; (executed:{:.2?})", world_slug, now.elapsed());
     }
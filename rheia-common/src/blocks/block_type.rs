@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::voxel_visibility::VoxelVisibility;
+use crate::chunks::chunk_light::MAX_LIGHT;
 
 const REGEX_FILE_NAME: &str = r"^(?:.*\/)*([a-zA-Z_0-9]+)(\.[a-zA-Z]+)";
 
@@ -110,6 +111,18 @@ pub struct BlockTypeManifest {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub map_color: Option<BlockColor>,
+
+    #[serde(skip_serializing_if = "BlockType::is_default")]
+    #[serde(default)]
+    pub falls: bool,
+
+    #[serde(skip_serializing_if = "BlockType::is_default")]
+    #[serde(default)]
+    pub light: u8,
+
+    #[serde(skip_serializing_if = "BlockType::is_default")]
+    #[serde(default)]
+    pub fluid: bool,
 }
 
 impl BlockTypeManifest {
@@ -121,7 +134,10 @@ impl BlockTypeManifest {
         let mut b = BlockType::new(self.block_content.clone())
             .category(category)
             .visibility(self.voxel_visibility.clone())
-            .map_color(self.map_color.clone());
+            .map_color(self.map_color.clone())
+            .falls(self.falls)
+            .light(self.light)
+            .fluid(self.fluid);
         if let Some(slug) = self.slug.as_ref() {
             b = b.set_slug(slug.clone());
         }
@@ -141,6 +157,15 @@ pub struct BlockType {
     category: String,
 
     map_color: Option<BlockColor>,
+
+    // Affected by gravity when unsupported
+    falls: bool,
+
+    // Level of the light emitted by the block
+    light: u8,
+
+    // Entities and falling blocks pass through it
+    fluid: bool,
 }
 
 impl BlockType {
@@ -176,6 +201,9 @@ impl BlockType {
             block_content,
             category: BlockType::default_category(),
             map_color: None,
+            falls: false,
+            light: 0,
+            fluid: false,
         }
     }
 
@@ -188,6 +216,33 @@ impl BlockType {
         self.map_color.as_ref()
     }
 
+    pub fn falls(mut self, falls: bool) -> Self {
+        self.falls = falls;
+        self
+    }
+
+    pub fn can_fall(&self) -> bool {
+        self.falls
+    }
+
+    pub fn light(mut self, light: u8) -> Self {
+        self.light = light.min(MAX_LIGHT);
+        self
+    }
+
+    pub fn get_light(&self) -> u8 {
+        self.light
+    }
+
+    pub fn fluid(mut self, fluid: bool) -> Self {
+        self.fluid = fluid;
+        self
+    }
+
+    pub fn is_fluid(&self) -> bool {
+        self.fluid
+    }
+
     pub fn set_slug<S: Into<String>>(mut self, slug: S) -> Self {
         self.slug = slug.into();
         self
@@ -223,6 +278,14 @@ impl BlockType {
         &mut self.block_content
     }
 
+    /// Textured blocks are part of the chunk collider; models can be sensors
+    pub fn has_collider(&self) -> bool {
+        match &self.block_content {
+            BlockContent::Texture { .. } => true,
+            BlockContent::ModelCube { collider_type, .. } => !collider_type.is_sensor(),
+        }
+    }
+
     pub fn get_model(&self) -> Option<&String> {
         match &self.block_content {
             BlockContent::ModelCube { model, .. } => {
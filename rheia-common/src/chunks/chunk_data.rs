@@ -7,14 +7,21 @@ use std::{
 use zip::{CompressionMethod, DateTime};
 
 use super::block_position::{BlockPosition, ChunkBlockPosition};
+use super::chunk_light::ChunkLight;
 
 pub type BlockIndexType = u16;
 
-// Contains block id and rotation
+/// Level of the fluid right next to the source
+pub const FLUID_MAX_LEVEL: u8 = 7;
+
+// Contains block id, rotation and fluid level
 #[derive(Clone, Eq, Copy, Serialize, Deserialize)]
 pub struct BlockDataInfo {
     id: BlockIndexType,
     face: Option<BlockFace>,
+
+    // Level of the flowing fluid; None is a full block
+    level: Option<u8>,
 }
 
 impl std::fmt::Debug for BlockDataInfo {
@@ -23,19 +30,27 @@ impl std::fmt::Debug for BlockDataInfo {
             Some(f) => format!(" face:{:?}", f),
             None => "".to_string(),
         };
-        write!(f, "b#{}{}", self.id, face)
+        let level = match self.level {
+            Some(l) => format!(" level:{}", l),
+            None => "".to_string(),
+        };
+        write!(f, "b#{}{}{}", self.id, face, level)
     }
 }
 
 impl PartialEq for BlockDataInfo {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id && self.face == other.face
+        self.id == other.id && self.face == other.face && self.level == other.level
     }
 }
 
 impl BlockDataInfo {
     pub fn create(id: BlockIndexType, face: Option<BlockFace>) -> Self {
-        Self { id, face }
+        Self { id, face, level: None }
+    }
+
+    pub fn create_fluid(id: BlockIndexType, level: Option<u8>) -> Self {
+        Self { id, face: None, level }
     }
 
     pub fn get_id(&self) -> BlockIndexType {
@@ -49,6 +64,75 @@ impl BlockDataInfo {
     pub fn set_face(&mut self, face: Option<BlockFace>) {
         self.face = face;
     }
+
+    pub fn get_level(&self) -> Option<u8> {
+        self.level
+    }
+
+    /// Height of the block top from 0.0 to 1.0
+    pub fn get_height(&self) -> f32 {
+        match self.level {
+            Some(l) => l.min(FLUID_MAX_LEVEL) as f32 / (FLUID_MAX_LEVEL + 1) as f32,
+            None => 1.0,
+        }
+    }
+}
+
+/// Starts the versioned chunk format
+///
+/// Chunks of the original format start with the number of sections as u64,
+/// which is never that big
+const CHUNK_FORMAT_PREFIX: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Format with the fluid levels
+const CHUNK_FORMAT_VERSION: u8 = 2;
+
+// Format of the chunk data before fluid levels were added
+#[derive(Serialize, Deserialize)]
+struct LegacyBlockDataInfo {
+    id: BlockIndexType,
+    face: Option<BlockFace>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LegacyChunkSectionData {
+    data: HashMap<u16, LegacyBlockDataInfo>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LegacyChunkData {
+    data: Vec<Box<LegacyChunkSectionData>>,
+}
+
+impl From<&ChunkData> for LegacyChunkData {
+    fn from(chunk_data: &ChunkData) -> Self {
+        let mut data: Vec<Box<LegacyChunkSectionData>> = Default::default();
+        for section in chunk_data.data.iter() {
+            let mut legacy_section = LegacyChunkSectionData {
+                data: Default::default(),
+            };
+            for (index, b) in section.data.iter() {
+                let legacy_block = LegacyBlockDataInfo { id: b.id, face: b.face };
+                legacy_section.data.insert(*index, legacy_block);
+            }
+            data.push(Box::new(legacy_section));
+        }
+        Self { data }
+    }
+}
+
+impl From<LegacyChunkData> for ChunkData {
+    fn from(legacy: LegacyChunkData) -> Self {
+        let mut chunk_data = ChunkData::default();
+        for legacy_section in legacy.data {
+            let mut section = ChunkSectionData::default();
+            for (index, b) in legacy_section.data {
+                section.data.insert(index, BlockDataInfo::create(b.id, b.face));
+            }
+            chunk_data.data.push(Box::new(section));
+        }
+        chunk_data
+    }
 }
 
 // Contains all chunk block data
@@ -91,10 +175,18 @@ impl ChunkSectionData {
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct ChunkData {
     data: Vec<Box<ChunkSectionData>>,
+
+    // Computed after the loading, so it's not stored with the blocks
+    #[serde(skip)]
+    light: ChunkLight,
 }
 
 impl ChunkData {
     pub fn encode_zip(&self) -> Vec<u8> {
+        ChunkData::zip(&self.encode())
+    }
+
+    pub(crate) fn zip(data: &[u8]) -> Vec<u8> {
         let mut archive_data: Vec<u8> = Default::default();
 
         let buff = std::io::Cursor::new(&mut archive_data);
@@ -105,38 +197,70 @@ impl ChunkData {
             .last_modified_time(DateTime::default());
 
         writer.start_file("data", options).unwrap();
-        writer.write_all(&self.encode()).unwrap();
+        writer.write_all(data).unwrap();
         writer.finish().unwrap();
         archive_data
     }
 
+    /// Chunks without the fluid levels keep the original format
     pub fn encode(&self) -> Vec<u8> {
-        let encoded = bincode::serialize(&self).unwrap();
+        if !self.has_levels() {
+            return bincode::serialize(&LegacyChunkData::from(self)).unwrap();
+        }
+        let mut encoded = CHUNK_FORMAT_PREFIX.to_vec();
+        encoded.push(CHUNK_FORMAT_VERSION);
+        encoded.extend(bincode::serialize(&self).unwrap());
         encoded
     }
 
+    fn has_levels(&self) -> bool {
+        self.data
+            .iter()
+            .any(|section| section.data.values().any(|b| b.level.is_some()))
+    }
+
     pub fn decode_zip(data: Vec<u8>) -> Result<Self, String> {
+        ChunkData::decode(ChunkData::unzip(data)?)
+    }
+
+    pub(crate) fn unzip(data: Vec<u8>) -> Result<Vec<u8>, String> {
         let file = std::io::Cursor::new(&data);
-        let mut zip = zip::ZipArchive::new(file).unwrap();
+        let mut zip = match zip::ZipArchive::new(file) {
+            Ok(z) => z,
+            Err(e) => return Err(format!("Unzip error: &c{}", e)),
+        };
 
         let mut archive_file_data = Vec::new();
 
         for i in 0..zip.len() {
-            let archive_file = zip.by_index(i).unwrap();
-            for i in archive_file.bytes() {
-                archive_file_data.push(i.unwrap());
+            let mut archive_file = match zip.by_index(i) {
+                Ok(f) => f,
+                Err(e) => return Err(format!("Unzip error: &c{}", e)),
+            };
+            if let Err(e) = archive_file.read_to_end(&mut archive_file_data) {
+                return Err(format!("Unzip error: &c{}", e));
             }
             break;
         }
-        ChunkData::decode(archive_file_data)
+        Ok(archive_file_data)
     }
 
+    /// Decoder is picked by the format prefix
     pub fn decode(encoded: Vec<u8>) -> Result<Self, String> {
-        let chunk_data: Self = match bincode::deserialize(&encoded) {
-            Ok(d) => d,
-            Err(e) => return Err(format!("Decode chunk error: &c{} ", e)),
+        let Some(versioned) = encoded.strip_prefix(&CHUNK_FORMAT_PREFIX) else {
+            return match bincode::deserialize::<LegacyChunkData>(&encoded) {
+                Ok(legacy) => Ok(legacy.into()),
+                Err(e) => Err(format!("Decode chunk error: &c{} ", e)),
+            };
         };
-        Ok(chunk_data)
+        match versioned.split_first() {
+            Some((&CHUNK_FORMAT_VERSION, data)) => match bincode::deserialize(data) {
+                Ok(d) => Ok(d),
+                Err(e) => Err(format!("Decode chunk error: &c{} ", e)),
+            },
+            Some((version, _data)) => Err(format!("Decode chunk error: &cunknown format version {}", version)),
+            None => Err("Decode chunk error: &cformat version is missing".to_string()),
+        }
     }
 
     pub fn change_block(&mut self, section: u32, pos: &ChunkBlockPosition, block: Option<BlockDataInfo>) {
@@ -159,6 +283,40 @@ impl ChunkData {
         }
     }
 
+    /// Blocks of the other data which are different from this one
+    pub fn get_changes(&self, other: &ChunkData) -> Vec<(u32, ChunkBlockPosition, Option<BlockDataInfo>)> {
+        let mut changes: Vec<(u32, ChunkBlockPosition, Option<BlockDataInfo>)> = Default::default();
+        for (section, new_section) in other.data.iter().enumerate() {
+            let old_section = self.data.get(section);
+            for (index, block) in new_section.iter() {
+                if old_section.and_then(|s| s.data.get(index)) != Some(block) {
+                    changes.push((section as u32, ChunkBlockPosition::delinearize(*index), Some(*block)));
+                }
+            }
+            let Some(old_section) = old_section else {
+                continue;
+            };
+            for (index, _block) in old_section.iter() {
+                if !new_section.data.contains_key(index) {
+                    changes.push((section as u32, ChunkBlockPosition::delinearize(*index), None));
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn get_light(&self) -> &ChunkLight {
+        &self.light
+    }
+
+    pub fn get_light_mut(&mut self) -> &mut ChunkLight {
+        &mut self.light
+    }
+
+    pub fn set_light(&mut self, light: ChunkLight) {
+        self.light = light;
+    }
+
     pub fn push_section(&mut self, data: ChunkSectionData) {
         if self.data.len() >= VERTICAL_SECTIONS {
             panic!("Tried to insert sections more than max {VERTICAL_SECTIONS}");
@@ -170,7 +328,12 @@ impl ChunkData {
 #[cfg(test)]
 mod tests {
     use crate::{
-        chunks::{chunk_data::ChunkData, chunk_position::ChunkPosition},
+        blocks::block_info::BlockFace,
+        chunks::{
+            block_position::ChunkBlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+            chunk_position::ChunkPosition,
+        },
         world_generator::{
             default::{WorldGenerator, WorldGeneratorSettings},
             traits::IWorldGenerator,
@@ -196,4 +359,64 @@ mod tests {
             decoded_chunk_data.get(0).unwrap().len()
         );
     }
+
+    #[test]
+    fn test_chunk_changes() {
+        let (removed, added) = (ChunkBlockPosition::new(1, 2, 3), ChunkBlockPosition::new(4, 5, 6));
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(Default::default());
+        chunk_data.change_block(0, &removed, Some(BlockDataInfo::create(1, None)));
+        assert_eq!(chunk_data.get_changes(&chunk_data).len(), 0);
+
+        let mut changed = chunk_data.clone();
+        changed.change_block(0, &removed, None);
+        changed.change_block(0, &added, Some(BlockDataInfo::create(3, None)));
+
+        let changes = chunk_data.get_changes(&changed);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&(0, removed, None)));
+        assert!(changes.contains(&(0, added, Some(BlockDataInfo::create(3, None)))));
+    }
+
+    #[test]
+    fn test_legacy_chunk_data() {
+        // Chunk saved before fluid levels: one section with the block #5 facing east at 0
+        let encoded: Vec<u8> = vec![
+            1, 0, 0, 0, 0, 0, 0, 0, // sections
+            1, 0, 0, 0, 0, 0, 0, 0, // blocks of the section
+            0, 0, // block index
+            5, 0, // block id
+            1, 0, 0, 0, 0, // face: Some(East)
+        ];
+        let chunk_data = ChunkData::decode(encoded.clone()).unwrap();
+        let section = chunk_data.get(0).unwrap();
+        assert_eq!(section.len(), 1);
+        let pos = ChunkBlockPosition::new(0, 0, 0);
+        assert_eq!(
+            *section.get(&pos).unwrap(),
+            BlockDataInfo::create(5, Some(BlockFace::East))
+        );
+
+        // Chunk without levels is saved in the same format
+        assert_eq!(chunk_data.encode(), encoded);
+    }
+
+    #[test]
+    fn test_fluid_levels_chunk_data() {
+        let pos = ChunkBlockPosition::new(1, 2, 3);
+        let mut section = ChunkSectionData::default();
+        section.insert(&pos, BlockDataInfo::create_fluid(4, Some(3)));
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(section);
+
+        let encoded = chunk_data.encode();
+        assert_eq!(encoded[..5], [0xFF, 0xFF, 0xFF, 0xFF, 2]);
+
+        let decoded = ChunkData::decode(encoded.clone()).unwrap();
+        assert_eq!(decoded.get(0).unwrap().get(&pos).unwrap().get_level(), Some(3));
+
+        let mut unknown = encoded;
+        unknown[4] = 99;
+        assert!(ChunkData::decode(unknown).is_err());
+    }
 }
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use super::{block_position::ChunkBlockPosition, chunk_data::ChunkData};
+use crate::{CHUNK_SIZE, VERTICAL_SECTIONS};
+
+pub const MAX_LIGHT: u8 = 15;
+
+const SECTION_VOLUME: usize = CHUNK_SIZE as usize * CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LightChannel {
+    /// Light coming from above the world
+    Sky,
+    /// Light of the emissive blocks
+    Block,
+}
+
+/// Light of every block of the chunk column
+///
+/// Each byte keeps the sky light in the high half and the block light in the low one
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChunkLight {
+    sections: Vec<Vec<u8>>,
+}
+
+impl ChunkLight {
+    /// Light of all sections set to zero
+    pub fn dark() -> Self {
+        Self {
+            sections: vec![vec![0; SECTION_VOLUME]; VERTICAL_SECTIONS],
+        }
+    }
+
+    /// Returns false if the light was never computed
+    pub fn is_computed(&self) -> bool {
+        self.sections.len() == VERTICAL_SECTIONS
+    }
+
+    pub fn get(&self, channel: LightChannel, section: u32, pos: &ChunkBlockPosition) -> u8 {
+        let Some(section) = self.sections.get(section as usize) else {
+            return 0;
+        };
+        let value = section[pos.linearize() as usize];
+        match channel {
+            LightChannel::Sky => value >> 4,
+            LightChannel::Block => value & 0x0F,
+        }
+    }
+
+    pub fn set(&mut self, channel: LightChannel, section: u32, pos: &ChunkBlockPosition, level: u8) {
+        if !self.is_computed() {
+            *self = ChunkLight::dark();
+        }
+        let value = &mut self.sections[section as usize][pos.linearize() as usize];
+        let level = level.min(MAX_LIGHT);
+        *value = match channel {
+            LightChannel::Sky => (*value & 0x0F) | (level << 4),
+            LightChannel::Block => (*value & 0xF0) | level,
+        };
+    }
+
+    /// The brightest of the sky and the block light
+    pub fn get_level(&self, section: u32, pos: &ChunkBlockPosition) -> u8 {
+        self.get(LightChannel::Sky, section, pos)
+            .max(self.get(LightChannel::Block, section, pos))
+    }
+
+    pub fn encode_zip(&self) -> Vec<u8> {
+        ChunkData::zip(&bincode::serialize(&self).unwrap())
+    }
+
+    pub fn decode_zip(data: Vec<u8>) -> Result<Self, String> {
+        let encoded = ChunkData::unzip(data)?;
+        let light: Self = match bincode::deserialize(&encoded) {
+            Ok(l) => l,
+            Err(e) => return Err(format!("Decode chunk light error: &c{} ", e)),
+        };
+        if !light.sections.is_empty()
+            && (!light.is_computed() || light.sections.iter().any(|s| s.len() != SECTION_VOLUME))
+        {
+            return Err("Decode chunk light error: &cwrong size".to_string());
+        }
+        Ok(light)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkLight, LightChannel};
+    use crate::chunks::block_position::ChunkBlockPosition;
+
+    #[test]
+    fn test_chunk_light() {
+        let pos = ChunkBlockPosition::new(1, 2, 3);
+        let mut light = ChunkLight::default();
+        assert_eq!(light.get(LightChannel::Sky, 4, &pos), 0);
+
+        light.set(LightChannel::Sky, 4, &pos, 15);
+        light.set(LightChannel::Block, 4, &pos, 7);
+        assert_eq!(light.get(LightChannel::Sky, 4, &pos), 15);
+        assert_eq!(light.get(LightChannel::Block, 4, &pos), 7);
+
+        light.set(LightChannel::Sky, 4, &pos, 3);
+        assert_eq!(light.get_level(4, &pos), 7);
+
+        let decoded = ChunkLight::decode_zip(light.encode_zip()).unwrap();
+        assert!(decoded == light);
+    }
+
+    #[test]
+    fn test_chunk_light_corrupted() {
+        let mut light = ChunkLight::default();
+        light.set(LightChannel::Sky, 0, &ChunkBlockPosition::new(1, 2, 3), 15);
+        let mut encoded = light.encode_zip();
+        encoded.truncate(encoded.len() / 2);
+        assert!(ChunkLight::decode_zip(encoded).is_err());
+        assert!(ChunkLight::decode_zip(vec![1, 2, 3]).is_err());
+    }
+}
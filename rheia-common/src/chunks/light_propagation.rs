@@ -0,0 +1,482 @@
+use ahash::{AHashMap, AHashSet};
+use std::collections::VecDeque;
+
+use super::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::{BlockIndexType, ChunkData},
+    chunk_light::{ChunkLight, LightChannel, MAX_LIGHT},
+    chunk_position::ChunkPosition,
+};
+use crate::{
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+    blocks::{block_type::BlockType, voxel_visibility::VoxelVisibility},
+};
+
+const WORLD_HEIGHT: i64 = CHUNK_SIZE as i64 * VERTICAL_SECTIONS as i64;
+
+const NEIGHBORS: [(i64, i64, i64); 6] = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+/// How the block passes and emits the light
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BlockLight {
+    opaque: bool,
+    // Additional decrease of the light passing through
+    filter: u8,
+    emission: u8,
+}
+
+impl BlockLight {
+    const AIR: Self = Self {
+        opaque: false,
+        filter: 0,
+        emission: 0,
+    };
+    const SOLID: Self = Self {
+        opaque: true,
+        filter: 0,
+        emission: 0,
+    };
+
+    /// Level of the light coming into this block from the neighbour
+    fn get_passed_level(&self, channel: LightChannel, level: u8, downward: bool) -> u8 {
+        if self.opaque {
+            return 0;
+        }
+        // Sky light falls down through the air without fading
+        if channel == LightChannel::Sky && downward && level == MAX_LIGHT && self.filter == 0 {
+            return MAX_LIGHT;
+        }
+        level.saturating_sub(1 + self.filter)
+    }
+}
+
+/// Light properties of the block types by their id
+#[derive(Default, Clone)]
+pub struct LightBlocks {
+    blocks: AHashMap<BlockIndexType, BlockLight>,
+}
+
+impl LightBlocks {
+    /// Only textured opaque blocks stop the light; the rest dim it
+    pub fn add(&mut self, block_id: BlockIndexType, block_type: &BlockType) {
+        let visibility = block_type.get_voxel_visibility();
+        let light = BlockLight {
+            opaque: block_type.get_block_content().is_texture() && *visibility == VoxelVisibility::Opaque,
+            filter: (*visibility != VoxelVisibility::Empty) as u8,
+            emission: block_type.get_light(),
+        };
+        self.blocks.insert(block_id, light);
+    }
+
+    /// Unknown blocks are opaque
+    fn get(&self, block_id: Option<BlockIndexType>) -> BlockLight {
+        match block_id {
+            Some(id) => self.blocks.get(&id).cloned().unwrap_or(BlockLight::SOLID),
+            None => BlockLight::AIR,
+        }
+    }
+}
+
+/// Loaded chunk columns used for the light propagation
+///
+/// Light spreads no further than MAX_LIGHT blocks, so the columns
+/// around the changed one are enough; the rest are treated as unloaded
+pub struct LightArea<'a> {
+    blocks: &'a LightBlocks,
+    columns: AHashMap<ChunkPosition, &'a mut ChunkData>,
+
+    // Sections which mesh depends on the changed light
+    changed: AHashSet<(ChunkPosition, u32)>,
+}
+
+impl<'a> LightArea<'a> {
+    pub fn new(blocks: &'a LightBlocks) -> Self {
+        Self {
+            blocks,
+            columns: Default::default(),
+            changed: Default::default(),
+        }
+    }
+
+    pub fn add_column(&mut self, chunk_position: ChunkPosition, chunk_data: &'a mut ChunkData) {
+        self.columns.insert(chunk_position, chunk_data);
+    }
+
+    /// Chunk sections which need to be rebuilt because of the light change
+    pub fn get_changed(&self) -> &AHashSet<(ChunkPosition, u32)> {
+        &self.changed
+    }
+
+    /// Returns None if the block is outside of the area or the world height
+    fn get_block(&self, position: &BlockPosition) -> Option<BlockLight> {
+        if position.get_y() < 0 || position.get_y() >= WORLD_HEIGHT {
+            return None;
+        }
+        let column = self.columns.get(&position.get_chunk_position())?;
+        let (section, block_position) = position.get_block_position();
+        let block_id = column
+            .get(section as usize)
+            .and_then(|s| s.get(&block_position))
+            .map(|b| b.get_id());
+        Some(self.blocks.get(block_id))
+    }
+
+    fn get_light(&self, channel: LightChannel, position: &BlockPosition) -> u8 {
+        if position.get_y() < 0 || position.get_y() >= WORLD_HEIGHT {
+            return 0;
+        }
+        let Some(column) = self.columns.get(&position.get_chunk_position()) else {
+            return 0;
+        };
+        let (section, block_position) = position.get_block_position();
+        column.get_light().get(channel, section, &block_position)
+    }
+
+    fn set_light(&mut self, channel: LightChannel, position: &BlockPosition, level: u8) {
+        let chunk_position = position.get_chunk_position();
+        let Some(column) = self.columns.get_mut(&chunk_position) else {
+            return;
+        };
+        let (section, block_position) = position.get_block_position();
+        column.get_light_mut().set(channel, section, &block_position, level);
+
+        // Faces of the neighbour blocks may belong to the other sections
+        let edge = |v: u8| -> &'static [i64] {
+            match v {
+                0 => &[0, -1],
+                v if v == CHUNK_SIZE - 1 => &[0, 1],
+                _ => &[0],
+            }
+        };
+        for dx in edge(block_position.x) {
+            for dz in edge(block_position.z) {
+                for dy in edge(block_position.y) {
+                    let section = section as i64 + dy;
+                    if section < 0 || section >= VERTICAL_SECTIONS as i64 {
+                        continue;
+                    }
+                    let chunk = ChunkPosition::new(chunk_position.x + dx, chunk_position.z + dz);
+                    self.changed.insert((chunk, section as u32));
+                }
+            }
+        }
+    }
+
+    /// Light which the block has regardless of the neighbours
+    fn get_own_light(&self, channel: LightChannel, position: &BlockPosition, block: &BlockLight) -> u8 {
+        match channel {
+            LightChannel::Block => block.emission,
+            LightChannel::Sky if position.get_y() == WORLD_HEIGHT - 1 => {
+                block.get_passed_level(channel, MAX_LIGHT, true)
+            }
+            LightChannel::Sky => 0,
+        }
+    }
+
+    /// Computes the light of the loaded column from scratch
+    /// and spreads it into the neighbour columns of the area
+    ///
+    /// Neighbours must not contain the light of this column computed before
+    pub fn light_column(&mut self, chunk_position: &ChunkPosition) {
+        let Some(column) = self.columns.get_mut(chunk_position) else {
+            return;
+        };
+        column.set_light(ChunkLight::dark());
+
+        let mut sky: VecDeque<BlockPosition> = Default::default();
+        let mut emitted: VecDeque<BlockPosition> = Default::default();
+
+        let size = CHUNK_SIZE as i64;
+        for x in 0..size {
+            for z in 0..size {
+                // Sky light falls straight down until it meets the opaque block
+                let mut level = MAX_LIGHT;
+                for y in (0..WORLD_HEIGHT).rev() {
+                    let position = BlockPosition::new(chunk_position.x * size + x, y, chunk_position.z * size + z);
+                    let block = self.get_block(&position).unwrap_or(BlockLight::SOLID);
+                    if block.emission > 0 {
+                        self.set_light(LightChannel::Block, &position, block.emission);
+                        emitted.push_back(position.clone());
+                    }
+                    level = block.get_passed_level(LightChannel::Sky, level, true);
+                    if level > 0 {
+                        self.set_light(LightChannel::Sky, &position, level);
+                        sky.push_back(position);
+                    }
+                }
+            }
+        }
+        self.seed_borders(chunk_position, &mut sky, &mut emitted);
+        self.spread(LightChannel::Sky, &mut sky);
+        self.spread(LightChannel::Block, &mut emitted);
+    }
+
+    /// Exchanges the light between the column and its loaded neighbours
+    pub fn spread_borders(&mut self, chunk_position: &ChunkPosition) {
+        let mut sky: VecDeque<BlockPosition> = Default::default();
+        let mut emitted: VecDeque<BlockPosition> = Default::default();
+        self.seed_borders(chunk_position, &mut sky, &mut emitted);
+        self.spread(LightChannel::Sky, &mut sky);
+        self.spread(LightChannel::Block, &mut emitted);
+    }
+
+    /// Updates the light around the block, which is already changed
+    pub fn update_block(&mut self, position: &BlockPosition) {
+        let Some(block) = self.get_block(position) else {
+            return;
+        };
+        for channel in [LightChannel::Sky, LightChannel::Block] {
+            let mut removal: VecDeque<(BlockPosition, u8)> = Default::default();
+            let mut queue: VecDeque<BlockPosition> = Default::default();
+
+            let old_level = self.get_light(channel, position);
+            if old_level > 0 {
+                self.set_light(channel, position, 0);
+                removal.push_back((position.clone(), old_level));
+            }
+            self.unspread(channel, &mut removal, &mut queue);
+
+            let own_level = self.get_own_light(channel, position, &block);
+            if own_level > self.get_light(channel, position) {
+                self.set_light(channel, position, own_level);
+                queue.push_back(position.clone());
+            }
+
+            // Neighbours fill the block again
+            for (x, y, z) in NEIGHBORS {
+                let neighbor = position.offset(x, y, z);
+                if self.get_light(channel, &neighbor) > 0 {
+                    queue.push_back(neighbor);
+                }
+            }
+            self.spread(channel, &mut queue);
+        }
+    }
+
+    /// Pushes the edge blocks of the column and the facing edges of its neighbours
+    fn seed_borders(
+        &self,
+        chunk_position: &ChunkPosition,
+        sky: &mut VecDeque<BlockPosition>,
+        emitted: &mut VecDeque<BlockPosition>,
+    ) {
+        let size = CHUNK_SIZE as i64;
+        let (min_x, min_z) = (chunk_position.x * size, chunk_position.z * size);
+        for i in 0..size {
+            let edges = [
+                (min_x - 1, min_z + i),
+                (min_x, min_z + i),
+                (min_x + size - 1, min_z + i),
+                (min_x + size, min_z + i),
+                (min_x + i, min_z - 1),
+                (min_x + i, min_z),
+                (min_x + i, min_z + size - 1),
+                (min_x + i, min_z + size),
+            ];
+            for (x, z) in edges {
+                for y in 0..WORLD_HEIGHT {
+                    let position = BlockPosition::new(x, y, z);
+                    if self.get_light(LightChannel::Sky, &position) > 1 {
+                        sky.push_back(position.clone());
+                    }
+                    if self.get_light(LightChannel::Block, &position) > 1 {
+                        emitted.push_back(position);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flood fill from the lit blocks of the queue
+    fn spread(&mut self, channel: LightChannel, queue: &mut VecDeque<BlockPosition>) {
+        while let Some(position) = queue.pop_front() {
+            let level = self.get_light(channel, &position);
+            if level == 0 {
+                continue;
+            }
+            for (x, y, z) in NEIGHBORS {
+                let neighbor = position.offset(x, y, z);
+                let Some(block) = self.get_block(&neighbor) else {
+                    continue;
+                };
+                let passed = block.get_passed_level(channel, level, y == -1);
+                if passed > self.get_light(channel, &neighbor) {
+                    self.set_light(channel, &neighbor, passed);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    /// Removes the light which came from the removed blocks;
+    /// the blocks lit by the other sources are pushed to the queue to fill the gap
+    fn unspread(
+        &mut self,
+        channel: LightChannel,
+        removal: &mut VecDeque<(BlockPosition, u8)>,
+        queue: &mut VecDeque<BlockPosition>,
+    ) {
+        let mut removed: Vec<(BlockPosition, BlockLight)> = Default::default();
+        while let Some((position, level)) = removal.pop_front() {
+            for (x, y, z) in NEIGHBORS {
+                let neighbor = position.offset(x, y, z);
+                let Some(block) = self.get_block(&neighbor) else {
+                    continue;
+                };
+                let neighbor_level = self.get_light(channel, &neighbor);
+                if neighbor_level == 0 {
+                    continue;
+                }
+                let falling_sky =
+                    channel == LightChannel::Sky && y == -1 && level == MAX_LIGHT && neighbor_level == MAX_LIGHT;
+                if neighbor_level < level || falling_sky {
+                    self.set_light(channel, &neighbor, 0);
+                    removal.push_back((neighbor.clone(), neighbor_level));
+                    removed.push((neighbor, block));
+                } else {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        // Emissive blocks keep their own light
+        for (position, block) in removed {
+            let own_level = self.get_own_light(channel, &position, &block);
+            if own_level > self.get_light(channel, &position) {
+                self.set_light(channel, &position, own_level);
+                queue.push_back(position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LightArea, LightBlocks};
+    use crate::{
+        VERTICAL_SECTIONS,
+        blocks::block_type::{BlockContent, BlockType},
+        chunks::{
+            block_position::BlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData},
+            chunk_light::LightChannel,
+            chunk_position::ChunkPosition,
+        },
+    };
+
+    const STONE: u16 = 1;
+    const LAMP: u16 = 2;
+
+    fn create_blocks() -> LightBlocks {
+        let mut blocks = LightBlocks::default();
+        blocks.add(STONE, &BlockType::new(BlockContent::single("stone.png")));
+        blocks.add(LAMP, &BlockType::new(BlockContent::single("lamp.png")).light(10));
+        blocks
+    }
+
+    fn create_column() -> ChunkData {
+        let mut chunk_data = ChunkData::default();
+        for _ in 0..VERTICAL_SECTIONS {
+            chunk_data.push_section(Default::default());
+        }
+        chunk_data
+    }
+
+    fn set_block(chunk_data: &mut ChunkData, position: &BlockPosition, block_id: Option<u16>) {
+        let (section, block_position) = position.get_block_position();
+        let block_info = block_id.map(|id| BlockDataInfo::create(id, None));
+        chunk_data.change_block(section, &block_position, block_info);
+    }
+
+    /// Lights both columns separately and exchanges the light between them
+    fn light_from_scratch(blocks: &LightBlocks, center: &mut ChunkData, side: &mut ChunkData) {
+        let (center_position, side_position) = (ChunkPosition::new(0, 0), ChunkPosition::new(-1, 0));
+        LightArea::new(blocks).light_column_alone(center_position, center);
+        LightArea::new(blocks).light_column_alone(side_position, side);
+
+        let mut area = LightArea::new(blocks);
+        area.add_column(center_position, center);
+        area.add_column(side_position, side);
+        area.spread_borders(&center_position);
+    }
+
+    impl<'a> LightArea<'a> {
+        fn light_column_alone(mut self, chunk_position: ChunkPosition, chunk_data: &'a mut ChunkData) {
+            self.add_column(chunk_position, chunk_data);
+            self.light_column(&chunk_position);
+        }
+    }
+
+    #[test]
+    fn test_sky_light() {
+        let blocks = create_blocks();
+        let (mut center, mut side) = (create_column(), create_column());
+
+        // The center column is covered by the roof
+        for x in 0..16 {
+            for z in 0..16 {
+                set_block(&mut center, &BlockPosition::new(x, 100, z), Some(STONE));
+            }
+        }
+        light_from_scratch(&blocks, &mut center, &mut side);
+
+        let (section, block) = BlockPosition::new(8, 101, 8).get_block_position();
+        assert_eq!(center.get_light().get(LightChannel::Sky, section, &block), 15);
+
+        // Sky light comes under the roof only from the side column
+        for (x, level) in [(0, 14), (5, 9), (8, 6), (15, 0)] {
+            let (section, block) = BlockPosition::new(x, 50, 8).get_block_position();
+            assert_eq!(center.get_light().get(LightChannel::Sky, section, &block), level);
+        }
+    }
+
+    #[test]
+    fn test_light_update() {
+        let blocks = create_blocks();
+        let (mut center, mut side) = (create_column(), create_column());
+        for x in 0..16 {
+            for z in 0..16 {
+                set_block(&mut center, &BlockPosition::new(x, 100, z), Some(STONE));
+            }
+        }
+        light_from_scratch(&blocks, &mut center, &mut side);
+
+        let changes = [
+            (BlockPosition::new(8, 100, 8), None),
+            (BlockPosition::new(6, 50, 8), Some(LAMP)),
+            (BlockPosition::new(0, 100, 3), None),
+            (BlockPosition::new(-1, 60, 3), Some(STONE)),
+            (BlockPosition::new(8, 100, 8), Some(STONE)),
+            (BlockPosition::new(7, 50, 8), Some(LAMP)),
+            (BlockPosition::new(6, 50, 8), None),
+        ];
+        for (position, block_id) in changes {
+            let chunk_position = ChunkPosition::new(position.get_x().div_euclid(16), 0);
+            match chunk_position.x {
+                0 => set_block(&mut center, &position, block_id),
+                _ => set_block(&mut side, &position, block_id),
+            }
+            let mut area = LightArea::new(&blocks);
+            area.add_column(ChunkPosition::new(0, 0), &mut center);
+            area.add_column(ChunkPosition::new(-1, 0), &mut side);
+            area.update_block(&position);
+            assert!(
+                area.get_changed()
+                    .contains(&(chunk_position, position.get_y() as u32 / 16))
+            );
+
+            // Updated light is the same as the computed one
+            let (mut expected_center, mut expected_side) = (center.clone(), side.clone());
+            light_from_scratch(&blocks, &mut expected_center, &mut expected_side);
+            assert!(
+                center.get_light() == expected_center.get_light(),
+                "change {:?}",
+                position
+            );
+            assert!(side.get_light() == expected_side.get_light(), "change {:?}", position);
+        }
+
+        let (section, block) = BlockPosition::new(9, 50, 8).get_block_position();
+        assert_eq!(center.get_light().get(LightChannel::Block, section, &block), 8);
+    }
+}
@@ -1,5 +1,9 @@
 pub mod block_position;
 pub mod chunk_data;
+pub mod chunk_light;
 pub mod chunk_position;
+pub mod light_propagation;
 pub mod position;
 pub mod rotation;
+pub mod scheduled_tick;
+pub mod stored_entity;
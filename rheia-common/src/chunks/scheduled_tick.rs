@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::block_position::BlockPosition;
+
+/// Block update which must be executed after the delay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledTick {
+    position: BlockPosition,
+
+    // Ticks left until the update
+    delay: u32,
+}
+
+impl ScheduledTick {
+    pub fn create(position: BlockPosition, delay: u32) -> Self {
+        Self { position, delay }
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_delay(&self) -> u32 {
+        self.delay
+    }
+
+    pub fn set_delay(&mut self, delay: u32) {
+        self.delay = delay;
+    }
+
+    /// Returns true if the update is due
+    pub fn tick(&mut self) -> bool {
+        self.delay = self.delay.saturating_sub(1);
+        self.delay == 0
+    }
+
+    pub fn encode(ticks: &Vec<ScheduledTick>) -> Vec<u8> {
+        bincode::serialize(ticks).unwrap()
+    }
+
+    pub fn decode(encoded: Vec<u8>) -> Result<Vec<ScheduledTick>, String> {
+        match bincode::deserialize(&encoded) {
+            Ok(d) => Ok(d),
+            Err(e) => Err(format!("Decode scheduled ticks error: &c{}", e)),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use super::{position::Vector3, rotation::Rotation};
+
+/// Server-owned entity which is saved together with the chunk it is in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredEntity {
+    // Unique inside the world and kept between the restarts
+    id: u64,
+    position: Vector3,
+    rotation: Rotation,
+
+    // Components are encoded by the server
+    components: Vec<u8>,
+}
+
+impl StoredEntity {
+    pub fn create(id: u64, position: Vector3, rotation: Rotation, components: Vec<u8>) -> Self {
+        Self {
+            id,
+            position,
+            rotation,
+            components,
+        }
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn get_position(&self) -> &Vector3 {
+        &self.position
+    }
+
+    pub fn get_rotation(&self) -> &Rotation {
+        &self.rotation
+    }
+
+    pub fn get_components(&self) -> &Vec<u8> {
+        &self.components
+    }
+
+    pub fn encode(entities: &Vec<StoredEntity>) -> Vec<u8> {
+        bincode::serialize(entities).unwrap()
+    }
+
+    pub fn decode(encoded: Vec<u8>) -> Result<Vec<StoredEntity>, String> {
+        match bincode::deserialize(&encoded) {
+            Ok(d) => Ok(d),
+            Err(e) => Err(format!("Decode stored entities error: &c{}", e)),
+        }
+    }
+}
@@ -18,9 +18,12 @@ const DEFAULT_BLOCKS_YML: &str = r#"
   block_content: !texture
     texture: default://assets/block/water_overlay.png
   voxel_visibility: translucent
+  map_color: [63, 118, 228]
+  fluid: true
 
 - block_content: !texture
     texture: default://assets/block/stone.png
+  map_color: [125, 125, 125]
 - block_content: !texture
     texture: default://assets/block/smooth_stone.png
 - block_content: !texture
@@ -32,12 +35,16 @@ const DEFAULT_BLOCKS_YML: &str = r#"
 
 - block_content: !texture
     texture: default://assets/block/gravel.png
+  map_color: [136, 126, 126]
+  falls: true
 - block_content: !texture
     texture: default://assets/block/coarse_dirt.png
 - block_content: !texture
     texture: default://assets/block/bedrock.png
 - block_content: !texture
     texture: default://assets/block/sand.png
+  map_color: [219, 207, 163]
+  falls: true
 - block_content: !texture
     texture: default://assets/block/amethyst_block.png
 - block_content: !texture
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+pub const DEFAULT_RANDOM_TICK_SPEED: u32 = 3;
+pub const MAX_RANDOM_TICK_SPEED: u32 = 1000;
+
+/// View distance is also limited by the server settings
+pub const MIN_VIEW_DISTANCE: u16 = 2;
+pub const MAX_VIEW_DISTANCE: u16 = 64;
+
+/// Names of the rules used by the commands and the storage
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display, EnumString, EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum GameRule {
+    AllowBlockEdit,
+    Pvp,
+    FallDamage,
+    MaxViewDistance,
+    RandomTickSpeed,
+    FluidFlow,
+    FallingBlocks,
+}
+
+impl GameRule {
+    pub fn get_all() -> Vec<GameRule> {
+        GameRule::iter().collect()
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "on" => Ok(true),
+        "false" | "off" => Ok(false),
+        _ => Err(format!("&cValue &4\"{}\"&c must be true or false", value)),
+    }
+}
+
+fn parse_number<T: FromStr + PartialOrd + std::fmt::Display>(value: &str, min: T, max: T) -> Result<T, String> {
+    match T::from_str(value) {
+        Ok(v) if min <= v && v <= max => Ok(v),
+        _ => Err(format!("&cValue &4\"{}\"&c must be from {} to {}", value, min, max)),
+    }
+}
+
+/// Behaviour of the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRules {
+    allow_block_edit: bool,
+    pvp: bool,
+    fall_damage: bool,
+    max_view_distance: u16,
+    random_tick_speed: u32,
+    fluid_flow: bool,
+    falling_blocks: bool,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            allow_block_edit: true,
+            pvp: true,
+            fall_damage: true,
+            max_view_distance: MAX_VIEW_DISTANCE,
+            random_tick_speed: DEFAULT_RANDOM_TICK_SPEED,
+            fluid_flow: true,
+            falling_blocks: true,
+        }
+    }
+}
+
+impl GameRules {
+    /// If players can place and break blocks
+    pub fn is_allow_block_edit(&self) -> bool {
+        self.allow_block_edit
+    }
+
+    /// If players can hurt each other
+    pub fn is_pvp(&self) -> bool {
+        self.pvp
+    }
+
+    /// If players are hurt by falling
+    pub fn is_fall_damage(&self) -> bool {
+        self.fall_damage
+    }
+
+    /// Chunks distance around the players in this world
+    pub fn get_max_view_distance(&self) -> u16 {
+        self.max_view_distance
+    }
+
+    /// How many random blocks of each chunk section are ticked every tick
+    pub fn get_random_tick_speed(&self) -> u32 {
+        self.random_tick_speed
+    }
+
+    pub fn is_fluid_flow(&self) -> bool {
+        self.fluid_flow
+    }
+
+    pub fn is_falling_blocks(&self) -> bool {
+        self.falling_blocks
+    }
+
+    pub fn get_value(&self, rule: &GameRule) -> String {
+        match rule {
+            GameRule::AllowBlockEdit => self.allow_block_edit.to_string(),
+            GameRule::Pvp => self.pvp.to_string(),
+            GameRule::FallDamage => self.fall_damage.to_string(),
+            GameRule::MaxViewDistance => self.max_view_distance.to_string(),
+            GameRule::RandomTickSpeed => self.random_tick_speed.to_string(),
+            GameRule::FluidFlow => self.fluid_flow.to_string(),
+            GameRule::FallingBlocks => self.falling_blocks.to_string(),
+        }
+    }
+
+    /// Value is parsed according to the type of the rule
+    pub fn set_value(&mut self, rule: &GameRule, value: &str) -> Result<(), String> {
+        match rule {
+            GameRule::AllowBlockEdit => self.allow_block_edit = parse_bool(value)?,
+            GameRule::Pvp => self.pvp = parse_bool(value)?,
+            GameRule::FallDamage => self.fall_damage = parse_bool(value)?,
+            GameRule::MaxViewDistance => {
+                self.max_view_distance = parse_number(value, MIN_VIEW_DISTANCE, MAX_VIEW_DISTANCE)?
+            }
+            GameRule::RandomTickSpeed => self.random_tick_speed = parse_number(value, 0, MAX_RANDOM_TICK_SPEED)?,
+            GameRule::FluidFlow => self.fluid_flow = parse_bool(value)?,
+            GameRule::FallingBlocks => self.falling_blocks = parse_bool(value)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameRule, GameRules, MAX_VIEW_DISTANCE};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_game_rules() {
+        let mut rules = GameRules::default();
+        assert_eq!(rules.is_allow_block_edit(), true);
+
+        let rule = GameRule::from_str("allow_block_edit").unwrap();
+        rules.set_value(&rule, "false").unwrap();
+        assert_eq!(rules.is_allow_block_edit(), false);
+        assert_eq!(rules.get_value(&rule), "false");
+
+        rules.set_value(&GameRule::RandomTickSpeed, "10").unwrap();
+        assert_eq!(rules.get_random_tick_speed(), 10);
+
+        assert!(rules.set_value(&GameRule::Pvp, "10").is_err());
+        assert!(rules.set_value(&GameRule::MaxViewDistance, "1").is_err());
+        assert!(rules.set_value(&GameRule::MaxViewDistance, "off").is_err());
+        assert_eq!(rules.get_max_view_distance(), MAX_VIEW_DISTANCE);
+    }
+}
@@ -9,9 +9,22 @@ pub mod default_resources;
 pub mod default_blocks;
 pub mod default_blocks_ids;
 pub mod commands;
+pub mod regions;
+pub mod world_time;
+pub mod world_weather;
+pub mod world_border;
+pub mod game_rules;
 
 pub type WorldStorageManager = SQLiteStorage;
 
 pub const CHUNK_SIZE: u8 = 16_u8;
 pub const CHUNK_SIZE_BOUNDARY: u32 = CHUNK_SIZE as u32 + 2;
 pub const VERTICAL_SECTIONS: usize = 16;
+
+// Player controller physics shared by the client and the server checks
+pub const PLAYER_HEIGHT: f32 = 1.8;
+pub const PLAYER_RADIUS: f32 = 0.4;
+pub const PLAYER_MASS: f32 = 3.0;
+pub const PLAYER_MOVEMENT_SPEED: f32 = 4.0;
+pub const PLAYER_JUMP_SPEED: f32 = 8.0;
+pub const PLAYER_GRAVITY: f32 = -10.0;
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::chunks::block_position::BlockPosition;
+
+/// Actions which can be allowed or denied inside the region
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Display, EnumString, EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum RegionFlag {
+    Build,
+    Break,
+    Interact,
+    Pvp,
+}
+
+impl RegionFlag {
+    pub fn get_all() -> Vec<RegionFlag> {
+        RegionFlag::iter().collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RegionShape {
+    Cuboid {
+        min: BlockPosition,
+        max: BlockPosition,
+    },
+    // Horizontal polygon of x and z points
+    Polygon {
+        points: Vec<(i64, i64)>,
+        min_y: i64,
+        max_y: i64,
+    },
+}
+
+impl RegionShape {
+    /// Corners can be passed in any order
+    pub fn cuboid(a: &BlockPosition, b: &BlockPosition) -> Self {
+        Self::Cuboid {
+            min: BlockPosition::new(
+                a.get_x().min(b.get_x()),
+                a.get_y().min(b.get_y()),
+                a.get_z().min(b.get_z()),
+            ),
+            max: BlockPosition::new(
+                a.get_x().max(b.get_x()),
+                a.get_y().max(b.get_y()),
+                a.get_z().max(b.get_z()),
+            ),
+        }
+    }
+
+    pub fn polygon(points: Vec<(i64, i64)>, min_y: i64, max_y: i64) -> Result<Self, String> {
+        if points.len() < 3 {
+            return Err("&cpolygon must have at least 3 points".to_string());
+        }
+        Ok(Self::Polygon {
+            points,
+            min_y: min_y.min(max_y),
+            max_y: min_y.max(max_y),
+        })
+    }
+
+    pub fn contains(&self, position: &BlockPosition) -> bool {
+        match self {
+            RegionShape::Cuboid { min, max } => {
+                (min.get_x()..=max.get_x()).contains(&position.get_x())
+                    && (min.get_y()..=max.get_y()).contains(&position.get_y())
+                    && (min.get_z()..=max.get_z()).contains(&position.get_z())
+            }
+            RegionShape::Polygon { points, min_y, max_y } => {
+                if !(*min_y..=*max_y).contains(&position.get_y()) {
+                    return false;
+                }
+                // Ray casting by the center of the block
+                let (x, z) = (position.get_x() as f64 + 0.5, position.get_z() as f64 + 0.5);
+                let mut inside = false;
+                let mut j = points.len() - 1;
+                for i in 0..points.len() {
+                    let (xi, zi) = (points[i].0 as f64, points[i].1 as f64);
+                    let (xj, zj) = (points[j].0 as f64, points[j].1 as f64);
+                    if (zi > z) != (zj > z) && x < (xj - xi) * (z - zi) / (zj - zi) + xi {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// Protected zone of the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    name: String,
+    shape: RegionShape,
+
+    // Logins of the players
+    owners: Vec<String>,
+    members: Vec<String>,
+
+    // Flags which are not set are denied for the players outside of the region
+    flags: BTreeMap<RegionFlag, bool>,
+}
+
+impl Region {
+    pub fn create(name: String, shape: RegionShape) -> Self {
+        Self {
+            name,
+            shape,
+            owners: Default::default(),
+            members: Default::default(),
+            flags: Default::default(),
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_shape(&self) -> &RegionShape {
+        &self.shape
+    }
+
+    pub fn get_owners(&self) -> &Vec<String> {
+        &self.owners
+    }
+
+    pub fn get_members(&self) -> &Vec<String> {
+        &self.members
+    }
+
+    pub fn get_flags(&self) -> &BTreeMap<RegionFlag, bool> {
+        &self.flags
+    }
+
+    pub fn add_owner(&mut self, login: String) {
+        if !self.owners.contains(&login) {
+            self.owners.push(login);
+        }
+    }
+
+    /// Returns false if the player is already a member
+    pub fn add_member(&mut self, login: String) -> bool {
+        if self.members.contains(&login) {
+            return false;
+        }
+        self.members.push(login);
+        true
+    }
+
+    pub fn set_flag(&mut self, flag: RegionFlag, value: bool) {
+        self.flags.insert(flag, value);
+    }
+
+    pub fn is_owner(&self, login: &String) -> bool {
+        self.owners.contains(login)
+    }
+
+    pub fn is_member(&self, login: &String) -> bool {
+        self.is_owner(login) || self.members.contains(login)
+    }
+
+    pub fn contains(&self, position: &BlockPosition) -> bool {
+        self.shape.contains(position)
+    }
+
+    /// Owners and members are always allowed
+    pub fn is_allowed(&self, flag: &RegionFlag, login: &String) -> bool {
+        self.is_member(login) || self.flags.get(flag).cloned().unwrap_or(false)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    pub fn decode(encoded: Vec<u8>) -> Result<Region, String> {
+        match bincode::deserialize(&encoded) {
+            Ok(d) => Ok(d),
+            Err(e) => Err(format!("Decode region error: &c{}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Region, RegionFlag, RegionShape};
+    use crate::chunks::block_position::BlockPosition;
+
+    #[test]
+    fn test_region_shapes() {
+        let cuboid = RegionShape::cuboid(&BlockPosition::new(5, 10, -5), &BlockPosition::new(-5, 0, 5));
+        assert!(cuboid.contains(&BlockPosition::new(0, 0, 0)));
+        assert!(cuboid.contains(&BlockPosition::new(5, 10, -5)));
+        assert!(!cuboid.contains(&BlockPosition::new(0, 11, 0)));
+
+        // Triangle
+        let polygon = RegionShape::polygon(vec![(0, 0), (10, 0), (0, 10)], 0, 5).unwrap();
+        assert!(polygon.contains(&BlockPosition::new(1, 0, 1)));
+        assert!(!polygon.contains(&BlockPosition::new(8, 0, 8)));
+        assert!(!polygon.contains(&BlockPosition::new(1, 6, 1)));
+
+        assert!(RegionShape::polygon(vec![(0, 0), (10, 0)], 0, 5).is_err());
+    }
+
+    #[test]
+    fn test_region_flags() {
+        let shape = RegionShape::cuboid(&BlockPosition::new(0, 0, 0), &BlockPosition::new(1, 1, 1));
+        let mut region = Region::create("spawn".to_string(), shape);
+        region.add_owner("owner".to_string());
+        region.add_member("member".to_string());
+        region.set_flag(RegionFlag::Interact, true);
+
+        let guest = "guest".to_string();
+        assert!(region.is_allowed(&RegionFlag::Build, &"owner".to_string()));
+        assert!(region.is_allowed(&RegionFlag::Break, &"member".to_string()));
+        assert!(!region.is_allowed(&RegionFlag::Build, &guest));
+        assert!(region.is_allowed(&RegionFlag::Interact, &guest));
+
+        let decoded = Region::decode(region.encode()).unwrap();
+        assert_eq!(decoded, region);
+    }
+}
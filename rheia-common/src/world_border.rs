@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{CHUNK_SIZE, chunks::chunk_position::ChunkPosition};
+
+/// Border can't be smaller than a single chunk
+pub const MIN_BORDER_SIZE: f32 = CHUNK_SIZE as f32;
+pub const MAX_BORDER_SIZE: f32 = 30_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum WorldBorderShape {
+    Circle,
+    Square,
+}
+
+impl WorldBorderShape {
+    pub fn get_all() -> Vec<WorldBorderShape> {
+        WorldBorderShape::iter().collect()
+    }
+}
+
+/// Limits the world around the center
+///
+/// Size is the distance from the center to the edge: the radius of the circle
+/// or the half of the square side. Border can be resized smoothly over time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldBorder {
+    center_x: f32,
+    center_z: f32,
+    shape: WorldBorderShape,
+    size: f32,
+
+    // Resize animation
+    target_size: f32,
+    remaining: f32,
+}
+
+impl WorldBorder {
+    pub fn create(center_x: f32, center_z: f32, shape: WorldBorderShape, size: f32) -> Self {
+        let size = size.clamp(MIN_BORDER_SIZE, MAX_BORDER_SIZE);
+        Self {
+            center_x,
+            center_z,
+            shape,
+            size,
+            target_size: size,
+            remaining: 0.0,
+        }
+    }
+
+    pub fn get_center(&self) -> (f32, f32) {
+        (self.center_x, self.center_z)
+    }
+
+    pub fn set_center(&mut self, center_x: f32, center_z: f32) {
+        self.center_x = center_x;
+        self.center_z = center_z;
+    }
+
+    pub fn get_shape(&self) -> &WorldBorderShape {
+        &self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: WorldBorderShape) {
+        self.shape = shape;
+    }
+
+    pub fn get_size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn get_target_size(&self) -> f32 {
+        self.target_size
+    }
+
+    /// The largest size during the resize
+    pub fn get_max_size(&self) -> f32 {
+        self.size.max(self.target_size)
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    /// Zero duration changes the size at once
+    pub fn resize(&mut self, target_size: f32, duration: Duration) {
+        self.target_size = target_size.clamp(MIN_BORDER_SIZE, MAX_BORDER_SIZE);
+        self.remaining = duration.as_secs_f32();
+        if self.remaining <= 0.0 {
+            self.size = self.target_size;
+        }
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        if !self.is_resizing() {
+            return;
+        }
+        let delta = delta.as_secs_f32();
+        if delta >= self.remaining {
+            self.size = self.target_size;
+            self.remaining = 0.0;
+            return;
+        }
+        self.size += (self.target_size - self.size) * delta / self.remaining;
+        self.remaining -= delta;
+    }
+
+    pub fn contains(&self, x: f32, z: f32) -> bool {
+        let (dx, dz) = (x - self.center_x, z - self.center_z);
+        match self.shape {
+            WorldBorderShape::Circle => dx * dx + dz * dz <= self.size * self.size,
+            WorldBorderShape::Square => dx.abs() <= self.size && dz.abs() <= self.size,
+        }
+    }
+
+    /// If any part of the chunk is inside the border at any moment of the resize
+    ///
+    /// Chunks which only touch the border are outside
+    pub fn contains_chunk(&self, chunk_position: &ChunkPosition) -> bool {
+        let min_x = chunk_position.x as f32 * CHUNK_SIZE as f32;
+        let min_z = chunk_position.z as f32 * CHUNK_SIZE as f32;
+        let max_x = min_x + CHUNK_SIZE as f32;
+        let max_z = min_z + CHUNK_SIZE as f32;
+
+        // The nearest point of the chunk to the center
+        let dx = self.center_x.clamp(min_x, max_x) - self.center_x;
+        let dz = self.center_z.clamp(min_z, max_z) - self.center_z;
+        let size = self.get_max_size();
+        match self.shape {
+            WorldBorderShape::Circle => dx * dx + dz * dz < size * size,
+            WorldBorderShape::Square => dx.abs() < size && dz.abs() < size,
+        }
+    }
+
+    /// The nearest point inside the border
+    pub fn clamp(&self, x: f32, z: f32) -> (f32, f32) {
+        if self.contains(x, z) {
+            return (x, z);
+        }
+        let (dx, dz) = (x - self.center_x, z - self.center_z);
+
+        // Small step inside, so the point isn't at the edge
+        let size = (self.size - 0.5).max(0.0);
+        match self.shape {
+            WorldBorderShape::Circle => {
+                let distance = (dx * dx + dz * dz).sqrt();
+                (
+                    self.center_x + dx / distance * size,
+                    self.center_z + dz / distance * size,
+                )
+            }
+            WorldBorderShape::Square => (
+                self.center_x + dx.clamp(-size, size),
+                self.center_z + dz.clamp(-size, size),
+            ),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    pub fn decode(encoded: Vec<u8>) -> Result<WorldBorder, String> {
+        match bincode::deserialize(&encoded) {
+            Ok(d) => Ok(d),
+            Err(e) => Err(format!("Decode world border error: &c{}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorldBorder, WorldBorderShape};
+    use crate::chunks::chunk_position::ChunkPosition;
+    use std::time::Duration;
+
+    #[test]
+    fn test_world_border() {
+        let border = WorldBorder::create(0.0, 0.0, WorldBorderShape::Circle, 100.0);
+        assert!(border.contains(60.0, 60.0));
+        assert!(!border.contains(80.0, 80.0));
+        assert!(border.contains_chunk(&ChunkPosition::new(6, 0)));
+        assert!(!border.contains_chunk(&ChunkPosition::new(7, 0)));
+        assert_eq!(border.clamp(200.0, 0.0), (99.5, 0.0));
+
+        let border = WorldBorder::create(0.0, 0.0, WorldBorderShape::Square, 100.0);
+        assert!(border.contains(80.0, -80.0));
+        assert!(border.contains_chunk(&ChunkPosition::new(-7, 6)));
+        assert_eq!(border.clamp(200.0, -150.0), (99.5, -99.5));
+    }
+
+    #[test]
+    fn test_world_border_resize() {
+        let mut border = WorldBorder::create(0.0, 0.0, WorldBorderShape::Circle, 100.0);
+        border.resize(50.0, Duration::from_secs(10));
+        assert_eq!(border.get_max_size(), 100.0);
+
+        border.advance(Duration::from_secs(5));
+        assert_eq!(border.get_size(), 75.0);
+        assert!(border.is_resizing());
+
+        border.advance(Duration::from_secs(10));
+        assert_eq!(border.get_size(), 50.0);
+        assert!(!border.is_resizing());
+    }
+}
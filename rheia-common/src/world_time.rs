@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+/// How many times the clock advances each second
+pub const TIME_TICKS_PER_SECOND: f64 = 20.0;
+
+/// Length of the full day and night cycle in ticks
+pub const DAY_LENGTH: f64 = 24000.0;
+
+/// Sunrise is at 0, noon at 6000, sunset at 12000 and midnight at 18000
+pub const TIME_DAY: f64 = 1000.0;
+pub const TIME_NIGHT: f64 = 13000.0;
+
+pub const MAX_TIME_SPEED: f32 = 100.0;
+
+/// Clock of the world, shared by the server and the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldTime {
+    time: f64,
+    speed: f32,
+}
+
+impl Default for WorldTime {
+    fn default() -> Self {
+        Self::create(TIME_DAY, 1.0)
+    }
+}
+
+impl WorldTime {
+    pub fn create(time: f64, speed: f32) -> Self {
+        Self {
+            time: time.rem_euclid(DAY_LENGTH),
+            speed: speed.clamp(0.0, MAX_TIME_SPEED),
+        }
+    }
+
+    /// Time of the day in ticks
+    pub fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: f64) {
+        self.time = time.rem_euclid(DAY_LENGTH);
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(0.0, MAX_TIME_SPEED);
+    }
+
+    /// Frozen clock has zero speed
+    pub fn is_frozen(&self) -> bool {
+        self.speed == 0.0
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        if self.is_frozen() {
+            return;
+        }
+        let ticks = delta.as_secs_f64() * TIME_TICKS_PER_SECOND * self.speed as f64;
+        self.time = (self.time + ticks).rem_euclid(DAY_LENGTH);
+    }
+
+    /// Angle of the sun over the horizon in radians: 0 at sunrise, PI/2 at noon
+    pub fn get_sun_angle(&self) -> f32 {
+        (self.time / DAY_LENGTH) as f32 * TAU
+    }
+
+    /// From 0.0 at night to 1.0 at day with smooth sunrise and sunset
+    pub fn get_daylight(&self) -> f32 {
+        (self.get_sun_angle().sin() * 3.0).clamp(-1.0, 1.0) * 0.5 + 0.5
+    }
+
+    /// Parses the ticks or the "day" and "night" names
+    pub fn parse_time(value: &str) -> Result<f64, String> {
+        match value {
+            "day" => Ok(TIME_DAY),
+            "night" => Ok(TIME_NIGHT),
+            _ => match value.parse::<f64>() {
+                Ok(t) if t.is_finite() => Ok(t.rem_euclid(DAY_LENGTH)),
+                _ => Err(format!(
+                    "&cTime must be a number of ticks, \"day\" or \"night\"; got &4\"{}\"",
+                    value
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DAY_LENGTH, TIME_DAY, TIME_NIGHT, WorldTime};
+    use std::time::Duration;
+
+    #[test]
+    fn test_world_time() {
+        let mut time = WorldTime::create(DAY_LENGTH - 10.0, 1.0);
+        time.advance(Duration::from_secs(1));
+        assert_eq!(time.get_time(), 10.0);
+
+        time.set_speed(0.0);
+        time.advance(Duration::from_secs(10));
+        assert_eq!(time.get_time(), 10.0);
+
+        assert!(WorldTime::create(6000.0, 1.0).get_daylight() == 1.0);
+        assert!(WorldTime::create(18000.0, 1.0).get_daylight() == 0.0);
+
+        assert_eq!(WorldTime::parse_time("day"), Ok(TIME_DAY));
+        assert_eq!(WorldTime::parse_time("night"), Ok(TIME_NIGHT));
+        assert_eq!(WorldTime::parse_time("30000"), Ok(6000.0));
+        assert!(WorldTime::parse_time("noon").is_err());
+    }
+}
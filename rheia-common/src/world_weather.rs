@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum WeatherType {
+    Clear,
+    Rain,
+    Storm,
+    Snow,
+}
+
+impl WeatherType {
+    pub fn get_all() -> Vec<WeatherType> {
+        WeatherType::iter().collect()
+    }
+}
+
+/// What the players see; intensity is from 0.0 to 1.0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    weather_type: WeatherType,
+    intensity: f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::create(WeatherType::Clear, 0.0)
+    }
+}
+
+impl Weather {
+    pub fn create(weather_type: WeatherType, intensity: f32) -> Self {
+        let intensity = match weather_type {
+            WeatherType::Clear => 0.0,
+            _ => intensity.clamp(0.0, 1.0),
+        };
+        Self {
+            weather_type,
+            intensity,
+        }
+    }
+
+    pub fn get_weather_type(&self) -> &WeatherType {
+        &self.weather_type
+    }
+
+    pub fn get_intensity(&self) -> f32 {
+        self.intensity
+    }
+}
+
+/// Weather of the world with its schedule
+///
+/// Weather without the remaining time lasts until it's changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldWeather {
+    weather: Weather,
+    remaining: Option<f32>,
+
+    // If the weather changes randomly
+    cycle: bool,
+}
+
+impl Default for WorldWeather {
+    fn default() -> Self {
+        Self::create(Default::default(), None, true)
+    }
+}
+
+impl WorldWeather {
+    pub fn create(weather: Weather, remaining: Option<f32>, cycle: bool) -> Self {
+        Self {
+            weather,
+            remaining,
+            cycle,
+        }
+    }
+
+    pub fn get_weather(&self) -> &Weather {
+        &self.weather
+    }
+
+    /// Seconds until the weather is over
+    pub fn get_remaining(&self) -> Option<f32> {
+        self.remaining
+    }
+
+    pub fn set_weather(&mut self, weather: Weather, duration: Option<f32>) {
+        self.weather = weather;
+        self.remaining = duration;
+    }
+
+    pub fn is_cycle(&self) -> bool {
+        self.cycle
+    }
+
+    pub fn set_cycle(&mut self, cycle: bool) {
+        self.cycle = cycle;
+    }
+
+    /// Returns true when the weather is over
+    pub fn advance(&mut self, delta: Duration) -> bool {
+        let Some(remaining) = self.remaining.as_mut() else {
+            return false;
+        };
+        *remaining -= delta.as_secs_f32();
+        *remaining <= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Weather, WeatherType, WorldWeather};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_world_weather() {
+        assert_eq!(WeatherType::from_str("storm"), Ok(WeatherType::Storm));
+        assert_eq!(Weather::create(WeatherType::Clear, 1.0).get_intensity(), 0.0);
+        assert_eq!(Weather::create(WeatherType::Rain, 2.0).get_intensity(), 1.0);
+
+        let mut weather = WorldWeather::default();
+        assert!(!weather.advance(Duration::from_secs(1000)));
+
+        weather.set_weather(Weather::create(WeatherType::Snow, 0.5), Some(10.0));
+        assert!(!weather.advance(Duration::from_secs(5)));
+        assert!(weather.advance(Duration::from_secs(5)));
+    }
+}
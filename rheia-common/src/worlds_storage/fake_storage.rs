@@ -1,8 +1,18 @@
 use std::collections::BTreeMap;
 
-use crate::chunks::{
-    chunk_data::{BlockIndexType, ChunkData},
-    chunk_position::ChunkPosition,
+use crate::{
+    chunks::{
+        block_position::BlockPosition,
+        chunk_data::{BlockIndexType, ChunkData},
+        chunk_position::ChunkPosition,
+        scheduled_tick::ScheduledTick,
+        stored_entity::StoredEntity,
+    },
+    game_rules::GameRules,
+    regions::Region,
+    world_border::WorldBorder,
+    world_time::WorldTime,
+    world_weather::WorldWeather,
 };
 
 use super::taits::{IWorldStorage, WorldInfo, WorldStorageSettings};
@@ -25,10 +35,110 @@ impl IWorldStorage for FakeWorldStorage {
         unimplemented!()
     }
 
+    fn load_chunk_encoded(&self, _chunk_id: Self::PrimaryKey) -> Result<Vec<u8>, String> {
+        Err("fake storage has no chunks".to_string())
+    }
+
     fn save_chunk_data(&self, _chunk_position: &ChunkPosition, _data: &ChunkData) -> Result<Self::PrimaryKey, String> {
         Ok(())
     }
 
+    fn load_scheduled_ticks(&self, _chunk_position: &ChunkPosition) -> Result<Vec<ScheduledTick>, String> {
+        Ok(Default::default())
+    }
+
+    fn save_scheduled_ticks(&self, _chunk_position: &ChunkPosition, _ticks: &Vec<ScheduledTick>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_chunk_entities(&self, _chunk_position: &ChunkPosition) -> Result<Vec<StoredEntity>, String> {
+        Ok(Default::default())
+    }
+
+    fn save_chunk_entities(
+        &self,
+        _chunk_position: &ChunkPosition,
+        _entities: &Vec<StoredEntity>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_last_entity_id(&self) -> Result<u64, String> {
+        Ok(0)
+    }
+
+    fn save_last_entity_id(&self, _id: u64) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn quarantine_chunk(&self, _chunk_id: Self::PrimaryKey, _error: &String) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn list_chunks(&self) -> Result<Vec<(ChunkPosition, Self::PrimaryKey)>, String> {
+        Ok(Default::default())
+    }
+
+    fn load_forced_chunks(&self) -> Result<Vec<ChunkPosition>, String> {
+        Ok(Default::default())
+    }
+
+    fn set_chunk_forced(&self, _chunk_position: &ChunkPosition, _forced: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_regions(&self) -> Result<Vec<Region>, String> {
+        Ok(Default::default())
+    }
+
+    fn save_region(&self, _region: &Region) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn delete_region(&self, _name: &String) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_spawn_position(&self) -> Result<Option<BlockPosition>, String> {
+        Ok(None)
+    }
+
+    fn save_spawn_position(&self, _position: &BlockPosition) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_world_time(&self) -> Result<Option<WorldTime>, String> {
+        Ok(None)
+    }
+
+    fn save_world_time(&self, _world_time: &WorldTime) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_world_weather(&self) -> Result<Option<WorldWeather>, String> {
+        Ok(None)
+    }
+
+    fn save_world_weather(&self, _world_weather: &WorldWeather) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_world_border(&self) -> Result<Option<WorldBorder>, String> {
+        Ok(None)
+    }
+
+    fn save_world_border(&self, _border: Option<&WorldBorder>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_game_rules(&self) -> Result<GameRules, String> {
+        Ok(Default::default())
+    }
+
+    fn save_game_rules(&self, _game_rules: &GameRules) -> Result<(), String> {
+        Ok(())
+    }
+
     fn scan_worlds(_settings: &WorldStorageSettings) -> Result<Vec<WorldInfo>, String> {
         let worlds: Vec<WorldInfo> = Default::default();
         Ok(worlds)
@@ -2,13 +2,24 @@ use std::{
     collections::BTreeMap,
     fs::{create_dir_all, read_dir, remove_file},
     io::{Seek, SeekFrom, Write},
+    str::FromStr,
 };
 
 use rusqlite::{Connection, DatabaseName, OptionalExtension, blob::ZeroBlob};
 
-use crate::chunks::{
-    chunk_data::{BlockIndexType, ChunkData},
-    chunk_position::ChunkPosition,
+use crate::{
+    chunks::{
+        block_position::BlockPosition,
+        chunk_data::{BlockIndexType, ChunkData},
+        chunk_position::ChunkPosition,
+        scheduled_tick::ScheduledTick,
+        stored_entity::StoredEntity,
+    },
+    game_rules::{GameRule, GameRules},
+    regions::Region,
+    world_border::WorldBorder,
+    world_time::WorldTime,
+    world_weather::{Weather, WeatherType, WorldWeather},
 };
 
 use super::taits::{IWorldStorage, WorldInfo, WorldStorageSettings};
@@ -26,6 +37,67 @@ const SQL_READ_SEED: &str = "SELECT seed FROM world_info;";
 const SQL_SELECT_CHUNK_ID: &str = "SELECT id FROM chunks WHERE x=?1 AND z=?2;";
 const SQL_INSERT_CHUNK: &str = "INSERT INTO chunks (x, z, sections_data) VALUES (?1, ?2, ?3);";
 const SQL_UPDATE_CHUNK: &str = "UPDATE chunks SET sections_data = ?2 WHERE id=?1";
+const SQL_SELECT_CHUNKS: &str = "SELECT id, x, z FROM chunks;";
+
+const SQL_CREATE_TABLE_QUARANTINE: &str = "CREATE TABLE IF NOT EXISTS quarantined_chunks \
+    (id INTEGER PRIMARY KEY, x INTEGER, z INTEGER, sections_data BLOB, error TEXT, created_at TEXT DEFAULT CURRENT_TIMESTAMP);";
+const SQL_QUARANTINE_CHUNK: &str = "INSERT INTO quarantined_chunks (x, z, sections_data, error) \
+    SELECT x, z, sections_data, ?2 FROM chunks WHERE id=?1;";
+const SQL_DELETE_CHUNK: &str = "DELETE FROM chunks WHERE id=?1;";
+
+const SQL_CREATE_TABLE_TICKS: &str =
+    "CREATE TABLE IF NOT EXISTS scheduled_ticks (x INTEGER, z INTEGER, ticks_data BLOB, PRIMARY KEY (x, z));";
+const SQL_SELECT_TICKS: &str = "SELECT ticks_data FROM scheduled_ticks WHERE x=?1 AND z=?2;";
+const SQL_REPLACE_TICKS: &str = "INSERT OR REPLACE INTO scheduled_ticks (x, z, ticks_data) VALUES (?1, ?2, ?3);";
+const SQL_DELETE_TICKS: &str = "DELETE FROM scheduled_ticks WHERE x=?1 AND z=?2;";
+
+const SQL_CREATE_TABLE_ENTITIES: &str =
+    "CREATE TABLE IF NOT EXISTS chunk_entities (x INTEGER, z INTEGER, entities_data BLOB, PRIMARY KEY (x, z));";
+const SQL_SELECT_ENTITIES: &str = "SELECT entities_data FROM chunk_entities WHERE x=?1 AND z=?2;";
+const SQL_REPLACE_ENTITIES: &str = "INSERT OR REPLACE INTO chunk_entities (x, z, entities_data) VALUES (?1, ?2, ?3);";
+const SQL_DELETE_ENTITIES: &str = "DELETE FROM chunk_entities WHERE x=?1 AND z=?2;";
+
+const SQL_CREATE_TABLE_ENTITY_ID: &str =
+    "CREATE TABLE IF NOT EXISTS entity_id (id INTEGER PRIMARY KEY, last_id INTEGER);";
+const SQL_SELECT_ENTITY_ID: &str = "SELECT last_id FROM entity_id WHERE id=0;";
+const SQL_REPLACE_ENTITY_ID: &str = "INSERT OR REPLACE INTO entity_id (id, last_id) VALUES (0, ?1);";
+
+const SQL_CREATE_TABLE_FORCED: &str =
+    "CREATE TABLE IF NOT EXISTS forced_chunks (x INTEGER, z INTEGER, PRIMARY KEY (x, z));";
+const SQL_SELECT_FORCED: &str = "SELECT x, z FROM forced_chunks;";
+const SQL_INSERT_FORCED: &str = "INSERT OR IGNORE INTO forced_chunks (x, z) VALUES (?1, ?2);";
+const SQL_DELETE_FORCED: &str = "DELETE FROM forced_chunks WHERE x=?1 AND z=?2;";
+
+const SQL_CREATE_TABLE_REGIONS: &str = "CREATE TABLE IF NOT EXISTS regions (name TEXT PRIMARY KEY, region_data BLOB);";
+const SQL_SELECT_REGIONS: &str = "SELECT region_data FROM regions;";
+const SQL_REPLACE_REGION: &str = "INSERT OR REPLACE INTO regions (name, region_data) VALUES (?1, ?2);";
+const SQL_DELETE_REGION: &str = "DELETE FROM regions WHERE name=?1;";
+
+const SQL_CREATE_TABLE_SPAWN: &str =
+    "CREATE TABLE IF NOT EXISTS world_spawn (id INTEGER PRIMARY KEY, x INTEGER, y INTEGER, z INTEGER);";
+const SQL_SELECT_SPAWN: &str = "SELECT x, y, z FROM world_spawn WHERE id=0;";
+const SQL_REPLACE_SPAWN: &str = "INSERT OR REPLACE INTO world_spawn (id, x, y, z) VALUES (0, ?1, ?2, ?3);";
+
+const SQL_CREATE_TABLE_TIME: &str =
+    "CREATE TABLE IF NOT EXISTS world_time (id INTEGER PRIMARY KEY, time REAL, speed REAL);";
+const SQL_SELECT_TIME: &str = "SELECT time, speed FROM world_time WHERE id=0;";
+const SQL_REPLACE_TIME: &str = "INSERT OR REPLACE INTO world_time (id, time, speed) VALUES (0, ?1, ?2);";
+
+const SQL_CREATE_TABLE_WEATHER: &str = "CREATE TABLE IF NOT EXISTS world_weather \
+    (id INTEGER PRIMARY KEY, weather_type TEXT, intensity REAL, remaining REAL, cycle INTEGER);";
+const SQL_SELECT_WEATHER: &str = "SELECT weather_type, intensity, remaining, cycle FROM world_weather WHERE id=0;";
+const SQL_REPLACE_WEATHER: &str = "INSERT OR REPLACE INTO world_weather (id, weather_type, intensity, remaining, cycle) \
+    VALUES (0, ?1, ?2, ?3, ?4);";
+
+const SQL_CREATE_TABLE_BORDER: &str =
+    "CREATE TABLE IF NOT EXISTS world_border (id INTEGER PRIMARY KEY, border_data BLOB);";
+const SQL_SELECT_BORDER: &str = "SELECT border_data FROM world_border WHERE id=0;";
+const SQL_REPLACE_BORDER: &str = "INSERT OR REPLACE INTO world_border (id, border_data) VALUES (0, ?1);";
+const SQL_DELETE_BORDER: &str = "DELETE FROM world_border WHERE id=0;";
+
+const SQL_CREATE_TABLE_GAME_RULES: &str = "CREATE TABLE IF NOT EXISTS game_rules (rule TEXT PRIMARY KEY, value TEXT);";
+const SQL_SELECT_GAME_RULES: &str = "SELECT rule, value FROM game_rules;";
+const SQL_REPLACE_GAME_RULE: &str = "INSERT OR REPLACE INTO game_rules (rule, value) VALUES (?1, ?2);";
 
 const SQL_CREATE_TABLE_IDS: &str =
     "CREATE TABLE IF NOT EXISTS world_block_ids (block_id INTEGER UNIQUE, block_slug STRING);";
@@ -87,6 +159,41 @@ impl IWorldStorage for SQLiteStorage {
             log::info!(target: "worlds", "World db &e\"{}\"&r created", path.to_str().unwrap());
         }
 
+        // Created separately for the worlds from older versions
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_FORCED, ()) {
+            return Err(format!("World forced chunks table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_QUARANTINE, ()) {
+            return Err(format!("World quarantine table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_TICKS, ()) {
+            return Err(format!("World scheduled ticks table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_ENTITIES, ()) {
+            return Err(format!("World entities table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_ENTITY_ID, ()) {
+            return Err(format!("World entity id table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_REGIONS, ()) {
+            return Err(format!("World regions table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_SPAWN, ()) {
+            return Err(format!("World spawn table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_TIME, ()) {
+            return Err(format!("World time table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_WEATHER, ()) {
+            return Err(format!("World weather table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_BORDER, ()) {
+            return Err(format!("World border table create error: &c{}", e));
+        }
+        if let Err(e) = db.execute(SQL_CREATE_TABLE_GAME_RULES, ()) {
+            return Err(format!("Game rules table create error: &c{}", e));
+        }
+
         Ok(Self { db, slug: world_slug })
     }
 
@@ -106,28 +213,32 @@ impl IWorldStorage for SQLiteStorage {
     }
 
     fn load_chunk_data(&self, chunk_id: Self::PrimaryKey) -> Result<ChunkData, String> {
-        let blob = self
-            .db
-            .blob_open(DatabaseName::Main, "chunks", "sections_data", chunk_id.clone(), true)
-            .unwrap();
-        let mut encoded = vec![0u8; blob.size() as usize];
-        blob.read_at_exact(&mut encoded, 0).unwrap();
-
+        let encoded = self.load_chunk_encoded(chunk_id)?;
         let encoded_len = encoded.len();
         let sections = match ChunkData::decode_zip(encoded) {
             Ok(d) => d,
             Err(e) => {
-                return Err(format!(
-                    "Error: {} (encoded size:{} blob size: {})",
-                    e,
-                    encoded_len,
-                    blob.size()
-                ));
+                return Err(format!("Error: {} (encoded size:{})", e, encoded_len));
             }
         };
         Ok(sections)
     }
 
+    fn load_chunk_encoded(&self, chunk_id: Self::PrimaryKey) -> Result<Vec<u8>, String> {
+        let blob = match self
+            .db
+            .blob_open(DatabaseName::Main, "chunks", "sections_data", chunk_id.clone(), true)
+        {
+            Ok(b) => b,
+            Err(e) => return Err(format!("Chunk #{} blob open error: &c{}", chunk_id, e)),
+        };
+        let mut encoded = vec![0u8; blob.size() as usize];
+        if let Err(e) = blob.read_at_exact(&mut encoded, 0) {
+            return Err(format!("Chunk #{} blob read error: &c{}", chunk_id, e));
+        }
+        Ok(encoded)
+    }
+
     fn save_chunk_data(&self, chunk_position: &ChunkPosition, data: &ChunkData) -> Result<Self::PrimaryKey, String> {
         let encoded = data.encode_zip();
 
@@ -154,17 +265,363 @@ impl IWorldStorage for SQLiteStorage {
             }
         };
 
-        let mut blob = self
+        let mut blob = match self
             .db
             .blob_open(DatabaseName::Main, "chunks", "sections_data", chunk_id.clone(), false)
-            .unwrap();
-        let bytes_written = blob.write(encoded.as_slice()).unwrap();
-        assert_eq!(encoded.len(), bytes_written);
-        blob.seek(SeekFrom::Start(0)).unwrap();
+        {
+            Ok(b) => b,
+            Err(e) => return Err(format!("Chunk #{} blob open error: &c{}", chunk_id, e)),
+        };
+        let bytes_written = match blob.write(encoded.as_slice()) {
+            Ok(b) => b,
+            Err(e) => return Err(format!("Chunk #{} blob write error: &c{}", chunk_id, e)),
+        };
+        if encoded.len() != bytes_written {
+            return Err(format!(
+                "Chunk #{} written {} bytes of {}",
+                chunk_id,
+                bytes_written,
+                encoded.len()
+            ));
+        }
+        if let Err(e) = blob.seek(SeekFrom::Start(0)) {
+            return Err(format!("Chunk #{} blob seek error: &c{}", chunk_id, e));
+        }
 
         Ok(chunk_id)
     }
 
+    fn load_scheduled_ticks(&self, chunk_position: &ChunkPosition) -> Result<Vec<ScheduledTick>, String> {
+        let encoded: rusqlite::Result<Vec<u8>> =
+            self.db
+                .query_row(SQL_SELECT_TICKS, (chunk_position.x, chunk_position.z), |row| row.get(0));
+        let encoded = match encoded.optional() {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Chunk {} scheduled ticks load error: &c{}", chunk_position, e)),
+        };
+        match encoded {
+            Some(encoded) => ScheduledTick::decode(encoded),
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn save_scheduled_ticks(&self, chunk_position: &ChunkPosition, ticks: &Vec<ScheduledTick>) -> Result<(), String> {
+        let result = match ticks.len() {
+            0 => self.db.execute(SQL_DELETE_TICKS, (chunk_position.x, chunk_position.z)),
+            _ => self.db.execute(
+                SQL_REPLACE_TICKS,
+                (chunk_position.x, chunk_position.z, ScheduledTick::encode(ticks)),
+            ),
+        };
+        if let Err(e) = result {
+            return Err(format!("Chunk {} scheduled ticks save error: &c{}", chunk_position, e));
+        }
+        Ok(())
+    }
+
+    fn load_chunk_entities(&self, chunk_position: &ChunkPosition) -> Result<Vec<StoredEntity>, String> {
+        let encoded: rusqlite::Result<Vec<u8>> =
+            self.db
+                .query_row(SQL_SELECT_ENTITIES, (chunk_position.x, chunk_position.z), |row| {
+                    row.get(0)
+                });
+        let encoded = match encoded.optional() {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Chunk {} entities load error: &c{}", chunk_position, e)),
+        };
+        match encoded {
+            Some(encoded) => StoredEntity::decode(encoded),
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn save_chunk_entities(&self, chunk_position: &ChunkPosition, entities: &Vec<StoredEntity>) -> Result<(), String> {
+        let result = match entities.len() {
+            0 => self
+                .db
+                .execute(SQL_DELETE_ENTITIES, (chunk_position.x, chunk_position.z)),
+            _ => self.db.execute(
+                SQL_REPLACE_ENTITIES,
+                (chunk_position.x, chunk_position.z, StoredEntity::encode(entities)),
+            ),
+        };
+        if let Err(e) = result {
+            return Err(format!("Chunk {} entities save error: &c{}", chunk_position, e));
+        }
+        Ok(())
+    }
+
+    fn load_last_entity_id(&self) -> Result<u64, String> {
+        let id: rusqlite::Result<i64> = self.db.query_row(SQL_SELECT_ENTITY_ID, [], |row| row.get(0));
+        match id.optional() {
+            Ok(id) => Ok(id.unwrap_or(0) as u64),
+            Err(e) => Err(format!("Last entity id load error: &c{}", e)),
+        }
+    }
+
+    fn save_last_entity_id(&self, id: u64) -> Result<(), String> {
+        if let Err(e) = self.db.execute(SQL_REPLACE_ENTITY_ID, (id as i64,)) {
+            return Err(format!("Last entity id save error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn quarantine_chunk(&self, chunk_id: Self::PrimaryKey, error: &String) -> Result<(), String> {
+        if let Err(e) = self.db.execute(SQL_QUARANTINE_CHUNK, (&chunk_id, error)) {
+            return Err(format!("Chunk #{} quarantine error: &c{}", chunk_id, e));
+        }
+        if let Err(e) = self.db.execute(SQL_DELETE_CHUNK, (&chunk_id,)) {
+            return Err(format!("Chunk #{} delete error: &c{}", chunk_id, e));
+        }
+        Ok(())
+    }
+
+    fn list_chunks(&self) -> Result<Vec<(ChunkPosition, Self::PrimaryKey)>, String> {
+        let mut stmt = match self.db.prepare(SQL_SELECT_CHUNKS) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Chunks list error: &c{}", e)),
+        };
+        let rows = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            Ok((ChunkPosition::new(row.get(1)?, row.get(2)?), id))
+        });
+        let rows = match rows {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Chunks list error: &c{}", e)),
+        };
+
+        let mut chunks: Vec<(ChunkPosition, Self::PrimaryKey)> = Default::default();
+        for row in rows {
+            match row {
+                Ok(r) => chunks.push(r),
+                Err(e) => return Err(format!("Chunks list row error: &c{}", e)),
+            }
+        }
+        Ok(chunks)
+    }
+
+    fn load_forced_chunks(&self) -> Result<Vec<ChunkPosition>, String> {
+        let mut stmt = match self.db.prepare(SQL_SELECT_FORCED) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Forced chunks list error: &c{}", e)),
+        };
+        let rows = stmt.query_map([], |row| Ok(ChunkPosition::new(row.get(0)?, row.get(1)?)));
+        let rows = match rows {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Forced chunks list error: &c{}", e)),
+        };
+
+        let mut chunks: Vec<ChunkPosition> = Default::default();
+        for row in rows {
+            match row {
+                Ok(r) => chunks.push(r),
+                Err(e) => return Err(format!("Forced chunks row error: &c{}", e)),
+            }
+        }
+        Ok(chunks)
+    }
+
+    fn set_chunk_forced(&self, chunk_position: &ChunkPosition, forced: bool) -> Result<(), String> {
+        let sql = match forced {
+            true => SQL_INSERT_FORCED,
+            false => SQL_DELETE_FORCED,
+        };
+        if let Err(e) = self.db.execute(sql, (chunk_position.x, chunk_position.z)) {
+            return Err(format!("Forced chunk {} save error: &c{}", chunk_position, e));
+        }
+        Ok(())
+    }
+
+    fn load_regions(&self) -> Result<Vec<Region>, String> {
+        let mut stmt = match self.db.prepare(SQL_SELECT_REGIONS) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Regions list error: &c{}", e)),
+        };
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0));
+        let rows = match rows {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Regions list error: &c{}", e)),
+        };
+
+        let mut regions: Vec<Region> = Default::default();
+        for row in rows {
+            match row {
+                Ok(r) => regions.push(Region::decode(r)?),
+                Err(e) => return Err(format!("Regions row error: &c{}", e)),
+            }
+        }
+        Ok(regions)
+    }
+
+    fn save_region(&self, region: &Region) -> Result<(), String> {
+        if let Err(e) = self
+            .db
+            .execute(SQL_REPLACE_REGION, (region.get_name(), region.encode()))
+        {
+            return Err(format!("Region \"{}\" save error: &c{}", region.get_name(), e));
+        }
+        Ok(())
+    }
+
+    fn delete_region(&self, name: &String) -> Result<(), String> {
+        if let Err(e) = self.db.execute(SQL_DELETE_REGION, (name,)) {
+            return Err(format!("Region \"{}\" delete error: &c{}", name, e));
+        }
+        Ok(())
+    }
+
+    fn load_spawn_position(&self) -> Result<Option<BlockPosition>, String> {
+        let position = self
+            .db
+            .query_row(SQL_SELECT_SPAWN, [], |row| {
+                Ok(BlockPosition::new(row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .optional();
+        match position {
+            Ok(p) => Ok(p),
+            Err(e) => Err(format!("Spawn position load error: &c{}", e)),
+        }
+    }
+
+    fn save_spawn_position(&self, position: &BlockPosition) -> Result<(), String> {
+        let result = self.db.execute(
+            SQL_REPLACE_SPAWN,
+            (position.get_x(), position.get_y(), position.get_z()),
+        );
+        if let Err(e) = result {
+            return Err(format!("Spawn position save error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn load_world_time(&self) -> Result<Option<WorldTime>, String> {
+        let world_time = self
+            .db
+            .query_row(SQL_SELECT_TIME, [], |row| {
+                Ok(WorldTime::create(row.get(0)?, row.get(1)?))
+            })
+            .optional();
+        match world_time {
+            Ok(t) => Ok(t),
+            Err(e) => Err(format!("World time load error: &c{}", e)),
+        }
+    }
+
+    fn save_world_time(&self, world_time: &WorldTime) -> Result<(), String> {
+        let result = self
+            .db
+            .execute(SQL_REPLACE_TIME, (world_time.get_time(), world_time.get_speed()));
+        if let Err(e) = result {
+            return Err(format!("World time save error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn load_world_weather(&self) -> Result<Option<WorldWeather>, String> {
+        let row = self
+            .db
+            .query_row(SQL_SELECT_WEATHER, [], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, f32>(1)?,
+                    row.get::<_, Option<f32>>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })
+            .optional();
+        let (weather_type, intensity, remaining, cycle) = match row {
+            Ok(Some(r)) => r,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(format!("World weather load error: &c{}", e)),
+        };
+        let weather_type = match WeatherType::from_str(&weather_type) {
+            Ok(t) => t,
+            Err(_) => return Err(format!("World weather type &e\"{}\"&r is unknown", weather_type)),
+        };
+        Ok(Some(WorldWeather::create(
+            Weather::create(weather_type, intensity),
+            remaining,
+            cycle,
+        )))
+    }
+
+    fn save_world_weather(&self, world_weather: &WorldWeather) -> Result<(), String> {
+        let weather = world_weather.get_weather();
+        let result = self.db.execute(
+            SQL_REPLACE_WEATHER,
+            (
+                weather.get_weather_type().to_string(),
+                weather.get_intensity(),
+                world_weather.get_remaining(),
+                world_weather.is_cycle(),
+            ),
+        );
+        if let Err(e) = result {
+            return Err(format!("World weather save error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn load_world_border(&self) -> Result<Option<WorldBorder>, String> {
+        let border_data = self
+            .db
+            .query_row(SQL_SELECT_BORDER, [], |row| row.get::<_, Vec<u8>>(0))
+            .optional();
+        match border_data {
+            Ok(Some(d)) => Ok(Some(WorldBorder::decode(d)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("World border load error: &c{}", e)),
+        }
+    }
+
+    fn save_world_border(&self, border: Option<&WorldBorder>) -> Result<(), String> {
+        let result = match border {
+            Some(border) => self.db.execute(SQL_REPLACE_BORDER, (border.encode(),)),
+            None => self.db.execute(SQL_DELETE_BORDER, ()),
+        };
+        if let Err(e) = result {
+            return Err(format!("World border save error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn load_game_rules(&self) -> Result<GameRules, String> {
+        let mut stmt = match self.db.prepare(SQL_SELECT_GAME_RULES) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Game rules list error: &c{}", e)),
+        };
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)));
+        let rows = match rows {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Game rules list error: &c{}", e)),
+        };
+
+        let mut game_rules = GameRules::default();
+        for row in rows {
+            let (rule, value) = match row {
+                Ok(r) => r,
+                Err(e) => return Err(format!("Game rules row error: &c{}", e)),
+            };
+            let Ok(rule) = GameRule::from_str(&rule) else {
+                return Err(format!("Game rule &e\"{}\"&r is unknown", rule));
+            };
+            game_rules.set_value(&rule, &value)?;
+        }
+        Ok(game_rules)
+    }
+
+    fn save_game_rules(&self, game_rules: &GameRules) -> Result<(), String> {
+        for rule in GameRule::get_all() {
+            let result = self
+                .db
+                .execute(SQL_REPLACE_GAME_RULE, (rule.to_string(), game_rules.get_value(&rule)));
+            if let Err(e) = result {
+                return Err(format!("Game rule \"{}\" save error: &c{}", rule, e));
+            }
+        }
+        Ok(())
+    }
+
     fn scan_worlds(settings: &WorldStorageSettings) -> Result<Vec<WorldInfo>, String> {
         let mut worlds: Vec<WorldInfo> = Default::default();
 
@@ -303,17 +760,27 @@ mod tests {
     use std::env;
 
     use crate::{
-        chunks::{chunk_data::ChunkData, chunk_position::ChunkPosition},
+        chunks::{
+            block_position::BlockPosition, chunk_data::ChunkData, chunk_position::ChunkPosition, position::Vector3,
+            rotation::Rotation, scheduled_tick::ScheduledTick, stored_entity::StoredEntity,
+        },
+        game_rules::{GameRule, GameRules},
+        regions::{Region, RegionFlag, RegionShape},
+        world_border::{WorldBorder, WorldBorderShape},
         world_generator::{
             default::{WorldGenerator, WorldGeneratorSettings},
             traits::IWorldGenerator,
         },
+        world_time::WorldTime,
+        world_weather::{Weather, WeatherType, WorldWeather},
         worlds_storage::{
             sqlite_storage::SQLiteStorage,
             taits::{IWorldStorage, WorldStorageSettings},
         },
     };
 
+    const SQL_SELECT_QUARANTINED_COUNT: &str = "SELECT COUNT(*) FROM quarantined_chunks WHERE x=?1 AND z=?2;";
+
     fn generate_chunk(seed: u64, chunk_position: &ChunkPosition) -> ChunkData {
         let generator = WorldGenerator::create(Some(seed), WorldGeneratorSettings::default()).unwrap();
         generator.generate_chunk_data(&chunk_position)
@@ -346,4 +813,239 @@ mod tests {
 
         storage.delete(&settings).unwrap();
     }
+
+    #[test]
+    fn test_list_chunks() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_list_chunks".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.list_chunks().unwrap().len(), 0);
+
+        let chunk_position = ChunkPosition::new(3, -3);
+        let chunk_id = storage
+            .save_chunk_data(&chunk_position, &generate_chunk(1, &chunk_position))
+            .unwrap();
+
+        let chunks = storage.list_chunks().unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0, chunk_position);
+        assert_eq!(chunks[0].1, chunk_id);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_load_chunk_encoded() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_encoded".to_string(), 1, &settings).unwrap();
+
+        let chunk_position = ChunkPosition::new(2, 2);
+        let sections = generate_chunk(1, &chunk_position);
+        let chunk_id = storage.save_chunk_data(&chunk_position, &sections).unwrap();
+
+        let encoded = storage.load_chunk_encoded(chunk_id).unwrap();
+        let decoded = ChunkData::decode_zip(encoded).unwrap();
+        assert_eq!(decoded.get(0).unwrap().len(), sections.get(0).unwrap().len());
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_quarantine_chunk() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_quarantine".to_string(), 1, &settings).unwrap();
+
+        let chunk_position = ChunkPosition::new(1, 1);
+        let chunk_id = storage
+            .save_chunk_data(&chunk_position, &generate_chunk(1, &chunk_position))
+            .unwrap();
+
+        storage.quarantine_chunk(chunk_id, &"test".to_string()).unwrap();
+        assert_eq!(storage.has_chunk_data(&chunk_position).unwrap(), None);
+
+        let quarantined: i64 = storage
+            .db
+            .query_row(
+                SQL_SELECT_QUARANTINED_COUNT,
+                (chunk_position.x, chunk_position.z),
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(quarantined, 1);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_scheduled_ticks() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_ticks".to_string(), 1, &settings).unwrap();
+
+        let chunk_position = ChunkPosition::new(0, 0);
+        assert_eq!(storage.load_scheduled_ticks(&chunk_position).unwrap().len(), 0);
+
+        let ticks = vec![ScheduledTick::create(BlockPosition::new(1, 2, 3), 5)];
+        storage.save_scheduled_ticks(&chunk_position, &ticks).unwrap();
+        assert_eq!(storage.load_scheduled_ticks(&chunk_position).unwrap(), ticks);
+
+        // Empty list removes the record
+        storage.save_scheduled_ticks(&chunk_position, &Vec::new()).unwrap();
+        assert_eq!(storage.load_scheduled_ticks(&chunk_position).unwrap().len(), 0);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_chunk_entities() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_entities".to_string(), 1, &settings).unwrap();
+
+        let chunk_position = ChunkPosition::new(0, 0);
+        assert_eq!(storage.load_chunk_entities(&chunk_position).unwrap().len(), 0);
+        assert_eq!(storage.load_last_entity_id().unwrap(), 0);
+
+        let entity = StoredEntity::create(1, Vector3::new(1.5, 70.0, 2.5), Rotation::default(), vec![1, 2, 3]);
+        storage.save_chunk_entities(&chunk_position, &vec![entity]).unwrap();
+        storage.save_last_entity_id(1).unwrap();
+
+        let loaded = storage.load_chunk_entities(&chunk_position).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].get_id(), 1);
+        assert_eq!(*loaded[0].get_components(), vec![1, 2, 3]);
+        assert_eq!(storage.load_last_entity_id().unwrap(), 1);
+
+        // Empty list removes the record
+        storage.save_chunk_entities(&chunk_position, &Vec::new()).unwrap();
+        assert_eq!(storage.load_chunk_entities(&chunk_position).unwrap().len(), 0);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_forced_chunks() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_forced".to_string(), 1, &settings).unwrap();
+
+        let chunk_position = ChunkPosition::new(2, -3);
+        assert_eq!(storage.load_forced_chunks().unwrap().len(), 0);
+
+        storage.set_chunk_forced(&chunk_position, true).unwrap();
+        // Second time is ignored
+        storage.set_chunk_forced(&chunk_position, true).unwrap();
+        let forced = storage.load_forced_chunks().unwrap();
+        assert_eq!(forced.len(), 1);
+        assert_eq!(forced[0], chunk_position);
+
+        storage.set_chunk_forced(&chunk_position, false).unwrap();
+        assert_eq!(storage.load_forced_chunks().unwrap().len(), 0);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_regions() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_regions".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_regions().unwrap().len(), 0);
+
+        let shape = RegionShape::cuboid(&BlockPosition::new(0, 0, 0), &BlockPosition::new(10, 10, 10));
+        let mut region = Region::create("spawn".to_string(), shape);
+        storage.save_region(&region).unwrap();
+
+        // Saving again replaces the region
+        region.set_flag(RegionFlag::Interact, true);
+        storage.save_region(&region).unwrap();
+        assert_eq!(storage.load_regions().unwrap(), vec![region.clone()]);
+
+        storage.delete_region(region.get_name()).unwrap();
+        assert_eq!(storage.load_regions().unwrap().len(), 0);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_spawn_position() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_spawn".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_spawn_position().unwrap(), None);
+
+        storage.save_spawn_position(&BlockPosition::new(1, 70, -5)).unwrap();
+        storage.save_spawn_position(&BlockPosition::new(10, 64, 3)).unwrap();
+        assert_eq!(
+            storage.load_spawn_position().unwrap(),
+            Some(BlockPosition::new(10, 64, 3))
+        );
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_world_time() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_time".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_world_time().unwrap(), None);
+
+        storage.save_world_time(&WorldTime::create(12000.5, 2.0)).unwrap();
+        assert_eq!(
+            storage.load_world_time().unwrap(),
+            Some(WorldTime::create(12000.5, 2.0))
+        );
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_world_weather() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_weather".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_world_weather().unwrap(), None);
+
+        let weather = WorldWeather::create(Weather::create(WeatherType::Storm, 0.8), Some(60.0), false);
+        storage.save_world_weather(&weather).unwrap();
+        assert_eq!(storage.load_world_weather().unwrap(), Some(weather));
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_world_border() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_border".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_world_border().unwrap(), None);
+
+        let border = WorldBorder::create(10.0, -20.0, WorldBorderShape::Square, 500.0);
+        storage.save_world_border(Some(&border)).unwrap();
+        assert_eq!(storage.load_world_border().unwrap(), Some(border));
+
+        storage.save_world_border(None).unwrap();
+        assert_eq!(storage.load_world_border().unwrap(), None);
+
+        storage.delete(&settings).unwrap();
+    }
+
+    #[test]
+    fn test_game_rules() {
+        let data_path = env::current_dir().unwrap().clone();
+        let settings = WorldStorageSettings::create(data_path);
+        let storage = SQLiteStorage::create("tests_game_rules".to_string(), 1, &settings).unwrap();
+        assert_eq!(storage.load_game_rules().unwrap(), GameRules::default());
+
+        let mut game_rules = GameRules::default();
+        game_rules.set_value(&GameRule::AllowBlockEdit, "false").unwrap();
+        game_rules.set_value(&GameRule::RandomTickSpeed, "20").unwrap();
+        storage.save_game_rules(&game_rules).unwrap();
+        assert_eq!(storage.load_game_rules().unwrap(), game_rules);
+
+        storage.delete(&settings).unwrap();
+    }
 }
@@ -1,6 +1,16 @@
-use crate::chunks::{
-    chunk_data::{BlockIndexType, ChunkData},
-    chunk_position::ChunkPosition,
+use crate::{
+    chunks::{
+        block_position::BlockPosition,
+        chunk_data::{BlockIndexType, ChunkData},
+        chunk_position::ChunkPosition,
+        scheduled_tick::ScheduledTick,
+        stored_entity::StoredEntity,
+    },
+    game_rules::GameRules,
+    regions::Region,
+    world_border::WorldBorder,
+    world_time::WorldTime,
+    world_weather::WorldWeather,
 };
 use std::{collections::BTreeMap, path::PathBuf};
 
@@ -31,7 +41,58 @@ pub trait IWorldStorage: Sized {
     fn create(world_slug: String, seed: u64, settings: &WorldStorageSettings) -> Result<Self, Self::Error>;
     fn has_chunk_data(&self, chunk_position: &ChunkPosition) -> Result<Option<Self::PrimaryKey>, String>;
     fn load_chunk_data(&self, chunk_id: Self::PrimaryKey) -> Result<ChunkData, String>;
+    /// Data of the chunk as it is stored, without the decoding
+    fn load_chunk_encoded(&self, chunk_id: Self::PrimaryKey) -> Result<Vec<u8>, String>;
     fn save_chunk_data(&self, chunk_position: &ChunkPosition, data: &ChunkData) -> Result<Self::PrimaryKey, String>;
+
+    /// Pending block updates are stored separately from the chunk blocks
+    fn load_scheduled_ticks(&self, chunk_position: &ChunkPosition) -> Result<Vec<ScheduledTick>, String>;
+    fn save_scheduled_ticks(&self, chunk_position: &ChunkPosition, ticks: &Vec<ScheduledTick>) -> Result<(), String>;
+
+    /// Server-owned entities are stored by the chunk they are in
+    fn load_chunk_entities(&self, chunk_position: &ChunkPosition) -> Result<Vec<StoredEntity>, String>;
+    fn save_chunk_entities(&self, chunk_position: &ChunkPosition, entities: &Vec<StoredEntity>) -> Result<(), String>;
+
+    /// The last id given to the stored entities; ids are never reused
+    fn load_last_entity_id(&self) -> Result<u64, String>;
+    fn save_last_entity_id(&self, id: u64) -> Result<(), String>;
+
+    /// Moves the raw data of the unreadable chunk aside,
+    /// so the chunk can be created again
+    fn quarantine_chunk(&self, chunk_id: Self::PrimaryKey, error: &String) -> Result<(), String>;
+
+    /// Returns all chunks saved inside the storage
+    fn list_chunks(&self) -> Result<Vec<(ChunkPosition, Self::PrimaryKey)>, String>;
+
+    /// Returns chunks that must be kept loaded all the time
+    fn load_forced_chunks(&self) -> Result<Vec<ChunkPosition>, String>;
+    fn set_chunk_forced(&self, chunk_position: &ChunkPosition, forced: bool) -> Result<(), String>;
+
+    /// Protected zones of the world
+    fn load_regions(&self) -> Result<Vec<Region>, String>;
+    fn save_region(&self, region: &Region) -> Result<(), String>;
+    fn delete_region(&self, name: &String) -> Result<(), String>;
+
+    /// Where the players appear in the world; None if it was never set
+    fn load_spawn_position(&self) -> Result<Option<BlockPosition>, String>;
+    fn save_spawn_position(&self, position: &BlockPosition) -> Result<(), String>;
+
+    /// Clock of the world; None if it was never saved
+    fn load_world_time(&self) -> Result<Option<WorldTime>, String>;
+    fn save_world_time(&self, world_time: &WorldTime) -> Result<(), String>;
+
+    /// Weather of the world with its schedule; None if it was never saved
+    fn load_world_weather(&self) -> Result<Option<WorldWeather>, String>;
+    fn save_world_weather(&self, world_weather: &WorldWeather) -> Result<(), String>;
+
+    /// Border of the world; None if the world is infinite
+    fn load_world_border(&self) -> Result<Option<WorldBorder>, String>;
+    fn save_world_border(&self, border: Option<&WorldBorder>) -> Result<(), String>;
+
+    /// Rules which were never saved have the default values
+    fn load_game_rules(&self) -> Result<GameRules, String>;
+    fn save_game_rules(&self, game_rules: &GameRules) -> Result<(), String>;
+
     fn delete(&self, settings: &WorldStorageSettings) -> Result<(), String>;
 
     fn scan_worlds(settings: &WorldStorageSettings) -> Result<Vec<WorldInfo>, String>;
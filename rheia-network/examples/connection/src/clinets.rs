@@ -39,6 +39,7 @@ impl Client {
                             version: String::from("-"),
                             architecture: String::from("-"),
                             rendering_device: String::from("-"),
+                            render_distance: 12,
                         };
                         self.client.send_message(NetworkMessageType::ReliableOrdered, &msg);
                     }
@@ -67,6 +67,7 @@ impl Server {
                         version: _,
                         architecture: _,
                         rendering_device: _,
+                        render_distance: _,
                     } => {
                         let Some(connection) = self.connections.get_mut(&client_id) else {
                             continue;
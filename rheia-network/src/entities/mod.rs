@@ -1,3 +1,4 @@
+use common::chunks::chunk_data::BlockIndexType;
 use entity_tag::EntityTag;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +8,8 @@ pub mod entity_tag;
 pub enum EntitySkin {
     Generic,
     Fixed(String),
+    // Looks like the block with the id
+    Block(BlockIndexType),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -4,6 +4,9 @@ use common::chunks::chunk_data::{BlockDataInfo, BlockIndexType, ChunkData};
 use common::chunks::chunk_position::ChunkPosition;
 use common::chunks::position::Vector3;
 use common::chunks::rotation::Rotation;
+use common::world_border::WorldBorder;
+use common::world_time::WorldTime;
+use common::world_weather::Weather;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use strum_macros::Display;
@@ -18,6 +21,7 @@ pub enum ClientMessages {
         version: String,
         architecture: String,
         rendering_device: String,
+        render_distance: u16,
     },
     ConsoleInput {
         command: String,
@@ -41,6 +45,9 @@ pub enum ClientMessages {
         last_index: u32,
     },
     SettingsLoaded,
+    ChangeRenderDistance {
+        render_distance: u16,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -94,6 +101,11 @@ pub enum ServerMessages {
         rotation: Rotation,
         components: Vec<EntityNetworkComponent>,
     },
+    // Moves the player by the server; also used to correct invalid movement
+    PlayerTeleport {
+        world_slug: String,
+        position: Vector3,
+    },
     ChunkSectionInfo {
         world_slug: String,
         chunk_position: ChunkPosition,
@@ -103,6 +115,7 @@ pub enum ServerMessages {
         world_slug: String,
         chunk_position: ChunkPosition,
         encoded: Vec<u8>,
+        light: Vec<u8>,
     },
     UnloadChunks {
         world_slug: String,
@@ -139,6 +152,27 @@ pub enum ServerMessages {
         position: BlockPosition,
         new_block_info: Option<BlockDataInfo>,
     },
+    // Multiple block changes of the same tick
+    EditBlocks {
+        world_slug: String,
+        changes: Vec<(BlockPosition, Option<BlockDataInfo>)>,
+    },
+
+    // Clock of the world; the client advances it between the syncs
+    WorldTime {
+        world_slug: String,
+        world_time: WorldTime,
+    },
+    // Sent on each change of the weather
+    WorldWeather {
+        world_slug: String,
+        weather: Weather,
+    },
+    // None removes the border; the client animates the resize by itself
+    WorldBorder {
+        world_slug: String,
+        border: Option<WorldBorder>,
+    },
 }
 
 pub enum NetworkMessageType {
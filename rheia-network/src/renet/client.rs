@@ -1,4 +1,5 @@
 use common::chunks::chunk_data::ChunkData;
+use common::chunks::chunk_light::ChunkLight;
 use flume::{Drain, Receiver, Sender};
 use parking_lot::RwLockReadGuard;
 use parking_lot::{RwLock, RwLockWriteGuard};
@@ -137,11 +138,28 @@ impl IClientNetwork for RenetClientNetwork {
                         world_slug,
                         chunk_position,
                         encoded,
-                    } => ServerMessages::ChunkSectionInfo {
-                        world_slug,
-                        chunk_position,
-                        sections: ChunkData::decode_zip(encoded).unwrap(),
-                    },
+                        light,
+                    } => {
+                        let mut sections = match ChunkData::decode_zip(encoded) {
+                            Ok(s) => s,
+                            Err(e) => {
+                                self.send_network_error(format!("chunk {} decode error: {}", chunk_position, e));
+                                continue;
+                            }
+                        };
+                        match ChunkLight::decode_zip(light) {
+                            Ok(light) => sections.set_light(light),
+                            // Chunk without the light is lit by the client itself
+                            Err(e) => {
+                                log::error!(target: "network", "Chunk {} light decode error: {}", chunk_position, e);
+                            }
+                        }
+                        ServerMessages::ChunkSectionInfo {
+                            world_slug,
+                            chunk_position,
+                            sections,
+                        }
+                    }
                     _ => decoded,
                 };
                 self.network_decoder_out.0.send(decoded).unwrap();
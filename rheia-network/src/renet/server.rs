@@ -3,7 +3,10 @@ use renet_netcode::{NetcodeServerTransport, ServerAuthentication, ServerConfig};
 use std::{
     collections::HashMap,
     net::UdpSocket,
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, Ordering},
+    },
     time::{Duration, SystemTime},
 };
 use strum::IntoEnumIterator;
@@ -114,7 +117,7 @@ impl IServerNetwork<RenetServerConnection> for RenetServerNetwork {
         }
 
         connections.retain(|_key, c| {
-            if *c.is_to_disconnect() {
+            if c.is_to_disconnect() {
                 server.disconnect(c.get_client_id());
             }
             !c.is_to_disconnect()
@@ -164,7 +167,9 @@ pub struct RenetServerConnection {
     server: ServerLock,
     client_id: u64,
     ip: String,
-    to_disconnect: bool,
+
+    // Shared between all clones of the connection
+    to_disconnect: Arc<AtomicBool>,
 
     channel_client_messages: (Sender<ClientMessages>, Receiver<ClientMessages>),
 }
@@ -175,14 +180,14 @@ impl RenetServerConnection {
             server,
             client_id,
             ip,
-            to_disconnect: false,
+            to_disconnect: Default::default(),
 
             channel_client_messages: flume::unbounded(),
         }
     }
 
-    fn is_to_disconnect(&self) -> &bool {
-        &self.to_disconnect
+    fn is_to_disconnect(&self) -> bool {
+        self.to_disconnect.load(Ordering::Relaxed)
     }
 }
 
@@ -210,6 +215,6 @@ impl IServerConnection for RenetServerConnection {
     }
 
     fn disconnect(&mut self) {
-        self.to_disconnect = true;
+        self.to_disconnect.store(true, Ordering::Relaxed);
     }
 }
@@ -1,9 +1,13 @@
 use super::resources_manager::ResourceManager;
 use crate::{launch_settings::LaunchSettings, network::runtime_plugin::RuntimePlugin};
+use ahash::AHashMap;
 use bevy::prelude::{Res, ResMut, Resource};
 use common::{
-    blocks::{block_info::generate_block_id_map, block_type::BlockType},
-    chunks::chunk_data::BlockIndexType,
+    blocks::{
+        block_info::generate_block_id_map,
+        block_type::{BlockColor, BlockType},
+    },
+    chunks::{chunk_data::BlockIndexType, light_propagation::LightBlocks},
     default_blocks::generate_default_blocks,
 };
 use network::messages::ServerMessages;
@@ -99,10 +103,49 @@ impl ServerSettings {
         self.block_id_map.as_ref().expect("block_id_map is not set")
     }
 
+    pub fn get_block_type(&self, block_id: &BlockIndexType) -> Option<&BlockType> {
+        let block_slug = self.get_block_id_map().get(block_id)?;
+        self.blocks.iter().find(|b| b.get_slug() == block_slug)
+    }
+
+    /// Entities and falling blocks pass through the fluids
+    pub fn is_fluid(&self, block_id: &BlockIndexType) -> bool {
+        self.get_block_type(block_id).is_some_and(|b| b.is_fluid())
+    }
+
+    /// Map colors of all blocks which have it by their id
+    pub fn get_map_colors(&self) -> AHashMap<BlockIndexType, BlockColor> {
+        let mut colors: AHashMap<BlockIndexType, BlockColor> = Default::default();
+        for (block_id, block_slug) in self.get_block_id_map().iter() {
+            let Some(block_type) = self.blocks.iter().find(|b| b.get_slug() == block_slug) else {
+                continue;
+            };
+            if let Some(color) = block_type.get_map_color() {
+                colors.insert(*block_id, color.clone());
+            }
+        }
+        colors
+    }
+
+    /// Light properties of all blocks by their id
+    pub fn get_light_blocks(&self) -> LightBlocks {
+        let mut light_blocks = LightBlocks::default();
+        for (block_id, block_slug) in self.get_block_id_map().iter() {
+            if let Some(block_type) = self.blocks.iter().find(|b| b.get_slug() == block_slug) {
+                light_blocks.add(*block_id, block_type);
+            }
+        }
+        light_blocks
+    }
+
     pub fn add_block(&mut self, block_type: BlockType) {
         self.blocks.push(block_type);
     }
 
+    pub fn get_blocks(&self) -> &Vec<BlockType> {
+        &self.blocks
+    }
+
     pub fn get_blocks_count(&self) -> usize {
         self.blocks.len()
     }
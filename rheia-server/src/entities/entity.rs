@@ -26,6 +26,22 @@ impl Position {
         Self { x, y, z }
     }
 
+    pub fn get_x(&self) -> PositionFloatType {
+        self.x
+    }
+
+    pub fn get_y(&self) -> PositionFloatType {
+        self.y
+    }
+
+    pub fn set_y(&mut self, y: PositionFloatType) {
+        self.y = y;
+    }
+
+    pub fn get_z(&self) -> PositionFloatType {
+        self.z
+    }
+
     pub fn to_network(&self) -> NetworkVector3 {
         NetworkVector3::new(self.x, self.y, self.z)
     }
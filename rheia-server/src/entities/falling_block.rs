@@ -0,0 +1,32 @@
+use bevy::prelude::Component;
+use common::chunks::chunk_data::BlockDataInfo;
+
+/// Block affected by gravity which is falling down
+#[derive(Component, Clone)]
+pub struct FallingBlockComponent {
+    block_info: BlockDataInfo,
+
+    // Blocks per second
+    velocity: f32,
+}
+
+impl FallingBlockComponent {
+    pub fn create(block_info: BlockDataInfo) -> Self {
+        Self {
+            block_info,
+            velocity: 0.0,
+        }
+    }
+
+    pub fn get_block_info(&self) -> &BlockDataInfo {
+        &self.block_info
+    }
+
+    pub fn get_velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+    }
+}
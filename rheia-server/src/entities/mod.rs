@@ -8,6 +8,9 @@ pub mod commands;
 pub mod entity;
 pub mod entity_tag;
 pub mod events;
+pub mod falling_block;
+pub mod npc;
+pub mod npc_behaviour;
 pub mod skin;
 pub mod traits;
 
@@ -31,4 +34,11 @@ impl EntityComponent {
             },
         }
     }
+
+    pub fn from_network(component: EntityNetworkComponent) -> Self {
+        match component {
+            EntityNetworkComponent::Tag(c) => EntityComponent::Tag(c.map(EntityTagComponent::create)),
+            EntityNetworkComponent::Skin(c) => EntityComponent::Skin(c.map(EntitySkinComponent::create)),
+        }
+    }
 }
@@ -0,0 +1,57 @@
+use bevy::prelude::{Component, EntityRef};
+use common::chunks::stored_entity::StoredEntity;
+use network::entities::EntityNetworkComponent;
+
+use super::{
+    EntityComponent,
+    entity::{IntoServerPosition, IntoServerRotation, Position, Rotation},
+    entity_tag::EntityTagComponent,
+    skin::EntitySkinComponent,
+};
+
+/// Entity owned by the server and saved with the chunk it is in
+#[derive(Component, Clone)]
+pub struct NpcComponent {
+    // Unique inside the world
+    id: u64,
+}
+
+impl NpcComponent {
+    pub fn create(id: u64) -> Self {
+        Self { id }
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// Position, rotation and components of the npc for the world storage
+pub fn npc_to_stored(entity_ref: &EntityRef) -> Option<StoredEntity> {
+    let npc = entity_ref.get::<NpcComponent>()?;
+    let mut components: Vec<EntityNetworkComponent> = Default::default();
+    if let Some(tag) = entity_ref.get::<EntityTagComponent>() {
+        components.push(EntityComponent::Tag(Some(tag.clone())).to_network());
+    }
+    if let Some(skin) = entity_ref.get::<EntitySkinComponent>() {
+        components.push(EntityComponent::Skin(Some(skin.clone())).to_network());
+    }
+    Some(StoredEntity::create(
+        npc.get_id(),
+        entity_ref.get::<Position>()?.to_network(),
+        entity_ref.get::<Rotation>()?.to_network(),
+        bincode::serialize(&components).unwrap(),
+    ))
+}
+
+pub fn npc_from_stored(stored: &StoredEntity) -> Result<(Position, Rotation, Vec<EntityComponent>), String> {
+    let components: Vec<EntityNetworkComponent> = match bincode::deserialize(stored.get_components()) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Npc #{} components decode error: &c{}", stored.get_id(), e)),
+    };
+    Ok((
+        stored.get_position().to_server(),
+        stored.get_rotation().to_server(),
+        components.into_iter().map(EntityComponent::from_network).collect(),
+    ))
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::Component;
+use common::chunks::block_position::BlockPosition;
+
+/// What the npc is doing while its chunk is loaded
+#[derive(Clone, Debug)]
+pub enum NpcBehaviour {
+    Idle,
+    // Walks to the random positions around the home
+    Wander { radius: i64 },
+    // Keeps the distance to the player of the same world; the player is found by the login
+    Follow { login: String, distance: f32 },
+    // Walks through the waypoints in a loop
+    Patrol { waypoints: Vec<BlockPosition>, next: usize },
+    // Becomes idle after the arrival
+    ReturnHome,
+}
+
+/// Behaviour is not saved with the npc, so it stays idle after the reload
+#[derive(Component, Clone)]
+pub struct NpcBehaviourComponent {
+    behaviour: NpcBehaviour,
+    home: BlockPosition,
+
+    // Where the current path leads; paths are searched from the block the npc stands on
+    path_start: BlockPosition,
+    goal: Option<BlockPosition>,
+    path: Vec<BlockPosition>,
+
+    // Seconds before the next goal is chosen
+    wait: f32,
+}
+
+impl NpcBehaviourComponent {
+    pub fn create(behaviour: NpcBehaviour, home: BlockPosition) -> Self {
+        Self {
+            behaviour,
+            home,
+            path_start: Default::default(),
+            goal: None,
+            path: Default::default(),
+            wait: 0.0,
+        }
+    }
+
+    pub fn get_behaviour(&self) -> &NpcBehaviour {
+        &self.behaviour
+    }
+
+    pub fn get_behaviour_mut(&mut self) -> &mut NpcBehaviour {
+        &mut self.behaviour
+    }
+
+    /// Current path is dropped
+    pub fn set_behaviour(&mut self, behaviour: NpcBehaviour) {
+        self.behaviour = behaviour;
+        self.stop(0.0);
+    }
+
+    pub fn get_home(&self) -> &BlockPosition {
+        &self.home
+    }
+
+    pub fn get_goal(&self) -> Option<&BlockPosition> {
+        self.goal.as_ref()
+    }
+
+    pub fn get_path_start(&self) -> &BlockPosition {
+        &self.path_start
+    }
+
+    pub fn get_next_step(&self) -> Option<&BlockPosition> {
+        self.path.first()
+    }
+
+    pub fn set_path(&mut self, start: BlockPosition, goal: BlockPosition, path: Vec<BlockPosition>) {
+        self.path_start = start;
+        self.goal = Some(goal);
+        self.path = path;
+    }
+
+    /// Returns true if the goal is reached
+    pub fn step_passed(&mut self) -> bool {
+        if !self.path.is_empty() {
+            self.path.remove(0);
+        }
+        self.path.is_empty()
+    }
+
+    /// Drops the path and waits before the next goal
+    pub fn stop(&mut self, wait: f32) {
+        self.goal = None;
+        self.path.clear();
+        self.wait = wait;
+    }
+
+    /// Returns true if the waiting is over
+    pub fn update_wait(&mut self, delta: f32) -> bool {
+        self.wait = (self.wait - delta).max(0.0);
+        self.wait <= 0.0
+    }
+}
@@ -4,6 +4,13 @@ use common::worlds_storage::taits::WorldStorageSettings;
 use std::env;
 use std::path::PathBuf;
 
+use crate::network::edit_validation::EditBlockRules;
+use crate::network::rate_limiter::{
+    CONSOLE_RATE_LIMIT, EDIT_BLOCK_RATE_LIMIT, MOVE_RATE_LIMIT, OTHER_RATE_LIMIT, RATE_LIMIT_KICK_THRESHOLD, RateLimits,
+};
+use crate::worlds::chunks::chunk_column::CorruptChunkPolicy;
+use crate::{CHUNKS_DISTANCE, EDIT_BLOCK_DISTANCE, MIN_CHUNKS_DISTANCE};
+
 use log::LevelFilter;
 
 #[derive(Parser, Debug, Clone)]
@@ -23,6 +30,42 @@ pub struct MainCommand {
 
     #[arg(long = "server-data-path", short = 'd')]
     pub server_data_path: Option<String>,
+
+    /// Maximum chunks render distance that the client can request
+    #[arg(long = "max-render-distance", default_value_t = CHUNKS_DISTANCE)]
+    pub max_render_distance: u16,
+
+    /// What to do with chunks that can't be read from the world storage
+    #[arg(long = "corrupt-chunk-policy", value_enum, default_value_t = CorruptChunkPolicy::Regenerate)]
+    pub corrupt_chunk_policy: CorruptChunkPolicy,
+
+    /// Maximum distance from the player to the block he can edit
+    #[arg(long = "edit-distance", default_value_t = EDIT_BLOCK_DISTANCE)]
+    pub edit_distance: f32,
+
+    /// Allows players to place solid blocks inside of other players
+    #[arg(long = "allow-place-inside-players", default_value_t = false)]
+    pub allow_place_inside_players: bool,
+
+    /// Player movement messages per second allowed for the client; 0 to disable
+    #[arg(long = "move-rate-limit", default_value_t = MOVE_RATE_LIMIT)]
+    pub move_rate_limit: u32,
+
+    /// Block edit requests per second allowed for the client; 0 to disable
+    #[arg(long = "edit-block-rate-limit", default_value_t = EDIT_BLOCK_RATE_LIMIT)]
+    pub edit_block_rate_limit: u32,
+
+    /// Console commands per second allowed for the client; 0 to disable
+    #[arg(long = "console-rate-limit", default_value_t = CONSOLE_RATE_LIMIT)]
+    pub console_rate_limit: u32,
+
+    /// All other messages per second allowed for the client; 0 to disable
+    #[arg(long = "other-rate-limit", default_value_t = OTHER_RATE_LIMIT)]
+    pub other_rate_limit: u32,
+
+    /// Dropped messages after which the client will be kicked; 0 to never kick
+    #[arg(long = "rate-limit-kick", default_value_t = RATE_LIMIT_KICK_THRESHOLD)]
+    pub rate_limit_kick: u32,
 }
 
 pub(crate) fn get_log_level(level: &String) -> LevelFilter {
@@ -73,6 +116,26 @@ impl LaunchSettings {
         }
     }
 
+    /// Limits the render distance requested by the client
+    pub fn clamp_render_distance(&self, render_distance: u16) -> u16 {
+        render_distance
+            .min(self.args.max_render_distance)
+            .max(MIN_CHUNKS_DISTANCE)
+    }
+
+    pub fn get_edit_block_rules(&self) -> EditBlockRules {
+        EditBlockRules::create(self.args.edit_distance, self.args.allow_place_inside_players)
+    }
+
+    pub fn get_rate_limits(&self) -> RateLimits {
+        RateLimits::default()
+            .move_limit(self.args.move_rate_limit)
+            .edit_block_limit(self.args.edit_block_rate_limit)
+            .console_limit(self.args.console_rate_limit)
+            .other_limit(self.args.other_rate_limit)
+            .kick_threshold(self.args.rate_limit_kick)
+    }
+
     pub fn get_world_storage_settings(&self) -> WorldStorageSettings {
         WorldStorageSettings::create(self.get_server_data_path())
     }
@@ -26,8 +26,14 @@ mod worlds;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const CHUNKS_DISTANCE: u16 = 12;
+pub const MIN_CHUNKS_DISTANCE: u16 = 2;
+pub const SPAWN_AREA_DISTANCE: u16 = 3;
+pub const CHUNKS_LOADING_LIMIT: usize = 32;
 pub const CHUNKS_DESPAWN_TIMER: Duration = Duration::from_secs(5);
+pub const CHUNK_SAVE_RETRY_DELAY: Duration = Duration::from_secs(1);
+pub const CHUNK_SAVE_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
 pub static SEND_CHUNK_QUEUE_LIMIT: usize = 16;
+pub const EDIT_BLOCK_DISTANCE: f32 = 8.0;
 
 fn main() {
     log::set_logger(&CONSOLE_LOGGER).unwrap();
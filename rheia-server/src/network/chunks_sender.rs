@@ -26,11 +26,10 @@ pub fn send_chunks(worlds_manager: Res<WorldsManager>, network_container: Res<Ne
             }
 
             // Get all entites that watch this chunk
+            // Chunk can be kept loaded without any player
             let watch_entities = match chunks_map.get_chunk_watchers(&chunk_position) {
                 Some(v) => v,
-                None => {
-                    panic!("chunk_loaded_event_reader chunk {} not found", chunk_position);
-                }
+                None => continue,
             };
             'entity_loop: for entity in watch_entities {
                 let ecs = world.get_ecs();
@@ -6,19 +6,25 @@ use network::{
     messages::{NetworkMessageType, ServerMessages},
     server::IServerConnection,
 };
-use parking_lot::{RwLock, RwLockReadGuard, lock_api::MappedRwLockReadGuard};
-use std::{any::Any, fmt::Display, sync::Arc};
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard, lock_api::MappedRwLockReadGuard};
+use std::{any::Any, fmt::Display, sync::Arc, time::Duration};
 
 use crate::{
-    SEND_CHUNK_QUEUE_LIMIT,
+    CHUNKS_DISTANCE, SEND_CHUNK_QUEUE_LIMIT,
     console::console_sender::{ConsoleSender, ConsoleSenderType},
     entities::{
         EntityComponent,
         entity::{Position, Rotation},
     },
+    worlds::{bulk_edit::EditSelection, edit_history::EditHistory},
 };
 
-use super::{events::on_connection_info::PlayerConnectionInfoEvent, server::NetworkPlugin};
+use super::{
+    events::on_connection_info::PlayerConnectionInfoEvent,
+    movement_validation::MovementState,
+    rate_limiter::{ClientMessageKind, ClientRateLimiter, RateLimitResult, RateLimits},
+    server::NetworkPlugin,
+};
 
 /// Store player current world slug and his entity
 #[derive(Clone)]
@@ -91,6 +97,22 @@ pub struct ClientNetwork {
     // Chunks was sended by network
     // but not yet recieved by the player
     send_chunk_queue: Arc<RwLock<Vec<ChunkPosition>>>,
+
+    // Chunks render radius requested by the player
+    // and clamped by the server settings
+    render_distance: Arc<RwLock<u16>>,
+
+    // Limits incoming messages of the client
+    rate_limiter: Arc<RwLock<ClientRateLimiter>>,
+
+    // To validate the player's movement packets
+    movement_state: Arc<RwLock<MovementState>>,
+
+    // Block edits of the player which can be undone
+    edit_history: Arc<RwLock<EditHistory>>,
+
+    // Area and clipboard for the bulk edits
+    edit_selection: Arc<RwLock<EditSelection>>,
 }
 
 impl ClientNetwork {
@@ -101,6 +123,11 @@ impl ClientNetwork {
             world_entity: Default::default(),
             already_sended: Default::default(),
             send_chunk_queue: Default::default(),
+            render_distance: Arc::new(RwLock::new(CHUNKS_DISTANCE)),
+            rate_limiter: Default::default(),
+            movement_state: Default::default(),
+            edit_history: Default::default(),
+            edit_selection: Default::default(),
         }
     }
 
@@ -143,6 +170,46 @@ impl ClientNetwork {
         *self.world_entity.write() = world_entity;
     }
 
+    pub fn get_render_distance(&self) -> u16 {
+        *self.render_distance.read()
+    }
+
+    pub fn set_render_distance(&self, render_distance: u16) {
+        *self.render_distance.write() = render_distance;
+    }
+
+    pub fn update_rate_limiter(&self, delta: Duration) {
+        self.rate_limiter.write().update(delta);
+    }
+
+    /// Takes a token for the incoming message
+    pub fn check_rate_limit(&self, kind: ClientMessageKind, limits: &RateLimits) -> RateLimitResult {
+        self.rate_limiter.write().check(kind, limits)
+    }
+
+    pub fn get_movement_state_mut(&self) -> RwLockWriteGuard<'_, MovementState> {
+        self.movement_state.write()
+    }
+
+    pub fn get_edit_history_mut(&self) -> RwLockWriteGuard<'_, EditHistory> {
+        self.edit_history.write()
+    }
+
+    pub fn get_edit_selection_mut(&self) -> RwLockWriteGuard<'_, EditSelection> {
+        self.edit_selection.write()
+    }
+
+    /// Moves the player on the client side
+    pub fn send_teleport(&self, position: &Position) {
+        let lock = self.get_world_entity();
+        let world_entity = lock.as_ref().unwrap();
+        let input = ServerMessages::PlayerTeleport {
+            world_slug: world_entity.get_world_slug().clone(),
+            position: position.to_network(),
+        };
+        self.send_message(NetworkMessageType::ReliableOrdered, &input);
+    }
+
     pub fn network_send_spawn(&self, position: &Position, rotation: &Rotation, components: &Vec<EntityComponent>) {
         let lock = self.get_world_entity();
         let world_entity = lock.as_ref().unwrap();
@@ -170,6 +237,12 @@ impl ClientNetwork {
         self.send_chunk_queue.write().push(chunk_position.clone());
     }
 
+    /// Forgets all sent chunks; the client drops them together with the old world
+    pub fn clear_sended_chunks(&self) {
+        self.already_sended.write().clear();
+        self.send_chunk_queue.write().clear();
+    }
+
     /// Called when the player has sent a confirmation of receiving chunk data
     pub fn mark_chunks_as_recieved(&self, chunk_positions: Vec<ChunkPosition>) {
         let mut send_chunk_queue = self.send_chunk_queue.write();
@@ -40,4 +40,10 @@ impl ClientsContainer {
     pub fn get(&self, key: &u64) -> Option<&ClientNetwork> {
         self.players.get(key)
     }
+
+    pub fn get_by_login(&self, login: &String) -> Option<&ClientNetwork> {
+        self.players
+            .values()
+            .find(|c| c.get_client_info().map_or(false, |i| i.get_login() == login))
+    }
 }
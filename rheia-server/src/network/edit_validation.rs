@@ -0,0 +1,138 @@
+use bevy::prelude::Entity;
+use common::{
+    PLAYER_HEIGHT, PLAYER_RADIUS,
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::{BlockDataInfo, FLUID_MAX_LEVEL},
+        chunk_position::ChunkPosition,
+    },
+};
+
+use crate::{
+    client_resources::server_settings::ServerSettings, entities::entity::Position, worlds::world_manager::WorldManager,
+};
+
+use super::client_network::ClientNetwork;
+
+/// Rules applied to the block edits requested by clients
+#[derive(Clone, Debug)]
+pub struct EditBlockRules {
+    max_distance: f32,
+    allow_place_inside_players: bool,
+}
+
+impl EditBlockRules {
+    pub fn create(max_distance: f32, allow_place_inside_players: bool) -> Self {
+        Self {
+            max_distance,
+            allow_place_inside_players,
+        }
+    }
+
+    pub fn get_max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    pub fn is_allow_place_inside_players(&self) -> bool {
+        self.allow_place_inside_players
+    }
+}
+
+/// Checks that the player is allowed to change the block
+pub fn validate_edit_block(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    rules: &EditBlockRules,
+    player: Entity,
+    position: &BlockPosition,
+    new_block_info: &Option<BlockDataInfo>,
+) -> Result<(), String> {
+    if !world_manager.get_game_rules().is_allow_block_edit() {
+        return Err("&cblock editing is disabled in this world".to_string());
+    }
+    if let Some(block_info) = new_block_info {
+        if server_settings.get_block_type(&block_info.get_id()).is_none() {
+            return Err(format!("&cblock id &4{}&c doesn't exist", block_info.get_id()));
+        }
+        if block_info.get_level().unwrap_or_default() > FLUID_MAX_LEVEL {
+            return Err(format!("&cfluid level must be below &4{}", FLUID_MAX_LEVEL + 1));
+        }
+    }
+
+    let Some(player_position) = world_manager
+        .get_ecs()
+        .get_entity(player)
+        .and_then(|e| e.get::<Position>().cloned())
+    else {
+        return Err("&cplayer entity not found".to_string());
+    };
+    if !is_within_reach(&player_position, position, rules.get_max_distance()) {
+        return Err("&cblock is too far away".to_string());
+    }
+    if !world_manager.is_inside_border(position) {
+        return Err("&cblock is outside of the world border".to_string());
+    }
+
+    if let Some(block_info) = new_block_info {
+        if !rules.is_allow_place_inside_players()
+            && is_obstructing(server_settings, block_info)
+            && is_intersects_players(world_manager, position)
+        {
+            return Err("&cblock intersects with a player".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Distance is counted from the middle of the player to the center of the block
+fn is_within_reach(player_position: &Position, position: &BlockPosition, max_distance: f32) -> bool {
+    let block = position.get_position();
+    let dx = player_position.get_x() - (block.x + 0.5);
+    let dy = player_position.get_y() + PLAYER_HEIGHT / 2.0 - (block.y + 0.5);
+    let dz = player_position.get_z() - (block.z + 0.5);
+    dx * dx + dy * dy + dz * dz <= max_distance * max_distance
+}
+
+/// Whether the block has a solid collider
+fn is_obstructing(server_settings: &ServerSettings, block_info: &BlockDataInfo) -> bool {
+    if server_settings.is_fluid(&block_info.get_id()) {
+        return false;
+    }
+    match server_settings.get_block_type(&block_info.get_id()) {
+        Some(block_type) => block_type.has_collider(),
+        None => false,
+    }
+}
+
+fn is_intersects_players(world_manager: &WorldManager, position: &BlockPosition) -> bool {
+    let block = position.get_position();
+    let chunk_position = position.get_chunk_position();
+
+    // Players standing at the edge of the neighbour chunk can also be inside of the block
+    for x in -1..=1 {
+        for z in -1..=1 {
+            let chunk = ChunkPosition::new(chunk_position.x + x, chunk_position.z + z);
+            let Ok(entities) = world_manager.get_ecs().get_chunk_entities(&chunk) else {
+                continue;
+            };
+            for entity in entities {
+                if !entity.contains::<ClientNetwork>() {
+                    continue;
+                }
+                let Some(player_position) = entity.get::<Position>() else {
+                    continue;
+                };
+                if player_position.get_x() + PLAYER_RADIUS > block.x
+                    && player_position.get_x() - PLAYER_RADIUS < block.x + 1.0
+                    && player_position.get_y() + PLAYER_HEIGHT > block.y
+                    && player_position.get_y() < block.y + 1.0
+                    && player_position.get_z() + PLAYER_RADIUS > block.z
+                    && player_position.get_z() - PLAYER_RADIUS < block.z + 1.0
+                {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
@@ -8,3 +8,4 @@ pub mod on_edit_block;
 pub mod on_media_loaded;
 pub mod on_settings_loaded;
 pub mod on_resources_has_cache;
+pub mod on_change_render_distance;
@@ -0,0 +1,60 @@
+use bevy::prelude::Event;
+use bevy_ecs::prelude::EventReader;
+use bevy_ecs::system::Res;
+
+use crate::launch_settings::LaunchSettings;
+use crate::network::client_network::ClientNetwork;
+use crate::network::sync_players::sync_player_chunks_change;
+use crate::worlds::worlds_manager::WorldsManager;
+
+#[derive(Event)]
+pub struct ChangeRenderDistanceEvent {
+    client: ClientNetwork,
+    render_distance: u16,
+}
+
+impl ChangeRenderDistanceEvent {
+    pub fn new(client: ClientNetwork, render_distance: u16) -> Self {
+        Self {
+            client,
+            render_distance,
+        }
+    }
+}
+
+pub fn on_change_render_distance(
+    mut events: EventReader<ChangeRenderDistanceEvent>,
+    worlds_manager: Res<WorldsManager>,
+    launch_settings: Res<LaunchSettings>,
+) {
+    for event in events.read() {
+        let render_distance = launch_settings.clamp_render_distance(event.render_distance);
+        if event.client.get_render_distance() == render_distance {
+            continue;
+        }
+        event.client.set_render_distance(render_distance);
+
+        log::debug!(
+            target: "network",
+            "Client ip:{} changed render distance to {}",
+            event.client.get_client_ip(),
+            render_distance
+        );
+
+        // If the player is not in the world yet, the distance
+        // will be used at spawn
+        let Some(world_entity) = event.client.get_world_entity() else {
+            continue;
+        };
+
+        let mut world_manager = worlds_manager
+            .get_world_manager_mut(&world_entity.get_world_slug())
+            .unwrap();
+
+        let change = world_manager.change_render_distance(&world_entity, render_distance);
+        event
+            .client
+            .send_unload_chunks(world_entity.get_world_slug(), change.abandoned_chunks.clone());
+        sync_player_chunks_change(&*world_manager, world_entity.get_entity(), &change);
+    }
+}
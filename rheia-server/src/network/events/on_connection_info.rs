@@ -4,6 +4,7 @@ use bevy_ecs::system::Res;
 use network::messages::{NetworkMessageType, ServerMessages};
 
 use crate::client_resources::resources_manager::ResourceManager;
+use crate::launch_settings::LaunchSettings;
 use crate::network::client_network::ClientInfo;
 use crate::network::client_network::ClientNetwork;
 use crate::network::events::on_media_loaded::PlayerMediaLoadedEvent;
@@ -15,6 +16,7 @@ pub struct PlayerConnectionInfoEvent {
     pub version: String,
     pub architecture: String,
     pub rendering_device: String,
+    pub render_distance: u16,
 }
 
 impl PlayerConnectionInfoEvent {
@@ -24,6 +26,7 @@ impl PlayerConnectionInfoEvent {
         version: String,
         architecture: String,
         rendering_device: String,
+        render_distance: u16,
     ) -> Self {
         Self {
             client,
@@ -31,6 +34,7 @@ impl PlayerConnectionInfoEvent {
             version,
             architecture,
             rendering_device,
+            render_distance,
         }
     }
 }
@@ -38,19 +42,24 @@ impl PlayerConnectionInfoEvent {
 pub fn on_connection_info(
     mut connection_info_events: EventReader<PlayerConnectionInfoEvent>,
     resources_manager: Res<ResourceManager>,
+    launch_settings: Res<LaunchSettings>,
     mut player_media_loaded_events: EventWriter<PlayerMediaLoadedEvent>,
 ) {
     for event in connection_info_events.read() {
         event.client.set_client_info(ClientInfo::new(&event));
+        event
+            .client
+            .set_render_distance(launch_settings.clamp_render_distance(event.render_distance));
 
         let client_info = event.client.get_client_info().unwrap();
         log::info!(
             target: "network",
-            "Connected &a{} &7ip:&e{} &7id:&e{}&r &7version:&e{}",
+            "Connected &a{} &7ip:&e{} &7id:&e{}&r &7version:&e{} &7render distance:&e{}",
             client_info.get_login(),
             event.client.get_client_ip(),
             event.client.get_client_id(),
             client_info.get_version(),
+            event.client.get_render_distance(),
         );
 
         if resources_manager.has_any_resources() {
@@ -6,7 +6,10 @@ use bevy_ecs::{
 
 use crate::{
     entities::skin::EntitySkinComponent,
-    network::{client_network::ClientNetwork, clients_container::ClientsContainer, sync_entities::sync_entity_despawn},
+    network::{
+        client_network::ClientNetwork, clients_container::ClientsContainer, player_data::PlayerDataStore,
+        sync_entities::sync_entity_despawn,
+    },
     worlds::worlds_manager::WorldsManager,
 };
 
@@ -26,6 +29,7 @@ pub fn on_disconnect(
     mut disconnection_events: EventReader<PlayerDisconnectEvent>,
     mut clients: ResMut<ClientsContainer>,
     worlds_manager: Res<WorldsManager>,
+    player_data_store: Res<PlayerDataStore>,
 ) {
     for event in disconnection_events.read() {
         if let Some(i) = event.client.get_client_info() {
@@ -37,6 +41,10 @@ pub fn on_disconnect(
             );
         }
 
+        if let Err(e) = player_data_store.save_client(&*worlds_manager, &event.client) {
+            log::error!(target: "network", "{}", e);
+        }
+
         // Check if player was in the world, despawn if so
         let world_entity = event.client.get_world_entity();
         match world_entity {
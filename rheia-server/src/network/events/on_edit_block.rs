@@ -1,11 +1,23 @@
-use bevy::prelude::{Event, ResMut};
+use bevy::prelude::{Event, Res, ResMut};
 use bevy_ecs::prelude::EventReader;
-use common::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+use common::{
+    chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo},
+    regions::RegionFlag,
+};
 use network::messages::{NetworkMessageType, ServerMessages};
 
 use crate::{
-    network::{client_network::ClientNetwork, sync_world_change::sync_world_block_change},
-    worlds::worlds_manager::WorldsManager,
+    client_resources::server_settings::ServerSettings,
+    launch_settings::LaunchSettings,
+    network::{
+        client_network::ClientNetwork, edit_validation::validate_edit_block, sync_world_change::sync_world_block_change,
+    },
+    worlds::{
+        audit_log::AuditLog,
+        block_ticks::{BlockTickHandlers, schedule_block_updates},
+        edit_history::{BlockChange, EditAction},
+        worlds_manager::WorldsManager,
+    },
 };
 
 #[derive(Event)]
@@ -32,7 +44,15 @@ impl EditBlockEvent {
     }
 }
 
-pub fn on_edit_block(mut edit_block_events: EventReader<EditBlockEvent>, worlds_manager: ResMut<WorldsManager>) {
+pub fn on_edit_block(
+    mut edit_block_events: EventReader<EditBlockEvent>,
+    worlds_manager: ResMut<WorldsManager>,
+    server_settings: Res<ServerSettings>,
+    block_tick_handlers: Res<BlockTickHandlers>,
+    launch_settings: Res<LaunchSettings>,
+    audit_log: Res<AuditLog>,
+) {
+    let rules = launch_settings.get_edit_block_rules();
     for event in edit_block_events.read() {
         let world_entity = event.client.get_world_entity();
         let world_entity = match world_entity.as_ref() {
@@ -60,14 +80,73 @@ pub fn on_edit_block(mut edit_block_events: EventReader<EditBlockEvent>, worlds_
             .get_world_manager(&world_entity.get_world_slug())
             .unwrap();
 
-        if let Err(e) = world_manager
-            .get_chunks_map()
-            .edit_block(event.position.clone(), event.new_block_info.clone())
-        {
-            let msg = ServerMessages::ConsoleOutput { message: e };
-            event.client.send_message(NetworkMessageType::ReliableOrdered, &msg);
-            return;
-        }
+        let validation = validate_edit_block(
+            &*world_manager,
+            &server_settings,
+            &rules,
+            world_entity.get_entity(),
+            &event.position,
+            &event.new_block_info,
+        );
+        let flag = match event.new_block_info {
+            Some(_) => RegionFlag::Build,
+            None => RegionFlag::Break,
+        };
+        // Edit of the client without the login is also reverted
+        let validation = validation.and_then(|_| match event.client.get_client_info() {
+            Some(info) => Ok(info.get_login().clone()),
+            None => Err("&cplayer is not logged in".to_string()),
+        });
+        let validation = validation.and_then(|login| {
+            world_manager
+                .get_regions()
+                .check_action(&event.position, &login, &flag)?;
+            Ok(login)
+        });
+        let old_block_info = world_manager.get_chunks_map().get_block_info(&event.position);
+        let result = validation.and_then(|login| {
+            world_manager
+                .get_chunks_map()
+                .edit_block(event.position.clone(), event.new_block_info.clone())?;
+            Ok(login)
+        });
+        let login = match result {
+            Ok(login) => login,
+            Err(e) => {
+                log::warn!(
+                    target: "network",
+                    "Client ip:{} edit block {:?} rejected: {}",
+                    event.client.get_client_ip(),
+                    event.position,
+                    e
+                );
+
+                // Restore the actual state of the block on the client
+                let msg = ServerMessages::EditBlock {
+                    world_slug: world_manager.get_slug().clone(),
+                    position: event.position.clone(),
+                    new_block_info: world_manager.get_chunks_map().get_block_info(&event.position),
+                };
+                event.client.send_message(NetworkMessageType::WorldInfo, &msg);
+
+                let msg = ServerMessages::ConsoleOutput { message: e };
+                event.client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+                continue;
+            }
+        };
+        let change = BlockChange::new(event.position.clone(), old_block_info, event.new_block_info.clone());
+        audit_log.record(&login, world_manager.get_slug(), &change);
+        event
+            .client
+            .get_edit_history_mut()
+            .push(EditAction::new(world_manager.get_slug().clone(), vec![change]));
+
+        schedule_block_updates(
+            world_manager.get_chunks_map(),
+            &server_settings,
+            &block_tick_handlers,
+            &event.position,
+        );
         sync_world_block_change(&*world_manager, event.position, event.new_block_info)
     }
 }
@@ -1,11 +1,14 @@
 use bevy::prelude::Event;
 use bevy_ecs::prelude::EventReader;
-use bevy_ecs::system::ResMut;
+use bevy_ecs::system::{Res, ResMut};
 use common::chunks::block_position::BlockPositionTrait;
 
+use crate::client_resources::server_settings::ServerSettings;
 use crate::entities::entity::Rotation;
 use crate::network::client_network::{ClientNetwork, WorldEntity};
+use crate::network::movement_validation::validate_player_move;
 use crate::network::sync_players::sync_player_move;
+use crate::worlds::border::{get_border_correction, push_inside_border};
 use crate::worlds::world_manager::WorldManager;
 use crate::{entities::entity::Position, worlds::worlds_manager::WorldsManager};
 
@@ -26,7 +29,11 @@ impl PlayerMoveEvent {
     }
 }
 
-pub fn on_player_move(mut player_move_events: EventReader<PlayerMoveEvent>, worlds_manager: ResMut<WorldsManager>) {
+pub fn on_player_move(
+    mut player_move_events: EventReader<PlayerMoveEvent>,
+    worlds_manager: ResMut<WorldsManager>,
+    server_settings: Res<ServerSettings>,
+) {
     for event in player_move_events.read() {
         let world_entity = event.client.get_world_entity();
         let world_entity = match world_entity.as_ref() {
@@ -45,6 +52,30 @@ pub fn on_player_move(mut player_move_events: EventReader<PlayerMoveEvent>, worl
             .get_world_manager_mut(&world_entity.get_world_slug())
             .unwrap();
 
+        // Chunks outside of the border are never loaded, so it's checked first
+        if let Some(border) = world_manager.get_border() {
+            if get_border_correction(border, &event.position).is_some() {
+                log::warn!(
+                    target: "network",
+                    "Client {} tries to move outside of the world border",
+                    event.client
+                );
+
+                // Old position is outside too if the border was shrunk
+                if !push_inside_border(&mut *world_manager, &event.client) {
+                    let old_position = *world_manager
+                        .get_ecs()
+                        .get_entity(world_entity.get_entity())
+                        .unwrap()
+                        .get::<Position>()
+                        .unwrap();
+                    event.client.get_movement_state_mut().reset();
+                    event.client.send_teleport(&old_position);
+                }
+                continue;
+            }
+        }
+
         if !world_manager
             .get_chunks_map()
             .is_chunk_loaded(&event.position.get_chunk_position())
@@ -56,6 +87,31 @@ pub fn on_player_move(mut player_move_events: EventReader<PlayerMoveEvent>, worl
             );
             continue;
         }
+
+        let ecs = world_manager.get_ecs();
+        let old_position = *ecs
+            .get_entity(world_entity.get_entity())
+            .unwrap()
+            .get::<Position>()
+            .unwrap();
+        let validation = validate_player_move(
+            &*world_manager,
+            &server_settings,
+            &mut event.client.get_movement_state_mut(),
+            &old_position,
+            &event.position,
+        );
+        if let Err(e) = validation {
+            log::warn!(
+                target: "network",
+                "Client {} invalid movement: {}",
+                event.client, e
+            );
+
+            // Teleport the player back to the last valid position
+            event.client.send_teleport(&old_position);
+            continue;
+        }
         move_player(&mut *world_manager, world_entity, event.position, event.rotation);
     }
 }
@@ -1,11 +1,7 @@
 use crate::{
-    entities::{
-        entity::{Position, Rotation},
-        entity_tag::EntityTagComponent,
-        skin::EntitySkinComponent,
-        EntityComponent,
-    },
-    network::client_network::ClientNetwork,
+    client_resources::server_settings::ServerSettings,
+    entities::{EntityComponent, entity::Rotation, entity_tag::EntityTagComponent, skin::EntitySkinComponent},
+    network::{client_network::ClientNetwork, player_data::PlayerDataStore},
 };
 use bevy::prelude::{Commands, Event, Res};
 use bevy_ecs::prelude::EventReader;
@@ -28,26 +24,53 @@ pub fn on_settings_loaded(
     mut commands: Commands,
     mut events: EventReader<PlayerSettingsLoadedEvent>,
     worlds_manager: Res<WorldsManager>,
+    server_settings: Res<ServerSettings>,
+    player_data_store: Res<PlayerDataStore>,
 ) {
     for event in events.read() {
+        let login = event.client.get_client_info().unwrap().get_login().clone();
+        let player_data = match player_data_store.load(&login) {
+            Ok(d) => d,
+            Err(e) => {
+                log::error!(target: "network", "{}", e);
+                None
+            }
+        };
+
+        // Saved world may be removed since the last connection
+        if let Some(player_data) = player_data {
+            if let Some(world_manager) = worlds_manager.get_world_manager(player_data.get_world_slug()) {
+                let position =
+                    world_manager.get_safe_teleport_position(&server_settings, player_data.get_position().clone());
+                commands.queue(SpawnPlayer::create(
+                    player_data.get_world_slug().clone(),
+                    event.client.clone(),
+                    position,
+                    player_data.get_rotation().clone(),
+                    player_data.get_components().clone(),
+                ));
+                continue;
+            }
+        }
+
         let default_world = "default".to_string();
-        if !worlds_manager.has_world_with_slug(&default_world) {
+        let Some(world_manager) = worlds_manager.get_world_manager(&default_world) else {
             panic!("default world is not found");
         };
+        let position = world_manager.get_safe_spawn_position(&server_settings);
 
         let mut components: Vec<EntityComponent> = Default::default();
 
         let skin = EntitySkinComponent::create(NetworkEntitySkin::Generic);
         components.push(EntityComponent::Skin(Some(skin)));
 
-        let client_info = event.client.get_client_info().unwrap();
-        let tag = EntityTagComponent::create(NetworkEntityTag::create(client_info.get_login().clone(), 2.1, 32, 3));
+        let tag = EntityTagComponent::create(NetworkEntityTag::create(login, 2.1, 32, 3));
         components.push(EntityComponent::Tag(Some(tag)));
 
         commands.queue(SpawnPlayer::create(
             default_world,
             event.client.clone(),
-            Position::new(0.0, 80.0, 0.0),
+            position,
             Rotation::new(0.0, 0.0),
             components,
         ));
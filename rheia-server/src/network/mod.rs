@@ -6,4 +6,11 @@ pub mod clients_container;
 pub mod chunks_sender;
 pub mod sync_entities;
 pub mod sync_world_change;
+pub mod sync_world_time;
+pub mod sync_world_weather;
+pub mod sync_world_border;
 pub mod sync_players;
+pub mod edit_validation;
+pub mod rate_limiter;
+pub mod movement_validation;
+pub mod player_data;
@@ -0,0 +1,185 @@
+use common::{
+    PLAYER_GRAVITY, PLAYER_HEIGHT, PLAYER_JUMP_SPEED, PLAYER_MASS, PLAYER_MOVEMENT_SPEED, PLAYER_RADIUS,
+    chunks::{block_position::BlockPosition, position::Vector3},
+};
+use std::time::{Duration, Instant};
+
+use crate::{
+    client_resources::server_settings::ServerSettings, entities::entity::Position, worlds::world_manager::WorldManager,
+};
+
+// Network jitter allowance for the distance passed between packets
+const SPEED_TOLERANCE: f32 = 1.5;
+const SPEED_MARGIN: f32 = 0.5;
+const JUMP_MARGIN: f32 = 0.5;
+
+// Packets can be delayed, but a long pause doesn't allow to move further
+const MIN_MOVE_ELAPSED: Duration = Duration::from_millis(50);
+const MAX_MOVE_ELAPSED: Duration = Duration::from_secs(1);
+
+// The collider is shrunk to ignore the blocks the player is touching
+const COLLIDER_TOLERANCE: f32 = 0.05;
+
+// Distance between the checked positions of the movement, less than a block
+const SWEEP_STEP: f32 = 0.25;
+
+/// Movement of the player between his packets
+#[derive(Default)]
+pub struct MovementState {
+    last_move: Option<Instant>,
+
+    // Height gained since the player was standing on the ground
+    rise: f32,
+}
+
+impl MovementState {
+    /// Must be called after the player was moved by the server
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+/// Maximum height of the jump with the controller gravity
+fn get_jump_height() -> f32 {
+    let gravity = PLAYER_GRAVITY.abs() * PLAYER_MASS;
+    PLAYER_JUMP_SPEED * PLAYER_JUMP_SPEED / (2.0 * gravity)
+}
+
+fn is_solid(world_manager: &WorldManager, server_settings: &ServerSettings, position: &BlockPosition) -> bool {
+    let Some(block_info) = world_manager.get_chunks_map().get_block_info(position) else {
+        return false;
+    };
+    if server_settings.is_fluid(&block_info.get_id()) {
+        return false;
+    }
+    match server_settings.get_block_type(&block_info.get_id()) {
+        Some(block_type) => block_type.has_collider(),
+        None => false,
+    }
+}
+
+/// Blocks of the region covered by the player's collider
+fn get_collider_blocks(min: Vector3, max: Vector3) -> impl Iterator<Item = BlockPosition> {
+    let (min_x, max_x) = (
+        (min.x - PLAYER_RADIUS).floor() as i64,
+        (max.x + PLAYER_RADIUS).floor() as i64,
+    );
+    let (min_y, max_y) = (min.y.floor() as i64, (max.y + PLAYER_HEIGHT).floor() as i64);
+    let (min_z, max_z) = (
+        (min.z - PLAYER_RADIUS).floor() as i64,
+        (max.z + PLAYER_RADIUS).floor() as i64,
+    );
+    (min_x..=max_x)
+        .flat_map(move |x| (min_y..=max_y).flat_map(move |y| (min_z..=max_z).map(move |z| BlockPosition::new(x, y, z))))
+}
+
+/// Whether there is a solid block right under the player's feet
+fn is_grounded(world_manager: &WorldManager, server_settings: &ServerSettings, position: &Position) -> bool {
+    let min = Vector3::new(
+        position.get_x(),
+        position.get_y() - COLLIDER_TOLERANCE,
+        position.get_z(),
+    );
+    let mut blocks = get_collider_blocks(min, min).filter(|b| b.get_y() == min.y.floor() as i64);
+    blocks.any(|b| is_solid(world_manager, server_settings, &b))
+}
+
+/// Whether the player's cylinder at the position intersects any solid block
+fn is_colliding(world_manager: &WorldManager, server_settings: &ServerSettings, position: &Vector3) -> bool {
+    let radius = PLAYER_RADIUS - COLLIDER_TOLERANCE;
+    let bottom = position.y + COLLIDER_TOLERANCE;
+    let top = position.y + PLAYER_HEIGHT - COLLIDER_TOLERANCE;
+    for block in get_collider_blocks(*position, *position) {
+        let (x, y, z) = (block.get_x() as f32, block.get_y() as f32, block.get_z() as f32);
+        if y + 1.0 <= bottom || y >= top {
+            continue;
+        }
+        let dx = position.x - position.x.clamp(x, x + 1.0);
+        let dz = position.z - position.z.clamp(z, z + 1.0);
+        if dx * dx + dz * dz >= radius * radius {
+            continue;
+        }
+        if is_solid(world_manager, server_settings, &block) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Sweeps the player's collider along the movement over the blocks of the loaded chunks
+fn is_path_blocked(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    old_position: &Position,
+    new_position: &Position,
+) -> bool {
+    let old = old_position.to_network();
+    let new = new_position.to_network();
+
+    // The player who is stuck inside of blocks must be able to get out
+    if is_colliding(world_manager, server_settings, &old) {
+        return false;
+    }
+
+    let (dx, dy, dz) = (new.x - old.x, new.y - old.y, new.z - old.z);
+    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+    let steps = (distance / SWEEP_STEP).ceil().max(1.0) as u32;
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        let position = Vector3::new(old.x + dx * t, old.y + dy * t, old.z + dz * t);
+        if is_colliding(world_manager, server_settings, &position) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Checks the speed, jump height and collisions of the player's movement
+pub fn validate_player_move(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    state: &mut MovementState,
+    old_position: &Position,
+    new_position: &Position,
+) -> Result<(), String> {
+    let now = Instant::now();
+    let elapsed = match state.last_move {
+        Some(last_move) => now.duration_since(last_move).clamp(MIN_MOVE_ELAPSED, MAX_MOVE_ELAPSED),
+        None => MAX_MOVE_ELAPSED,
+    };
+    let seconds = elapsed.as_secs_f32();
+
+    let dx = new_position.get_x() - old_position.get_x();
+    let dy = new_position.get_y() - old_position.get_y();
+    let dz = new_position.get_z() - old_position.get_z();
+
+    let horizontal = (dx * dx + dz * dz).sqrt();
+    let max_horizontal = PLAYER_MOVEMENT_SPEED * seconds * SPEED_TOLERANCE + SPEED_MARGIN;
+    if horizontal > max_horizontal {
+        return Err(format!("moved too fast: {:.2} > {:.2}", horizontal, max_horizontal));
+    }
+
+    let max_vertical = PLAYER_JUMP_SPEED * seconds * SPEED_TOLERANCE + SPEED_MARGIN;
+    if dy > max_vertical {
+        return Err(format!("moved up too fast: {:.2} > {:.2}", dy, max_vertical));
+    }
+
+    let rise = match is_grounded(world_manager, server_settings, old_position) {
+        true => dy.max(0.0),
+        false => state.rise + dy.max(0.0),
+    };
+    if rise > get_jump_height() + JUMP_MARGIN {
+        return Err(format!("jumped too high: {:.2}", rise));
+    }
+
+    if is_path_blocked(world_manager, server_settings, old_position, new_position) {
+        return Err("moved through solid blocks".to_string());
+    }
+
+    state.last_move = Some(now);
+    state.rise = match is_grounded(world_manager, server_settings, new_position) {
+        true => 0.0,
+        false => rise,
+    };
+    Ok(())
+}
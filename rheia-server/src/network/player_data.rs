@@ -0,0 +1,248 @@
+use bevy::prelude::{Res, ResMut, Resource};
+use bevy::time::Time;
+use common::chunks::{position::Vector3 as NetworkVector3, rotation::Rotation as NetworkRotation};
+use network::entities::EntityNetworkComponent;
+use parking_lot::Mutex;
+use rusqlite::{Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::{
+    entities::{
+        EntityComponent,
+        entity::{IntoServerPosition, IntoServerRotation, Position, Rotation},
+        entity_tag::EntityTagComponent,
+        skin::EntitySkinComponent,
+    },
+    worlds::{world_manager::WorldManager, worlds_manager::WorldsManager},
+};
+
+use super::client_network::{ClientNetwork, WorldEntity};
+use super::clients_container::ClientsContainer;
+
+/// How often the data of the online players is saved
+pub const PLAYERS_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+// New player fields (inventory, stats) are added as new columns
+const SQL_CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS players (login TEXT PRIMARY KEY, world TEXT, \
+    x REAL, y REAL, z REAL, yaw REAL, pitch REAL, components BLOB, saved_at INTEGER);";
+const SQL_SELECT: &str = "SELECT world, x, y, z, yaw, pitch, components FROM players WHERE login=?1;";
+const SQL_REPLACE: &str = "INSERT OR REPLACE INTO players (login, world, x, y, z, yaw, pitch, components, saved_at) \
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9);";
+
+/// State of the player which is restored on the next connection
+#[derive(Clone)]
+pub struct PlayerData {
+    world_slug: String,
+    position: Position,
+    rotation: Rotation,
+    components: Vec<EntityComponent>,
+}
+
+impl PlayerData {
+    pub fn create(
+        world_slug: String,
+        position: Position,
+        rotation: Rotation,
+        components: Vec<EntityComponent>,
+    ) -> Self {
+        Self {
+            world_slug,
+            position,
+            rotation,
+            components,
+        }
+    }
+
+    /// Reads the player's entity from the world
+    pub fn from_world(world_manager: &WorldManager, world_entity: &WorldEntity) -> Option<Self> {
+        let entity_ref = world_manager.get_ecs().get_entity(world_entity.get_entity())?;
+        let mut components: Vec<EntityComponent> = Default::default();
+        if let Some(tag) = entity_ref.get::<EntityTagComponent>() {
+            components.push(EntityComponent::Tag(Some(tag.clone())));
+        }
+        if let Some(skin) = entity_ref.get::<EntitySkinComponent>() {
+            components.push(EntityComponent::Skin(Some(skin.clone())));
+        }
+        Some(Self::create(
+            world_manager.get_slug().clone(),
+            entity_ref.get::<Position>()?.clone(),
+            entity_ref.get::<Rotation>()?.clone(),
+            components,
+        ))
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn get_rotation(&self) -> &Rotation {
+        &self.rotation
+    }
+
+    pub fn get_components(&self) -> &Vec<EntityComponent> {
+        &self.components
+    }
+}
+
+/// Server-level storage of the players' data by their logins
+#[derive(Resource)]
+pub struct PlayerDataStore {
+    db: Mutex<Connection>,
+    autosave_timer: Duration,
+}
+
+impl PlayerDataStore {
+    pub fn create(path: PathBuf) -> Result<Self, String> {
+        let db = match Connection::open(path.clone()) {
+            Ok(db) => db,
+            Err(e) => return Err(format!("Players db \"{}\" open error: &c{}", path.display(), e)),
+        };
+        if let Err(e) = db.execute(SQL_CREATE_TABLE, ()) {
+            return Err(format!("Players table create error: &c{}", e));
+        }
+        Ok(Self {
+            db: Mutex::new(db),
+            autosave_timer: Duration::ZERO,
+        })
+    }
+
+    pub fn load(&self, login: &String) -> Result<Option<PlayerData>, String> {
+        let row = self
+            .db
+            .lock()
+            .query_row(SQL_SELECT, (login,), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    NetworkVector3::new(row.get(1)?, row.get(2)?, row.get(3)?),
+                    NetworkRotation::new(row.get(4)?, row.get(5)?),
+                    row.get::<_, Vec<u8>>(6)?,
+                ))
+            })
+            .optional();
+        let (world_slug, position, rotation, components) = match row {
+            Ok(Some(r)) => r,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(format!("Player \"{}\" load error: &c{}", login, e)),
+        };
+        let components: Vec<EntityNetworkComponent> = match bincode::deserialize(&components) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("Player \"{}\" components decode error: &c{}", login, e)),
+        };
+        Ok(Some(PlayerData::create(
+            world_slug,
+            position.to_server(),
+            rotation.to_server(),
+            components.into_iter().map(EntityComponent::from_network).collect(),
+        )))
+    }
+
+    pub fn save(&self, login: &String, data: &PlayerData) -> Result<(), String> {
+        let components: Vec<EntityNetworkComponent> = data.components.iter().map(|c| c.to_network()).collect();
+        let position = data.position.to_network();
+        let rotation = data.rotation.to_network();
+        let result = self.db.lock().execute(
+            SQL_REPLACE,
+            (
+                login,
+                &data.world_slug,
+                position.x,
+                position.y,
+                position.z,
+                rotation.yaw,
+                rotation.pitch,
+                bincode::serialize(&components).unwrap(),
+                chrono::Utc::now().timestamp(),
+            ),
+        );
+        if let Err(e) = result {
+            return Err(format!("Player \"{}\" save error: &c{}", login, e));
+        }
+        Ok(())
+    }
+
+    /// Saves the player only if the player is inside of a world
+    pub fn save_client(&self, worlds_manager: &WorldsManager, client: &ClientNetwork) -> Result<(), String> {
+        let Some(login) = client.get_client_info().map(|i| i.get_login().clone()) else {
+            return Ok(());
+        };
+        let Some(world_entity) = client.get_world_entity() else {
+            return Ok(());
+        };
+        let Some(world_manager) = worlds_manager.get_world_manager(world_entity.get_world_slug()) else {
+            return Ok(());
+        };
+        match PlayerData::from_world(&*world_manager, &world_entity) {
+            Some(data) => self.save(&login, &data),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the count of the saved players
+    pub fn save_all(&self, worlds_manager: &WorldsManager, clients: &ClientsContainer) -> usize {
+        let mut saved = 0;
+        for (_client_id, client) in clients.iter() {
+            match self.save_client(worlds_manager, client) {
+                Ok(()) => saved += 1,
+                Err(e) => log::error!(target: "network", "{}", e),
+            }
+        }
+        saved
+    }
+}
+
+pub fn autosave_players(
+    mut store: ResMut<PlayerDataStore>,
+    worlds_manager: Res<WorldsManager>,
+    clients: Res<ClientsContainer>,
+    time: Res<Time>,
+) {
+    store.autosave_timer += time.delta();
+    if store.autosave_timer < PLAYERS_AUTOSAVE_INTERVAL {
+        return;
+    }
+    store.autosave_timer = Duration::ZERO;
+
+    let now = std::time::Instant::now();
+    let saved = store.save_all(&*worlds_manager, &*clients);
+    if saved > 0 {
+        log::debug!(target: "network", "Players saved: {} (executed:{:.2?})", saved, now.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayerData, PlayerDataStore};
+    use crate::entities::{
+        EntityComponent,
+        entity::{Position, Rotation},
+        skin::EntitySkinComponent,
+    };
+    use network::messages::NetworkEntitySkin;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_player_data() {
+        let store = PlayerDataStore::create(PathBuf::from(":memory:")).unwrap();
+        let login = "player".to_string();
+        assert!(store.load(&login).unwrap().is_none());
+
+        let skin = EntitySkinComponent::create(NetworkEntitySkin::Generic);
+        let data = PlayerData::create(
+            "default".to_string(),
+            Position::new(1.0, 70.5, -3.0),
+            Rotation::new(0.5, 1.0),
+            vec![EntityComponent::Skin(Some(skin))],
+        );
+        store.save(&login, &data).unwrap();
+
+        let loaded = store.load(&login).unwrap().unwrap();
+        assert_eq!(loaded.get_world_slug(), "default");
+        assert!(*loaded.get_position() == Position::new(1.0, 70.5, -3.0));
+        assert_eq!(loaded.get_components().len(), 1);
+    }
+}
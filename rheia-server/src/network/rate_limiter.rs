@@ -0,0 +1,249 @@
+use ahash::AHashMap;
+use network::messages::ClientMessages;
+use std::time::Duration;
+
+// Client sends the movement 20 times per second
+pub const MOVE_RATE_LIMIT: u32 = 120;
+pub const EDIT_BLOCK_RATE_LIMIT: u32 = 20;
+pub const CONSOLE_RATE_LIMIT: u32 = 5;
+pub const OTHER_RATE_LIMIT: u32 = 100;
+pub const RATE_LIMIT_KICK_THRESHOLD: u32 = 100;
+
+// How many seconds of messages can be sent at once
+const BURST_SECONDS: f32 = 2.0;
+
+// How fast dropped messages are forgiven per second
+const VIOLATIONS_DECAY: f32 = 5.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ClientMessageKind {
+    PlayerMove,
+    EditBlock,
+    Console,
+    Other,
+}
+
+impl ClientMessageKind {
+    pub fn from_message(message: &ClientMessages) -> Self {
+        match message {
+            ClientMessages::PlayerMove { .. } => ClientMessageKind::PlayerMove,
+            ClientMessages::EditBlockRequest { .. } => ClientMessageKind::EditBlock,
+            ClientMessages::ConsoleInput { .. } => ClientMessageKind::Console,
+            _ => ClientMessageKind::Other,
+        }
+    }
+}
+
+/// Messages per second allowed for each message kind;
+/// zero disables the limit
+#[derive(Clone, Debug)]
+pub struct RateLimits {
+    move_limit: u32,
+    edit_block_limit: u32,
+    console_limit: u32,
+    other_limit: u32,
+
+    // Dropped messages after which the client will be kicked
+    kick_threshold: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            move_limit: MOVE_RATE_LIMIT,
+            edit_block_limit: EDIT_BLOCK_RATE_LIMIT,
+            console_limit: CONSOLE_RATE_LIMIT,
+            other_limit: OTHER_RATE_LIMIT,
+            kick_threshold: RATE_LIMIT_KICK_THRESHOLD,
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn move_limit(mut self, limit: u32) -> Self {
+        self.move_limit = limit;
+        self
+    }
+
+    pub fn edit_block_limit(mut self, limit: u32) -> Self {
+        self.edit_block_limit = limit;
+        self
+    }
+
+    pub fn console_limit(mut self, limit: u32) -> Self {
+        self.console_limit = limit;
+        self
+    }
+
+    pub fn other_limit(mut self, limit: u32) -> Self {
+        self.other_limit = limit;
+        self
+    }
+
+    pub fn kick_threshold(mut self, threshold: u32) -> Self {
+        self.kick_threshold = threshold;
+        self
+    }
+
+    pub fn get_limit(&self, kind: &ClientMessageKind) -> u32 {
+        match kind {
+            ClientMessageKind::PlayerMove => self.move_limit,
+            ClientMessageKind::EditBlock => self.edit_block_limit,
+            ClientMessageKind::Console => self.console_limit,
+            ClientMessageKind::Other => self.other_limit,
+        }
+    }
+
+    pub fn get_kick_threshold(&self) -> u32 {
+        self.kick_threshold
+    }
+}
+
+struct TokenBucket {
+    tokens: f32,
+    per_second: f32,
+}
+
+impl TokenBucket {
+    fn new(per_second: u32) -> Self {
+        let per_second = per_second as f32;
+        Self {
+            tokens: per_second * BURST_SECONDS,
+            per_second,
+        }
+    }
+
+    fn refill(&mut self, delta: Duration) {
+        self.tokens = (self.tokens + self.per_second * delta.as_secs_f32()).min(self.per_second * BURST_SECONDS);
+    }
+
+    fn try_take(&mut self) -> bool {
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum RateLimitResult {
+    Allowed,
+    Dropped,
+    Kick,
+}
+
+/// Token buckets of the single client
+#[derive(Default)]
+pub struct ClientRateLimiter {
+    buckets: AHashMap<ClientMessageKind, TokenBucket>,
+    violations: f32,
+    kicked: bool,
+}
+
+impl ClientRateLimiter {
+    pub fn update(&mut self, delta: Duration) {
+        for bucket in self.buckets.values_mut() {
+            bucket.refill(delta);
+        }
+        self.violations = (self.violations - VIOLATIONS_DECAY * delta.as_secs_f32()).max(0.0);
+    }
+
+    pub fn check(&mut self, kind: ClientMessageKind, limits: &RateLimits) -> RateLimitResult {
+        if self.kicked {
+            return RateLimitResult::Dropped;
+        }
+
+        let limit = limits.get_limit(&kind);
+        if limit == 0 {
+            return RateLimitResult::Allowed;
+        }
+
+        let bucket = self.buckets.entry(kind).or_insert_with(|| TokenBucket::new(limit));
+        if bucket.try_take() {
+            return RateLimitResult::Allowed;
+        }
+
+        self.violations += 1.0;
+        if limits.get_kick_threshold() > 0 && self.violations > limits.get_kick_threshold() as f32 {
+            self.kicked = true;
+            return RateLimitResult::Kick;
+        }
+        RateLimitResult::Dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientMessageKind, ClientRateLimiter, RateLimitResult, RateLimits};
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limiter_drop() {
+        let limits = RateLimits::default().edit_block_limit(2).kick_threshold(0);
+        let mut limiter = ClientRateLimiter::default();
+
+        // Burst allows two seconds of messages
+        for _ in 0..4 {
+            assert_eq!(
+                limiter.check(ClientMessageKind::EditBlock, &limits),
+                RateLimitResult::Allowed
+            );
+        }
+        assert_eq!(
+            limiter.check(ClientMessageKind::EditBlock, &limits),
+            RateLimitResult::Dropped
+        );
+
+        // Other kinds have their own buckets
+        assert_eq!(
+            limiter.check(ClientMessageKind::PlayerMove, &limits),
+            RateLimitResult::Allowed
+        );
+
+        limiter.update(Duration::from_millis(500));
+        assert_eq!(
+            limiter.check(ClientMessageKind::EditBlock, &limits),
+            RateLimitResult::Allowed
+        );
+        assert_eq!(
+            limiter.check(ClientMessageKind::EditBlock, &limits),
+            RateLimitResult::Dropped
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_steady_move() {
+        let limits = RateLimits::default();
+        let mut limiter = ClientRateLimiter::default();
+
+        // A minute of the movement at 30 messages per second
+        for _ in 0..30 * 60 {
+            limiter.update(Duration::from_secs_f32(1.0 / 30.0));
+            assert_eq!(
+                limiter.check(ClientMessageKind::PlayerMove, &limits),
+                RateLimitResult::Allowed
+            );
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_kick() {
+        let limits = RateLimits::default().console_limit(1).kick_threshold(3);
+        let mut limiter = ClientRateLimiter::default();
+
+        let mut results = Vec::new();
+        for _ in 0..6 {
+            results.push(limiter.check(ClientMessageKind::Console, &limits));
+        }
+        assert_eq!(results[1], RateLimitResult::Allowed);
+        assert_eq!(results[4], RateLimitResult::Dropped);
+        assert_eq!(results[5], RateLimitResult::Kick);
+
+        // Kick is reported only once
+        assert_eq!(
+            limiter.check(ClientMessageKind::Console, &limits),
+            RateLimitResult::Dropped
+        );
+    }
+}
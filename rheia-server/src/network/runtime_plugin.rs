@@ -3,12 +3,14 @@ use bevy_app::AppExit;
 use bevy_app::{First, Startup};
 use bevy_ecs::system::{Res, ResMut};
 use lazy_static::lazy_static;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
 use crate::console::console_handler::ConsoleHandler;
 use crate::worlds::worlds_manager::WorldsManager;
 
 use super::clients_container::ClientsContainer;
+use super::player_data::PlayerDataStore;
 
 lazy_static! {
     static ref SERVER_STATE: Arc<RwLock<ServerState>> = Arc::new(RwLock::new(ServerState::STARTED));
@@ -51,6 +53,19 @@ impl RuntimePlugin {
         *state = ServerState::STOPPING;
     }
 
+    /// Opens the database of the server data; if it fails, the server is stopped
+    /// and the in-memory database keeps the systems working until the stop
+    pub(crate) fn open_database<T, F: Fn(PathBuf) -> Result<T, String>>(path: PathBuf, open: F) -> T {
+        match open(path) {
+            Ok(db) => db,
+            Err(e) => {
+                log::error!(target: "main", "{}", e);
+                RuntimePlugin::stop();
+                open(PathBuf::from(":memory:")).unwrap()
+            }
+        }
+    }
+
     pub(crate) fn is_stopping() -> bool {
         let state = SERVER_STATE.write().unwrap();
         *state == ServerState::STOPPING
@@ -78,11 +93,15 @@ fn update_runtime(
     mut clients: ResMut<ClientsContainer>,
     mut console_handler: ResMut<ConsoleHandler>,
     worlds_manager: Res<WorldsManager>,
+    player_data_store: Res<PlayerDataStore>,
 ) {
     if RuntimePlugin::is_stopping() {
         log::info!(target: "main", "Server shutdown...");
+        player_data_store.save_all(&*worlds_manager, &*clients);
         clients.disconnect_all(Some("Server shutting down".to_string()));
-        worlds_manager.save_all().unwrap();
+        if let Err(e) = worlds_manager.save_all() {
+            log::error!(target: "main", "{}", e);
+        }
         console_handler.handle_stop_server();
         app_exit_events.write(AppExit::Success);
         RuntimePlugin::set_stoped();
@@ -1,4 +1,5 @@
 use super::events::{
+    on_change_render_distance::{ChangeRenderDistanceEvent, on_change_render_distance},
     on_connection::{PlayerConnectionEvent, on_connection},
     on_connection_info::{PlayerConnectionInfoEvent, on_connection_info},
     on_disconnect::{PlayerDisconnectEvent, on_disconnect},
@@ -13,6 +14,9 @@ use crate::entities::events::on_player_spawn::on_player_spawn;
 use crate::network::chunks_sender::send_chunks;
 use crate::network::client_network::ClientNetwork;
 use crate::network::clients_container::ClientsContainer;
+use crate::network::player_data::{PlayerDataStore, autosave_players};
+use crate::network::rate_limiter::{ClientMessageKind, RateLimitResult};
+use crate::network::runtime_plugin::RuntimePlugin;
 use crate::network::sync_players::PlayerSpawnEvent;
 use crate::{LaunchSettings, console::commands_executer::CommandsHandler};
 use bevy::time::Time;
@@ -70,6 +74,12 @@ impl NetworkPlugin {
         app.insert_resource(NetworkContainer::new(ip_port));
         app.insert_resource(ClientsContainer::default());
 
+        let mut players_path = server_settings.get_server_data_path();
+        players_path.push("players.db");
+        let player_data_store = RuntimePlugin::open_database(players_path, PlayerDataStore::create);
+        app.insert_resource(player_data_store);
+        app.add_systems(Update, autosave_players);
+
         app.add_systems(Update, receive_message_system);
         app.add_systems(Update, handle_events_system);
         app.add_systems(Update, send_chunks.after(handle_events_system));
@@ -100,6 +110,9 @@ impl NetworkPlugin {
         app.add_event::<PlayerSettingsLoadedEvent>();
         app.add_systems(Update, on_settings_loaded.after(handle_events_system));
 
+        app.add_event::<ChangeRenderDistanceEvent>();
+        app.add_systems(Update, on_change_render_distance.after(handle_events_system));
+
         app.add_event::<PlayerSpawnEvent>();
         app.add_systems(Update, on_player_spawn);
     }
@@ -114,12 +127,14 @@ fn receive_message_system(
     network_container: Res<NetworkContainer>,
     time: Res<Time>,
     clients: Res<ClientsContainer>,
+    launch_settings: Res<LaunchSettings>,
     mut resources_has_cache_events: EventWriter<ResourcesHasCacheEvent>,
     mut connection_info_events: EventWriter<PlayerConnectionInfoEvent>,
     mut player_move_events: EventWriter<PlayerMoveEvent>,
     mut edit_block_events: EventWriter<EditBlockEvent>,
     mut player_media_loaded_events: EventWriter<PlayerMediaLoadedEvent>,
     mut settings_loaded_events: EventWriter<PlayerSettingsLoadedEvent>,
+    mut render_distance_events: EventWriter<ChangeRenderDistanceEvent>,
 ) {
     #[cfg(feature = "trace")]
     let _span = bevy_utils::tracing::info_span!("receive_message_system").entered();
@@ -138,8 +153,26 @@ fn receive_message_system(
         log::error!(target: "network", "Network error: {}", message);
     }
 
+    let limits = launch_settings.get_rate_limits();
     for (client_id, client) in clients.iter() {
+        client.update_rate_limiter(time.delta());
         for decoded in client.get_connection().drain_client_messages() {
+            let kind = ClientMessageKind::from_message(&decoded);
+            match client.check_rate_limit(kind, &limits) {
+                RateLimitResult::Allowed => (),
+                RateLimitResult::Dropped => continue,
+                RateLimitResult::Kick => {
+                    log::warn!(
+                        target: "network",
+                        "Client {} was kicked for flooding with {:?} messages",
+                        client,
+                        kind
+                    );
+                    client.clone().send_disconnect(Some("Too many messages".to_string()));
+                    continue;
+                }
+            }
+
             match decoded {
                 ClientMessages::ResourcesHasCache { exists } => {
                     let event = ResourcesHasCacheEvent::new(client.clone(), exists);
@@ -168,9 +201,16 @@ fn receive_message_system(
                     version,
                     architecture,
                     rendering_device,
+                    render_distance,
                 } => {
-                    let info =
-                        PlayerConnectionInfoEvent::new(client.clone(), login, version, architecture, rendering_device);
+                    let info = PlayerConnectionInfoEvent::new(
+                        client.clone(),
+                        login,
+                        version,
+                        architecture,
+                        rendering_device,
+                        render_distance,
+                    );
                     connection_info_events.write(info);
                 }
                 ClientMessages::EditBlockRequest {
@@ -181,6 +221,10 @@ fn receive_message_system(
                     let edit = EditBlockEvent::new(client.clone(), world_slug, position, new_block_info);
                     edit_block_events.write(edit);
                 }
+                ClientMessages::ChangeRenderDistance { render_distance } => {
+                    let msg = ChangeRenderDistanceEvent::new(client.clone(), render_distance);
+                    render_distance_events.write(msg);
+                }
             }
         }
     }
@@ -50,53 +50,60 @@ pub fn send_entities_for_player(world_manager: &WorldManager, target_entity: Ent
     }
 }
 
-/// Передвижение игрока
-///
-/// Выполняет:
-///   • Вызыов синхронихации объекта игрока через sync_entity_move
+/// Смена видимых игроком чанков
 ///
 /// - вызывает для ClientNetwork:
 ///   • отправлять ему StopStreamingEntity из старых чанков
 ///   • и StartStreamingEntity для новых
-pub fn sync_player_move(world_manager: &WorldManager, target_entity: Entity, chunks_changed: &Option<ChunkChanged>) {
-    #[cfg(feature = "trace")]
-    let _span = bevy_utils::tracing::info_span!("sync_player_move").entered();
-
+pub fn sync_player_chunks_change(world_manager: &WorldManager, target_entity: Entity, change: &ChunkChanged) {
     let ecs = world_manager.get_ecs();
     let entity_ref = ecs.get_entity(target_entity).unwrap();
+    let client = entity_ref.get::<ClientNetwork>().unwrap();
 
-    if let Some(change) = chunks_changed {
-        let client = entity_ref.get::<ClientNetwork>().unwrap();
-
-        // Stop streaming entities from unseen chunks
-        let mut ids: Vec<u32> = Default::default();
-        for chunk in change.abandoned_chunks.iter() {
-            for entity_ref in world_manager.get_ecs().get_chunk_entities(&chunk).unwrap() {
-                ids.push(entity_ref.id().index());
-            }
-        }
-        if ids.len() > 0 {
-            let msg = ServerMessages::StopStreamingEntities {
-                world_slug: world_manager.get_slug().clone(),
-                ids: Default::default(),
-            };
-            client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+    // Stop streaming entities from unseen chunks
+    let mut ids: Vec<u32> = Default::default();
+    for chunk in change.abandoned_chunks.iter() {
+        for entity_ref in world_manager.get_ecs().get_chunk_entities(&chunk).unwrap() {
+            ids.push(entity_ref.id().index());
         }
+    }
+    if ids.len() > 0 {
+        let msg = ServerMessages::StopStreamingEntities {
+            world_slug: world_manager.get_slug().clone(),
+            ids: Default::default(),
+        };
+        client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+    }
 
-        // Start streaming entities from new chunks
-        for chunk in change.new_chunks.iter() {
-            for target_ref in world_manager.get_ecs().get_chunk_entities(&chunk).unwrap() {
-                if target_ref.id() == target_entity {
-                    continue;
-                }
-                if target_ref.get::<EntitySkinComponent>().is_some() {
-                    send_start_streaming_entity(&*client, target_ref, world_manager.get_slug().clone());
-                }
+    // Start streaming entities from new chunks
+    for chunk in change.new_chunks.iter() {
+        for target_ref in world_manager.get_ecs().get_chunk_entities(&chunk).unwrap() {
+            if target_ref.id() == target_entity {
+                continue;
+            }
+            if target_ref.get::<EntitySkinComponent>().is_some() {
+                send_start_streaming_entity(&*client, target_ref, world_manager.get_slug().clone());
             }
         }
     }
+}
+
+/// Передвижение игрока
+///
+/// Выполняет:
+///   • Вызыов синхронихации объекта игрока через sync_entity_move
+///   • Смену видимых чанков через sync_player_chunks_change
+pub fn sync_player_move(world_manager: &WorldManager, target_entity: Entity, chunks_changed: &Option<ChunkChanged>) {
+    #[cfg(feature = "trace")]
+    let _span = bevy_utils::tracing::info_span!("sync_player_move").entered();
+
+    if let Some(change) = chunks_changed {
+        sync_player_chunks_change(world_manager, target_entity, change);
+    }
 
     // Sync his entity if exists
+    let ecs = world_manager.get_ecs();
+    let entity_ref = ecs.get_entity(target_entity).unwrap();
     if entity_ref.get::<EntitySkinComponent>().is_some() {
         sync_entity_move(world_manager, target_entity, chunks_changed);
     }
@@ -1,6 +1,9 @@
+use ahash::AHashMap;
+use bevy::prelude::Entity;
 use common::chunks::{
     block_position::{BlockPosition, BlockPositionTrait},
     chunk_data::BlockDataInfo,
+    chunk_position::ChunkPosition,
 };
 use network::messages::{NetworkMessageType, ServerMessages};
 
@@ -32,3 +35,57 @@ pub fn sync_world_block_change(
         }
     }
 }
+
+/// Sends all changes to each watcher by a single message
+pub fn sync_world_block_changes(world_manager: &WorldManager, changes: &Vec<(BlockPosition, Option<BlockDataInfo>)>) {
+    let ecs = world_manager.get_ecs();
+
+    let mut watchers_changes: AHashMap<Entity, Vec<(BlockPosition, Option<BlockDataInfo>)>> = Default::default();
+    for (position, new_block_info) in changes.iter() {
+        let Some(entities) = world_manager
+            .get_chunks_map()
+            .get_chunk_watchers(&position.get_chunk_position())
+        else {
+            continue;
+        };
+        for entity in entities {
+            watchers_changes
+                .entry(*entity)
+                .or_default()
+                .push((position.clone(), new_block_info.clone()));
+        }
+    }
+
+    for (entity, changes) in watchers_changes {
+        let entity_ref = ecs.get_entity(entity).unwrap();
+        let network = entity_ref.get::<ClientNetwork>().unwrap();
+
+        let msg = ServerMessages::EditBlocks {
+            world_slug: world_manager.get_slug().clone(),
+            changes,
+        };
+        network.send_message(NetworkMessageType::WorldInfo, &msg);
+    }
+}
+
+/// Resends the whole chunk to the players who already have it;
+/// used instead of the separate blocks after bulk changes
+pub fn sync_world_chunk_change(world_manager: &WorldManager, chunk_position: &ChunkPosition) {
+    let ecs = world_manager.get_ecs();
+
+    let Some(message) = world_manager.get_network_chunk_bytes(chunk_position) else {
+        return;
+    };
+    let Some(entities) = world_manager.get_chunks_map().get_chunk_watchers(chunk_position) else {
+        return;
+    };
+    for entity in entities {
+        let entity_ref = ecs.get_entity(*entity).unwrap();
+        let network = entity_ref.get::<ClientNetwork>().unwrap();
+
+        // The rest will receive the actual chunk with the usual loading
+        if network.is_already_sended(chunk_position) {
+            network.send_message(NetworkMessageType::WorldInfo, &message);
+        }
+    }
+}
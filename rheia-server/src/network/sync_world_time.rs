@@ -0,0 +1,31 @@
+use network::messages::{NetworkMessageType, ServerMessages};
+
+use crate::worlds::world_manager::WorldManager;
+
+use super::client_network::ClientNetwork;
+use super::clients_container::ClientsContainer;
+
+fn get_world_time_message(world_manager: &WorldManager) -> ServerMessages {
+    ServerMessages::WorldTime {
+        world_slug: world_manager.get_slug().clone(),
+        world_time: world_manager.get_time().clone(),
+    }
+}
+
+pub fn send_world_time(world_manager: &WorldManager, client: &ClientNetwork) {
+    let msg = get_world_time_message(world_manager);
+    client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+}
+
+/// Sends the time to all players inside the world
+pub fn sync_world_time(world_manager: &WorldManager, clients: &ClientsContainer) {
+    let msg = get_world_time_message(world_manager);
+    for (_client_id, client) in clients.iter() {
+        let Some(world_entity) = client.get_world_entity() else {
+            continue;
+        };
+        if world_entity.get_world_slug() == world_manager.get_slug() {
+            client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use network::messages::{NetworkMessageType, ServerMessages};
+
+use crate::worlds::world_manager::WorldManager;
+
+use super::client_network::ClientNetwork;
+use super::clients_container::ClientsContainer;
+
+fn get_world_weather_message(world_manager: &WorldManager) -> ServerMessages {
+    ServerMessages::WorldWeather {
+        world_slug: world_manager.get_slug().clone(),
+        weather: world_manager.get_weather().get_weather().clone(),
+    }
+}
+
+pub fn send_world_weather(world_manager: &WorldManager, client: &ClientNetwork) {
+    let msg = get_world_weather_message(world_manager);
+    client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+}
+
+/// Sends the weather to all players inside the world
+pub fn sync_world_weather(world_manager: &WorldManager, clients: &ClientsContainer) {
+    let msg = get_world_weather_message(world_manager);
+    for (_client_id, client) in clients.iter() {
+        let Some(world_entity) = client.get_world_entity() else {
+            continue;
+        };
+        if world_entity.get_world_slug() == world_manager.get_slug() {
+            client.send_message(NetworkMessageType::ReliableOrdered, &msg);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::client_resources::server_settings::ServerSettings;
+use crate::console::console_sender::ConsoleSenderType;
+use crate::network::client_network::ClientNetwork;
+use ahash::AHashMap;
+use bevy_ecs::world::World;
+use common::chunks::block_position::BlockPosition;
+use common::chunks::chunk_data::BlockDataInfo;
+use common::commands::command::{Arg, Command, CommandMatch};
+use std::collections::BTreeMap;
+
+use super::audit_log::{AuditLog, BlockLogEntry, parse_duration};
+use super::bulk_edit::{BulkEditJob, BulkEditQueue, BulkOperation};
+use super::bulk_edit_commands::get_edit_player;
+
+/// How many entries are shown by the lookup commands
+const LOG_LOOKUP_LIMIT: usize = 20;
+
+const LOG_NEAR_DEFAULT_RADIUS: i64 = 5;
+const LOG_NEAR_MAX_RADIUS: i64 = 32;
+
+fn get_block_name(server_settings: &ServerSettings, block_info: &Option<BlockDataInfo>) -> String {
+    match block_info {
+        Some(b) => match server_settings.get_block_id_map().get(&b.get_id()) {
+            Some(slug) => slug.clone(),
+            None => format!("unknown:{}", b.get_id()),
+        },
+        None => "air".to_string(),
+    }
+}
+
+fn format_entry(server_settings: &ServerSettings, entry: &BlockLogEntry) -> String {
+    format!(
+        " - {} &e{}&r {} {:?}: {} -> {}",
+        entry.get_time_formatted(),
+        entry.get_login(),
+        entry.get_world_slug(),
+        entry.get_position(),
+        get_block_name(server_settings, entry.get_old_block_info()),
+        get_block_name(server_settings, entry.get_new_block_info()),
+    )
+}
+
+pub(crate) fn command_parser_log() -> Command {
+    Command::new("log".to_owned())
+        .subcommand_required(true)
+        .subcommand(Command::new("near".to_owned()).arg(Arg::new("radius".to_owned())))
+        .subcommand(
+            Command::new("player".to_owned())
+                .arg(Arg::new("name".to_owned()).required(true))
+                .arg(Arg::new("since".to_owned())),
+        )
+}
+
+pub(crate) fn command_log(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(log_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    let server_settings = world.resource::<ServerSettings>();
+    let audit_log = world.resource::<AuditLog>();
+
+    match log_subcommand.get_name().as_str() {
+        "near" => {
+            let Some(player) = get_edit_player(world, &sender) else {
+                return Ok(());
+            };
+            let radius = match log_subcommand.get_arg::<i64, _>("radius") {
+                Ok(r) => r.clamp(0, LOG_NEAR_MAX_RADIUS),
+                Err(_) => LOG_NEAR_DEFAULT_RADIUS,
+            };
+            let entries = audit_log.get_near(&player.world_slug, &player.position, radius, LOG_LOOKUP_LIMIT)?;
+            if entries.len() == 0 {
+                sender.send_console_message(format!("No block changes in radius {}", radius));
+                return Ok(());
+            }
+            sender.send_console_message(format!("Latest block changes in radius {}:", radius));
+            for entry in entries.iter() {
+                sender.send_console_message(format_entry(server_settings, entry));
+            }
+        }
+        "player" => {
+            let name = log_subcommand.get_arg::<String, _>("name")?;
+            let since = match log_subcommand.get_arg::<String, _>("since") {
+                Ok(s) => chrono::Utc::now().timestamp() - parse_duration(&s)?,
+                Err(_) => 0,
+            };
+            let (entries, total) = audit_log.get_by_player(&name, since, LOG_LOOKUP_LIMIT)?;
+            if total == 0 {
+                sender.send_console_message(format!("Player \"{}\" has no block changes", name));
+                return Ok(());
+            }
+            sender.send_console_message(format!(
+                "Block changes of \"{}\" (showing {} of {}):",
+                name,
+                entries.len(),
+                total
+            ));
+            for entry in entries.iter() {
+                sender.send_console_message(format_entry(server_settings, entry));
+            }
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn command_parser_rollback() -> Command {
+    Command::new("rollback".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("player".to_owned())
+                .arg(Arg::new("name".to_owned()).required(true))
+                .arg(Arg::new("since".to_owned()).required(true).choices(vec!["since"]))
+                .arg(Arg::new("duration".to_owned()).required(true))
+                .arg(Arg::new("radius".to_owned())),
+        )
+}
+
+/// Restores the blocks changed by the player to the state before his first change
+///
+/// Blocks changed by someone else after the player are skipped and reported
+///
+/// Radius is counted around the sender, so it can be used only by players
+pub(crate) fn command_rollback(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(rollback_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    if rollback_subcommand.get_arg::<String, _>("since")? != "since" {
+        return Err("&cUsage: rollback player <name> since <duration> [radius]".to_string());
+    }
+    let name = rollback_subcommand.get_arg::<String, _>("name")?;
+    let since =
+        chrono::Utc::now().timestamp() - parse_duration(&rollback_subcommand.get_arg::<String, _>("duration")?)?;
+    let radius = rollback_subcommand.get_arg::<i64, _>("radius").ok();
+
+    let is_player = sender.as_any().downcast_ref::<ClientNetwork>().is_some();
+    let player = match is_player {
+        true => match get_edit_player(world, &sender) {
+            Some(p) => Some(p),
+            None => return Ok(()),
+        },
+        false => None,
+    };
+    let area = match (radius, player.as_ref()) {
+        (Some(radius), Some(player)) => Some((&player.world_slug, &player.position, radius.max(0))),
+        (Some(_), None) => return Err("&cRadius can be used only by players".to_string()),
+        (None, _) => None,
+    };
+    let entries = world.resource::<AuditLog>().get_rollback(&name, since, area)?;
+
+    // The earliest state of each block is restored if the block is still the last one placed by the player
+    let mut worlds: BTreeMap<String, AHashMap<BlockPosition, (Option<BlockDataInfo>, Option<BlockDataInfo>)>> =
+        Default::default();
+    for entry in entries {
+        let block = worlds
+            .entry(entry.get_world_slug().clone())
+            .or_default()
+            .entry(entry.get_position().clone())
+            .or_insert((*entry.get_old_block_info(), None));
+        block.1 = *entry.get_new_block_info();
+    }
+    if worlds.len() == 0 {
+        sender.send_console_message(format!("Player \"{}\" has no block changes to rollback", name));
+        return Ok(());
+    }
+
+    for (world_slug, blocks) in worlds {
+        let blocks: Vec<(BlockPosition, Option<BlockDataInfo>, Option<BlockDataInfo>)> =
+            blocks.into_iter().map(|(p, (old, new))| (p, old, new)).collect();
+        let origin = blocks[0].0.clone();
+        let job = BulkEditJob::create(
+            player.as_ref().map(|p| p.client.clone()),
+            player.as_ref().map(|p| p.login.clone()),
+            world_slug.clone(),
+            BulkOperation::Restore(blocks),
+            origin,
+            origin,
+        );
+        let volume = job.get_volume();
+        world.resource_mut::<BulkEditQueue>().push(job);
+        sender.send_console_message(format!(
+            "Rollback of {} blocks of \"{}\" in world \"{}\" started",
+            volume, name, world_slug
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,376 @@
+use bevy::prelude::{Res, Resource};
+use common::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use std::path::PathBuf;
+
+use super::edit_history::BlockChange;
+
+const SQL_CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS block_log (id INTEGER PRIMARY KEY AUTOINCREMENT, \
+    login TEXT, world TEXT, x INTEGER, y INTEGER, z INTEGER, old_block BLOB, new_block BLOB, time INTEGER);";
+const SQL_CREATE_INDEX_POSITION: &str = "CREATE INDEX IF NOT EXISTS block_log_position ON block_log (world, x, z);";
+const SQL_CREATE_INDEX_LOGIN: &str = "CREATE INDEX IF NOT EXISTS block_log_login ON block_log (login, time);";
+
+const SQL_INSERT: &str = "INSERT INTO block_log (login, world, x, y, z, old_block, new_block, time) \
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);";
+const SQL_SELECT_NEAR: &str = "SELECT login, world, x, y, z, old_block, new_block, time FROM block_log \
+    WHERE world=?1 AND x BETWEEN ?2 AND ?3 AND y BETWEEN ?4 AND ?5 AND z BETWEEN ?6 AND ?7 ORDER BY id DESC LIMIT ?8;";
+const SQL_SELECT_PLAYER: &str = "SELECT login, world, x, y, z, old_block, new_block, time FROM block_log \
+    WHERE login=?1 AND time>=?2 ORDER BY id DESC LIMIT ?3;";
+const SQL_COUNT_PLAYER: &str = "SELECT COUNT(*) FROM block_log WHERE login=?1 AND time>=?2;";
+const SQL_SELECT_ROLLBACK: &str = "SELECT login, world, x, y, z, old_block, new_block, time FROM block_log \
+    WHERE login=?1 AND time>=?2 ORDER BY id ASC;";
+
+/// Recorded change of the block
+#[derive(Clone, Debug)]
+pub struct BlockLogEntry {
+    login: String,
+    world_slug: String,
+    position: BlockPosition,
+    old_block_info: Option<BlockDataInfo>,
+    new_block_info: Option<BlockDataInfo>,
+
+    // Unix timestamp in seconds
+    time: i64,
+}
+
+impl BlockLogEntry {
+    pub fn get_login(&self) -> &String {
+        &self.login
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_old_block_info(&self) -> &Option<BlockDataInfo> {
+        &self.old_block_info
+    }
+
+    pub fn get_new_block_info(&self) -> &Option<BlockDataInfo> {
+        &self.new_block_info
+    }
+
+    pub fn get_time_formatted(&self) -> String {
+        match chrono::DateTime::from_timestamp(self.time, 0) {
+            Some(t) => t.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => self.time.to_string(),
+        }
+    }
+}
+
+type RawEntry = (String, String, i64, i64, i64, Vec<u8>, Vec<u8>, i64);
+
+fn decode_entry(raw: RawEntry) -> Result<BlockLogEntry, String> {
+    let (login, world_slug, x, y, z, old_block, new_block, time) = raw;
+    let old_block_info = match bincode::deserialize(&old_block) {
+        Ok(b) => b,
+        Err(e) => return Err(format!("Block log decode error: &c{}", e)),
+    };
+    let new_block_info = match bincode::deserialize(&new_block) {
+        Ok(b) => b,
+        Err(e) => return Err(format!("Block log decode error: &c{}", e)),
+    };
+    Ok(BlockLogEntry {
+        login,
+        world_slug,
+        position: BlockPosition::new(x, y, z),
+        old_block_info,
+        new_block_info,
+        time,
+    })
+}
+
+/// Persistent log of all block changes with the players who made them
+///
+/// Changes are buffered and written once per tick in a single transaction
+#[derive(Resource)]
+pub struct AuditLog {
+    db: Mutex<Connection>,
+    pending: Mutex<Vec<BlockLogEntry>>,
+}
+
+impl AuditLog {
+    pub fn create(path: PathBuf) -> Result<Self, String> {
+        let db = match Connection::open(path.clone()) {
+            Ok(db) => db,
+            Err(e) => return Err(format!("Block log \"{}\" open error: &c{}", path.display(), e)),
+        };
+        for sql in [SQL_CREATE_TABLE, SQL_CREATE_INDEX_POSITION, SQL_CREATE_INDEX_LOGIN] {
+            if let Err(e) = db.execute(sql, ()) {
+                return Err(format!("Block log table create error: &c{}", e));
+            }
+        }
+        Ok(Self {
+            db: Mutex::new(db),
+            pending: Default::default(),
+        })
+    }
+
+    pub fn record(&self, login: &String, world_slug: &String, change: &BlockChange) {
+        self.pending.lock().push(BlockLogEntry {
+            login: login.clone(),
+            world_slug: world_slug.clone(),
+            position: change.get_position().clone(),
+            old_block_info: *change.get_old_block_info(),
+            new_block_info: *change.get_new_block_info(),
+            time: chrono::Utc::now().timestamp(),
+        });
+    }
+
+    /// Writes all recorded changes into the database
+    ///
+    /// Changes are kept for the next flush if the writing fails
+    pub fn flush(&self) -> Result<usize, String> {
+        let mut pending = std::mem::take(&mut *self.pending.lock());
+        if pending.len() == 0 {
+            return Ok(0);
+        }
+
+        if let Err(e) = self.write_entries(&pending) {
+            // Changes recorded during the writing go after the failed ones
+            let mut queue = self.pending.lock();
+            pending.append(&mut queue);
+            *queue = pending;
+            return Err(e);
+        }
+        Ok(pending.len())
+    }
+
+    fn write_entries(&self, entries: &[BlockLogEntry]) -> Result<(), String> {
+        let mut db = self.db.lock();
+        let tx = match db.transaction() {
+            Ok(tx) => tx,
+            Err(e) => return Err(format!("Block log transaction error: &c{}", e)),
+        };
+        {
+            let mut stmt = match tx.prepare_cached(SQL_INSERT) {
+                Ok(s) => s,
+                Err(e) => return Err(format!("Block log insert error: &c{}", e)),
+            };
+            for entry in entries.iter() {
+                let result = stmt.execute((
+                    &entry.login,
+                    &entry.world_slug,
+                    entry.position.get_x(),
+                    entry.position.get_y(),
+                    entry.position.get_z(),
+                    bincode::serialize(&entry.old_block_info).unwrap(),
+                    bincode::serialize(&entry.new_block_info).unwrap(),
+                    entry.time,
+                ));
+                if let Err(e) = result {
+                    return Err(format!("Block log insert error: &c{}", e));
+                }
+            }
+        }
+        if let Err(e) = tx.commit() {
+            return Err(format!("Block log commit error: &c{}", e));
+        }
+        Ok(())
+    }
+
+    fn select<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<BlockLogEntry>, String> {
+        self.flush()?;
+
+        let db = self.db.lock();
+        let mut stmt = match db.prepare(sql) {
+            Ok(s) => s,
+            Err(e) => return Err(format!("Block log select error: &c{}", e)),
+        };
+        let rows = stmt.query_map(params, |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ))
+        });
+        let rows = match rows {
+            Ok(r) => r,
+            Err(e) => return Err(format!("Block log select error: &c{}", e)),
+        };
+
+        let mut entries: Vec<BlockLogEntry> = Default::default();
+        for row in rows {
+            match row {
+                Ok(r) => entries.push(decode_entry(r)?),
+                Err(e) => return Err(format!("Block log row error: &c{}", e)),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The latest changes inside the cube around the center
+    pub fn get_near(
+        &self,
+        world_slug: &String,
+        center: &BlockPosition,
+        radius: i64,
+        limit: usize,
+    ) -> Result<Vec<BlockLogEntry>, String> {
+        self.select(
+            SQL_SELECT_NEAR,
+            (
+                world_slug,
+                center.get_x() - radius,
+                center.get_x() + radius,
+                center.get_y() - radius,
+                center.get_y() + radius,
+                center.get_z() - radius,
+                center.get_z() + radius,
+                limit as i64,
+            ),
+        )
+    }
+
+    /// The latest changes of the player and the total count of his changes since the time
+    pub fn get_by_player(
+        &self,
+        login: &String,
+        since: i64,
+        limit: usize,
+    ) -> Result<(Vec<BlockLogEntry>, usize), String> {
+        let entries = self.select(SQL_SELECT_PLAYER, (login, since, limit as i64))?;
+        let total: i64 = match self
+            .db
+            .lock()
+            .query_row(SQL_COUNT_PLAYER, (login, since), |row| row.get(0))
+        {
+            Ok(c) => c,
+            Err(e) => return Err(format!("Block log count error: &c{}", e)),
+        };
+        Ok((entries, total as usize))
+    }
+
+    /// All changes of the player since the time in the order they were made
+    ///
+    /// Area is the world with the center and the radius
+    pub fn get_rollback(
+        &self,
+        login: &String,
+        since: i64,
+        area: Option<(&String, &BlockPosition, i64)>,
+    ) -> Result<Vec<BlockLogEntry>, String> {
+        let mut entries = self.select(SQL_SELECT_ROLLBACK, (login, since))?;
+        if let Some((world_slug, center, radius)) = area {
+            entries.retain(|e| {
+                e.world_slug == *world_slug
+                    && (e.position.get_x() - center.get_x()).abs() <= radius
+                    && (e.position.get_y() - center.get_y()).abs() <= radius
+                    && (e.position.get_z() - center.get_z()).abs() <= radius
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Duration like "30s", "15m", "2h" or "3d" in seconds
+pub fn parse_duration(value: &String) -> Result<i64, String> {
+    let error = || format!("&cWrong duration &4\"{}\"&c; examples: 30s, 15m, 2h, 3d", value);
+    let Some(unit) = value.chars().last() else {
+        return Err(error());
+    };
+    let multiplier = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 60 * 60 * 24,
+        _ => return Err(error()),
+    };
+    match value[..value.len() - 1].parse::<u32>() {
+        Ok(v) => Ok(v as i64 * multiplier),
+        Err(_) => Err(error()),
+    }
+}
+
+pub fn flush_audit_log(audit_log: Res<AuditLog>) {
+    if let Err(e) = audit_log.flush() {
+        log::error!(target: "worlds", "{}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditLog, SQL_CREATE_TABLE, parse_duration};
+    use crate::worlds::edit_history::BlockChange;
+    use common::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_audit_log() {
+        let log = AuditLog::create(PathBuf::from(":memory:")).unwrap();
+        let world = "default".to_string();
+        let block = Some(BlockDataInfo::create(1, None));
+
+        log.record(
+            &"a".to_string(),
+            &world,
+            &BlockChange::new(BlockPosition::new(0, 0, 0), None, block),
+        );
+        log.record(
+            &"b".to_string(),
+            &world,
+            &BlockChange::new(BlockPosition::new(1, 0, 0), None, block),
+        );
+        log.record(
+            &"a".to_string(),
+            &world,
+            &BlockChange::new(BlockPosition::new(50, 0, 0), None, block),
+        );
+        log.record(
+            &"a".to_string(),
+            &world,
+            &BlockChange::new(BlockPosition::new(0, 0, 0), block, None),
+        );
+        assert_eq!(log.flush().unwrap(), 4);
+
+        let near = log.get_near(&world, &BlockPosition::new(0, 0, 0), 5, 10).unwrap();
+        assert_eq!(near.len(), 3);
+        // The latest change is the first one
+        assert_eq!(*near[0].get_new_block_info(), None);
+
+        let (entries, total) = log.get_by_player(&"a".to_string(), 0, 2).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(total, 3);
+
+        let area = Some((&world, &BlockPosition::new(0, 0, 0), 5));
+        let rollback = log.get_rollback(&"a".to_string(), 0, area).unwrap();
+        assert_eq!(rollback.len(), 2);
+        assert_eq!(*rollback[0].get_old_block_info(), None);
+    }
+
+    #[test]
+    fn test_audit_log_flush_error() {
+        let log = AuditLog::create(PathBuf::from(":memory:")).unwrap();
+        let world = "default".to_string();
+        let change = BlockChange::new(BlockPosition::new(0, 0, 0), None, Some(BlockDataInfo::create(1, None)));
+
+        log.record(&"a".to_string(), &world, &change);
+        log.db.lock().execute("DROP TABLE block_log;", ()).unwrap();
+        assert!(log.flush().is_err());
+
+        // Failed changes are written by the next flush
+        log.record(&"b".to_string(), &world, &change);
+        log.db.lock().execute(SQL_CREATE_TABLE, ()).unwrap();
+        assert_eq!(log.flush().unwrap(), 2);
+        assert_eq!(log.get_by_player(&"a".to_string(), 0, 10).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration(&"30s".to_string()).unwrap(), 30);
+        assert_eq!(parse_duration(&"2h".to_string()).unwrap(), 7200);
+        assert_eq!(parse_duration(&"3d".to_string()).unwrap(), 259200);
+        assert!(parse_duration(&"3w".to_string()).is_err());
+        assert!(parse_duration(&"h".to_string()).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::{Res, ResMut};
+use common::{
+    blocks::voxel_visibility::VoxelVisibility,
+    chunks::{
+        block_position::BlockPosition,
+        chunk_data::{BlockDataInfo, BlockIndexType, FLUID_MAX_LEVEL},
+    },
+    default_blocks_ids::BlockID,
+};
+
+use crate::{client_resources::server_settings::ServerSettings, network::runtime_plugin::RuntimePlugin};
+
+use super::{
+    block_ticks::{BlockTickContext, BlockTickHandlers},
+    falling_blocks::is_passable_for_falling,
+};
+
+/// Ticks between the water spreading steps
+pub const WATER_TICK_DELAY: u32 = 5;
+
+/// Ticks before the unsupported block starts to fall
+pub const FALLING_BLOCK_DELAY: u32 = 2;
+
+/// Handlers of the default blocks
+pub(crate) fn register_default_block_handlers(handlers: &mut BlockTickHandlers) {
+    handlers.register_random("grass", grass_random_tick);
+
+    handlers.register_scheduled("water", fluid_tick);
+    handlers.register_neighbor_update("water", WATER_TICK_DELAY);
+}
+
+/// Blocks with the `falls` flag are known only after all resources are loaded
+pub(crate) fn register_falling_blocks(server_settings: Res<ServerSettings>, mut handlers: ResMut<BlockTickHandlers>) {
+    if RuntimePlugin::is_stopped() {
+        return;
+    }
+
+    for block_type in server_settings.get_blocks().iter() {
+        if block_type.can_fall() {
+            handlers.register_scheduled(block_type.get_slug(), falling_block_tick);
+            handlers.register_neighbor_update(block_type.get_slug(), FALLING_BLOCK_DELAY);
+        }
+    }
+}
+
+/// Unsupported block turns into the falling entity
+fn falling_block_tick(context: &mut BlockTickContext) {
+    if !context.get_game_rules().is_falling_blocks() {
+        return;
+    }
+    let below = context.get_position().offset(0, -1, 0);
+    if !context.is_loaded(&below) {
+        return;
+    }
+    if !is_passable_for_falling(context.get_server_settings(), &context.get_block(&below)) {
+        return;
+    }
+    let position = context.get_position().clone();
+    let block_info = context.get_block_info().clone();
+    context.spawn_falling_block(position, block_info);
+}
+
+/// Grass under the opaque block turns into the dirt
+fn grass_random_tick(context: &mut BlockTickContext) {
+    let above = context.get_position().offset(0, 1, 0);
+    let covered = match context.get_block_type(&above) {
+        Some(block_type) => *block_type.get_voxel_visibility() == VoxelVisibility::Opaque,
+        None => false,
+    };
+    if covered {
+        let position = context.get_position().clone();
+        context.set_block(position, Some(BlockDataInfo::create(BlockID::CoarseDirt.id(), None)));
+    }
+}
+
+const HORIZONTAL_NEIGHBORS: [(i64, i64); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Source is stronger than any flowing fluid
+fn get_fluid_strength(block_info: &Option<BlockDataInfo>, fluid_id: BlockIndexType) -> Option<u8> {
+    let block_info = block_info.as_ref()?;
+    if block_info.get_id() != fluid_id {
+        return None;
+    }
+    match block_info.get_level() {
+        Some(level) => Some(level),
+        None => Some(FLUID_MAX_LEVEL + 1),
+    }
+}
+
+/// Fluid flows into the air only
+fn can_flow_into(context: &BlockTickContext, position: &BlockPosition) -> bool {
+    context.is_loaded(position) && context.get_block(position).is_none()
+}
+
+/// Fluid spreads sideways only when it lies on the ground
+fn is_spreading(context: &BlockTickContext, position: &BlockPosition, fluid_id: BlockIndexType) -> bool {
+    match context.get_block(&position.offset(0, -1, 0)) {
+        Some(block_info) => block_info.get_id() != fluid_id,
+        None => false,
+    }
+}
+
+/// Ground or the source under the fluid
+fn is_supported(context: &BlockTickContext, position: &BlockPosition, fluid_id: BlockIndexType) -> bool {
+    match context.get_block(&position.offset(0, -1, 0)) {
+        Some(block_info) => block_info.get_id() != fluid_id || block_info.get_level().is_none(),
+        None => false,
+    }
+}
+
+/// Level which flowing fluid must have by its neighbours;
+/// Some(None) means that the fluid becomes a source, None - the fluid dries up
+fn get_expected_level(context: &BlockTickContext, fluid_id: BlockIndexType) -> Option<Option<u8>> {
+    let position = context.get_position().clone();
+
+    let mut sources = 0;
+    let mut strength: u8 = 0;
+    for (x, z) in HORIZONTAL_NEIGHBORS {
+        let neighbor = position.offset(x, 0, z);
+        let Some(neighbor_strength) = get_fluid_strength(&context.get_block(&neighbor), fluid_id) else {
+            continue;
+        };
+        if neighbor_strength > FLUID_MAX_LEVEL {
+            sources += 1;
+        }
+        if is_spreading(context, &neighbor, fluid_id) {
+            strength = strength.max(neighbor_strength.min(FLUID_MAX_LEVEL + 1) - 1);
+        }
+    }
+
+    // Two sources nearby refill the supported fluid
+    if sources >= 2 && is_supported(context, &position, fluid_id) {
+        return Some(None);
+    }
+
+    // Falling fluid
+    let above = position.offset(0, 1, 0);
+    if get_fluid_strength(&context.get_block(&above), fluid_id).is_some() {
+        return Some(Some(FLUID_MAX_LEVEL));
+    }
+
+    match strength {
+        0 => None,
+        s => Some(Some(s)),
+    }
+}
+
+/// Fluid falls down, spreads with the decreasing level and dries up without the source
+///
+/// Any change schedules updates of the neighbours, so the flow goes step by step
+fn fluid_tick(context: &mut BlockTickContext) {
+    if !context.get_game_rules().is_fluid_flow() {
+        return;
+    }
+    let position = context.get_position().clone();
+    let block_info = context.get_block_info().clone();
+    let fluid_id = block_info.get_id();
+
+    if block_info.get_level().is_some() {
+        let expected = get_expected_level(context, fluid_id);
+        if expected != Some(block_info.get_level()) {
+            let new_block_info = expected.map(|level| BlockDataInfo::create_fluid(fluid_id, level));
+            context.set_block(position, new_block_info);
+            return;
+        }
+    }
+
+    let below = position.offset(0, -1, 0);
+    if can_flow_into(context, &below) {
+        context.set_block(
+            below,
+            Some(BlockDataInfo::create_fluid(fluid_id, Some(FLUID_MAX_LEVEL))),
+        );
+        return;
+    }
+    if !is_spreading(context, &position, fluid_id) {
+        return;
+    }
+
+    let strength = get_fluid_strength(&Some(block_info), fluid_id).unwrap();
+    let level = strength.min(FLUID_MAX_LEVEL + 1) - 1;
+    if level == 0 {
+        return;
+    }
+    for (x, z) in HORIZONTAL_NEIGHBORS {
+        let neighbor = position.offset(x, 0, z);
+        if can_flow_into(context, &neighbor) {
+            context.set_block(neighbor, Some(BlockDataInfo::create_fluid(fluid_id, Some(level))));
+        }
+    }
+}
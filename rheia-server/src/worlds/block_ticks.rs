@@ -0,0 +1,272 @@
+use ahash::{AHashMap, AHashSet};
+use bevy::prelude::{Res, Resource};
+use bracket_lib::random::RandomNumberGenerator;
+use common::{
+    blocks::block_type::BlockType,
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::BlockDataInfo,
+        chunk_position::ChunkPosition,
+    },
+    game_rules::GameRules,
+};
+
+use crate::{
+    client_resources::server_settings::ServerSettings,
+    network::{sync_entities::sync_entity_spawn, sync_world_change::sync_world_block_changes},
+};
+
+use super::{chunks::chunks_map::ChunkMap, world_manager::WorldManager, worlds_manager::WorldsManager};
+
+pub type BlockTickHandler = fn(&mut BlockTickContext);
+
+/// Handlers of the block updates by the block slug
+#[derive(Resource, Default)]
+pub struct BlockTickHandlers {
+    scheduled: AHashMap<String, BlockTickHandler>,
+    random: AHashMap<String, BlockTickHandler>,
+
+    // Delay of the update after the block or its neighbour was changed
+    neighbor_updates: AHashMap<String, u32>,
+}
+
+impl BlockTickHandlers {
+    /// Handler is called when the scheduled update of the block is due
+    pub fn register_scheduled<S: Into<String>>(&mut self, block_slug: S, handler: BlockTickHandler) {
+        self.scheduled.insert(block_slug.into(), handler);
+    }
+
+    /// Handler is called when the block is picked by the random tick
+    pub fn register_random<S: Into<String>>(&mut self, block_slug: S, handler: BlockTickHandler) {
+        self.random.insert(block_slug.into(), handler);
+    }
+
+    /// Block is scheduled to update when it or any of its neighbours is changed
+    pub fn register_neighbor_update<S: Into<String>>(&mut self, block_slug: S, delay: u32) {
+        self.neighbor_updates.insert(block_slug.into(), delay);
+    }
+
+    pub fn has_random(&self) -> bool {
+        !self.random.is_empty()
+    }
+}
+
+/// Access to the world for the block tick handler
+///
+/// Changes are collected and applied after all handlers of the tick
+pub struct BlockTickContext<'a> {
+    world_manager: &'a WorldManager,
+    server_settings: &'a ServerSettings,
+
+    position: BlockPosition,
+    block_info: BlockDataInfo,
+
+    changes: Vec<(BlockPosition, Option<BlockDataInfo>)>,
+    scheduled: Vec<(BlockPosition, u32)>,
+    falling_blocks: Vec<(BlockPosition, BlockDataInfo)>,
+}
+
+impl<'a> BlockTickContext<'a> {
+    fn new(
+        world_manager: &'a WorldManager,
+        server_settings: &'a ServerSettings,
+        position: BlockPosition,
+        block_info: BlockDataInfo,
+    ) -> Self {
+        Self {
+            world_manager,
+            server_settings,
+            position,
+            block_info,
+            changes: Default::default(),
+            scheduled: Default::default(),
+            falling_blocks: Default::default(),
+        }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        self.world_manager.get_slug()
+    }
+
+    pub fn get_game_rules(&self) -> &GameRules {
+        self.world_manager.get_game_rules()
+    }
+
+    pub fn get_server_settings(&self) -> &ServerSettings {
+        self.server_settings
+    }
+
+    /// Position of the updated block
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    /// The updated block
+    pub fn get_block_info(&self) -> &BlockDataInfo {
+        &self.block_info
+    }
+
+    /// Blocks of unloaded chunks can't be changed
+    pub fn is_loaded(&self, position: &BlockPosition) -> bool {
+        self.world_manager.get_chunks_map().is_position_loaded(position)
+    }
+
+    /// Returns None for the air and for the blocks of unloaded chunks
+    pub fn get_block(&self, position: &BlockPosition) -> Option<BlockDataInfo> {
+        self.world_manager.get_chunks_map().get_block_info(position)
+    }
+
+    pub fn get_block_type(&self, position: &BlockPosition) -> Option<&BlockType> {
+        let block_info = self.get_block(position)?;
+        self.server_settings.get_block_type(&block_info.get_id())
+    }
+
+    pub fn set_block(&mut self, position: BlockPosition, new_block_info: Option<BlockDataInfo>) {
+        self.changes.push((position, new_block_info));
+    }
+
+    pub fn schedule_tick(&mut self, position: BlockPosition, delay: u32) {
+        self.scheduled.push((position, delay));
+    }
+
+    /// Replaces the block with the falling entity
+    pub fn spawn_falling_block(&mut self, position: BlockPosition, block_info: BlockDataInfo) {
+        self.changes.push((position.clone(), None));
+        self.falling_blocks.push((position, block_info));
+    }
+}
+
+/// Scheduled and random block updates of all worlds
+pub fn update_block_ticks(
+    worlds_manager: Res<WorldsManager>,
+    server_settings: Res<ServerSettings>,
+    handlers: Res<BlockTickHandlers>,
+) {
+    #[cfg(feature = "trace")]
+    let _span = bevy_utils::tracing::info_span!("update_block_ticks").entered();
+
+    let mut rng = RandomNumberGenerator::new();
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let falling_blocks = update_world_block_ticks(&world.read(), &server_settings, &handlers, &mut rng);
+        if falling_blocks.is_empty() {
+            continue;
+        }
+
+        let mut world_manager = world.write();
+        for (position, block_info) in falling_blocks {
+            let entity = world_manager.spawn_falling_block(&position, block_info);
+            sync_entity_spawn(&world_manager, entity);
+        }
+    }
+}
+
+/// Returns blocks which must be turned into falling entities
+fn update_world_block_ticks(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    handlers: &BlockTickHandlers,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<(BlockPosition, BlockDataInfo)> {
+    let chunks_map = world_manager.get_chunks_map();
+
+    let mut ticks: Vec<(BlockPosition, BlockDataInfo, BlockTickHandler)> = Default::default();
+    for position in chunks_map.pop_due_ticks() {
+        // Block could be removed after the update was scheduled
+        let Some(block_info) = chunks_map.get_block_info(&position) else {
+            continue;
+        };
+        if let Some(handler) = get_handler(server_settings, &handlers.scheduled, &block_info) {
+            ticks.push((position, block_info, handler));
+        }
+    }
+    if handlers.has_random() {
+        let random_tick_speed = world_manager.get_game_rules().get_random_tick_speed();
+        for (position, block_info) in chunks_map.pick_random_ticks(random_tick_speed, rng) {
+            if let Some(handler) = get_handler(server_settings, &handlers.random, &block_info) {
+                ticks.push((position, block_info, handler));
+            }
+        }
+    }
+
+    let mut changes: AHashMap<BlockPosition, Option<BlockDataInfo>> = Default::default();
+    let mut scheduled: Vec<(BlockPosition, u32)> = Default::default();
+    let mut falling_blocks: Vec<(BlockPosition, BlockDataInfo)> = Default::default();
+    for (position, block_info, handler) in ticks {
+        let mut context = BlockTickContext::new(world_manager, server_settings, position, block_info);
+        handler(&mut context);
+        // The last change of the position wins
+        changes.extend(context.changes.drain(..));
+        scheduled.append(&mut context.scheduled);
+        falling_blocks.append(&mut context.falling_blocks);
+    }
+
+    let mut applied: Vec<(BlockPosition, Option<BlockDataInfo>)> = Default::default();
+    let mut changed_chunks: AHashSet<ChunkPosition> = Default::default();
+    for (position, new_block_info) in changes {
+        if chunks_map.get_block_info(&position) == new_block_info {
+            continue;
+        }
+        if let Err(e) = chunks_map.edit_block_no_light(position.clone(), new_block_info.clone()) {
+            log::error!(target: "worlds", "&cBlock tick change error: {}", e);
+            continue;
+        }
+        schedule_block_updates(chunks_map, server_settings, handlers, &position);
+        changed_chunks.insert(position.get_chunk_position());
+        applied.push((position, new_block_info));
+    }
+    for chunk_position in changed_chunks.iter() {
+        chunks_map.relight_chunk(chunk_position);
+    }
+    if !applied.is_empty() {
+        sync_world_block_changes(world_manager, &applied);
+    }
+
+    for (position, delay) in scheduled {
+        if let Err(e) = chunks_map.schedule_tick(position, delay) {
+            log::error!(target: "worlds", "&cBlock tick schedule error: {}", e);
+        }
+    }
+    falling_blocks
+}
+
+/// Schedules updates of the changed block and its neighbours
+/// which are registered for the neighbour updates
+pub fn schedule_block_updates(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    handlers: &BlockTickHandlers,
+    position: &BlockPosition,
+) {
+    let neighbors = [
+        position.clone(),
+        position.offset(1, 0, 0),
+        position.offset(-1, 0, 0),
+        position.offset(0, 1, 0),
+        position.offset(0, -1, 0),
+        position.offset(0, 0, 1),
+        position.offset(0, 0, -1),
+    ];
+    for neighbor in neighbors {
+        let Some(block_info) = chunks_map.get_block_info(&neighbor) else {
+            continue;
+        };
+        let Some(block_slug) = server_settings.get_block_id_map().get(&block_info.get_id()) else {
+            continue;
+        };
+        let Some(delay) = handlers.neighbor_updates.get(block_slug) else {
+            continue;
+        };
+        if let Err(e) = chunks_map.schedule_tick(neighbor, *delay) {
+            log::error!(target: "worlds", "&cBlock neighbor update error: {}", e);
+        }
+    }
+}
+
+fn get_handler(
+    server_settings: &ServerSettings,
+    handlers: &AHashMap<String, BlockTickHandler>,
+    block_info: &BlockDataInfo,
+) -> Option<BlockTickHandler> {
+    let block_slug = server_settings.get_block_id_map().get(&block_info.get_id())?;
+    handlers.get(block_slug).copied()
+}
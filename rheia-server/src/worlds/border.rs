@@ -0,0 +1,88 @@
+use bevy::time::Time;
+use bevy_ecs::system::Res;
+use common::world_border::WorldBorder;
+
+use crate::entities::entity::{Position, Rotation};
+use crate::network::{
+    client_network::ClientNetwork, clients_container::ClientsContainer, events::on_player_move::move_player,
+    sync_world_border::sync_world_border,
+};
+
+use super::{world_manager::WorldManager, worlds_manager::WorldsManager};
+
+/// The nearest position inside the border; None if the position is already inside
+pub fn get_border_correction(border: &WorldBorder, position: &Position) -> Option<Position> {
+    if border.contains(position.get_x(), position.get_z()) {
+        return None;
+    }
+    let (x, z) = border.clamp(position.get_x(), position.get_z());
+    Some(Position::new(x, position.get_y(), z))
+}
+
+/// Moves the player outside of the border to its edge
+///
+/// Returns true if the player was moved
+pub fn push_inside_border(world_manager: &mut WorldManager, client: &ClientNetwork) -> bool {
+    let Some(world_entity) = client.get_world_entity() else {
+        return false;
+    };
+    let Some(border) = world_manager.get_border() else {
+        return false;
+    };
+    let Some(entity_ref) = world_manager.get_ecs().get_entity(world_entity.get_entity()) else {
+        return false;
+    };
+    let (Some(position), Some(rotation)) = (entity_ref.get::<Position>(), entity_ref.get::<Rotation>()) else {
+        return false;
+    };
+    let Some(correction) = get_border_correction(border, position) else {
+        return false;
+    };
+    let rotation = *rotation;
+
+    move_player(world_manager, &world_entity, correction, rotation);
+    client.get_movement_state_mut().reset();
+    client.send_teleport(&correction);
+    true
+}
+
+/// Animates the border resize and keeps the players inside it
+pub fn update_world_border(worlds_manager: Res<WorldsManager>, clients: Res<ClientsContainer>, time: Res<Time>) {
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let mut world_manager = world.write();
+        let changed = world_manager.update_border(time.delta());
+        if changed {
+            sync_world_border(&*world_manager, &*clients);
+        }
+
+        let is_resizing = world_manager.get_border().map_or(false, |b| b.is_resizing());
+        if !changed && !is_resizing {
+            continue;
+        }
+        for (_client_id, client) in clients.iter() {
+            let in_world = match client.get_world_entity() {
+                Some(world_entity) => world_entity.get_world_slug() == world_manager.get_slug(),
+                None => false,
+            };
+            if in_world {
+                push_inside_border(&mut *world_manager, client);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_border_correction;
+    use crate::entities::entity::Position;
+    use common::world_border::{WorldBorder, WorldBorderShape};
+
+    #[test]
+    fn test_border_correction() {
+        let border = WorldBorder::create(100.0, 0.0, WorldBorderShape::Square, 50.0);
+        assert!(get_border_correction(&border, &Position::new(120.0, 64.0, 10.0)).is_none());
+
+        let correction = get_border_correction(&border, &Position::new(200.0, 64.0, 10.0)).unwrap();
+        assert!(correction == Position::new(149.5, 64.0, 10.0));
+    }
+}
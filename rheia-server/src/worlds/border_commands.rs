@@ -0,0 +1,122 @@
+use crate::console::console_sender::ConsoleSenderType;
+use bevy_ecs::world::World;
+use common::chunks::block_position::BlockPositionTrait;
+use common::commands::command::{Arg, Command, CommandMatch};
+use common::world_border::{MAX_BORDER_SIZE, MIN_BORDER_SIZE, WorldBorder, WorldBorderShape};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::audit_log::parse_duration;
+use super::time_commands::get_sender_world_slug;
+use super::worlds_manager::WorldsManager;
+
+pub(crate) fn command_parser_border() -> Command {
+    let shapes: Vec<String> = WorldBorderShape::get_all().iter().map(|s| s.to_string()).collect();
+    Command::new("border".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("set".to_owned())
+                .arg(Arg::new("size".to_owned()).required(true))
+                .arg(Arg::new("duration".to_owned()))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(
+            Command::new("center".to_owned())
+                .arg(Arg::new("x".to_owned()).required(true))
+                .arg(Arg::new("z".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(
+            Command::new("shape".to_owned())
+                .arg(Arg::new("shape".to_owned()).required(true).choices(shapes))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(Command::new("remove".to_owned()).arg(Arg::new("slug".to_owned())))
+}
+
+/// Size is the distance from the center to the edge;
+/// the new border is created around the world spawn
+pub(crate) fn command_border(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(border_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    let slug = get_sender_world_slug(&sender, border_subcommand)?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    let name = border_subcommand.get_name().as_str();
+    if name == "remove" {
+        if world_manager.get_border().is_none() {
+            sender.send_console_message(format!("World \"{}\" has no border", slug));
+            return Ok(());
+        }
+        world_manager.set_border(None)?;
+        sender.send_console_message(format!("Border of world \"{}\" is removed", slug));
+        return Ok(());
+    }
+
+    let mut border = match world_manager.get_border() {
+        Some(b) => b.clone(),
+        None if name == "set" => {
+            let spawn = world_manager.get_spawn();
+            WorldBorder::create(
+                spawn.get_x() as f32 + 0.5,
+                spawn.get_z() as f32 + 0.5,
+                WorldBorderShape::Circle,
+                MAX_BORDER_SIZE,
+            )
+        }
+        None => return Err(format!("&cWorld &4\"{}\"&c has no border", slug)),
+    };
+
+    match name {
+        "set" => {
+            let size = border_subcommand.get_arg::<f32, _>("size")?;
+            if !(MIN_BORDER_SIZE..=MAX_BORDER_SIZE).contains(&size) {
+                return Err(format!(
+                    "&cSize must be from {} to {}",
+                    MIN_BORDER_SIZE, MAX_BORDER_SIZE
+                ));
+            }
+            let duration = match border_subcommand.get_arg::<String, _>("duration") {
+                Ok(d) if world_manager.get_border().is_some() => Duration::from_secs(parse_duration(&d)? as u64),
+                _ => Duration::ZERO,
+            };
+            border.resize(size, duration);
+            match duration.is_zero() {
+                true => sender.send_console_message(format!("Border of world \"{}\" is set to {}", slug, size)),
+                false => sender.send_console_message(format!(
+                    "Border of world \"{}\" is resizing to {} within {:.0?}",
+                    slug, size, duration
+                )),
+            }
+        }
+        "center" => {
+            let x = border_subcommand.get_arg::<f32, _>("x")?;
+            let z = border_subcommand.get_arg::<f32, _>("z")?;
+            border.set_center(x, z);
+            sender.send_console_message(format!("Border center of world \"{}\" is set to {} {}", slug, x, z));
+        }
+        "shape" => {
+            let shape = border_subcommand.get_arg::<String, _>("shape")?;
+            let Ok(shape) = WorldBorderShape::from_str(&shape) else {
+                return Err(format!("&cShape &4\"{}\"&c is unknown", shape));
+            };
+            border.set_shape(shape);
+            sender.send_console_message(format!("Border shape of world \"{}\" is set to {}", slug, shape));
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+            return Ok(());
+        }
+    }
+    world_manager.set_border(Some(border))?;
+    Ok(())
+}
@@ -0,0 +1,604 @@
+use ahash::AHashMap;
+use bevy::prelude::{Res, ResMut, Resource};
+use common::{
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+    blocks::block_info::BlockFace,
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::{BlockDataInfo, BlockIndexType},
+        chunk_position::ChunkPosition,
+    },
+    regions::RegionFlag,
+};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    console::console_sender::{Console, ConsoleSender},
+    network::{client_network::ClientNetwork, sync_world_change::sync_world_chunk_change},
+};
+
+use super::{
+    audit_log::AuditLog,
+    edit_history::{BlockChange, EDIT_HISTORY_BLOCKS_LIMIT, EditAction},
+    world_manager::WorldManager,
+    worlds_manager::WorldsManager,
+};
+
+/// How many blocks are visited by the bulk edits during one tick
+pub const BULK_EDIT_BLOCKS_PER_TICK: usize = 65_536;
+
+/// Maximum volume of the single bulk edit
+pub const BULK_EDIT_MAX_BLOCKS: usize = 4 * 1024 * 1024;
+
+// Chunks ahead of the edit which are requested to load
+const PRELOAD_CHUNKS: usize = 8;
+const TICKET_OWNER: &str = "bulk_edit";
+const TICKET_DURATION: Duration = Duration::from_secs(30);
+
+// Positions of the rollback conflicts shown to the sender
+const CONFLICTS_SHOWN: usize = 20;
+
+/// Blocks copied relative to the player's position
+#[derive(Clone, Default)]
+pub struct Clipboard {
+    blocks: Vec<(BlockPosition, Option<BlockDataInfo>)>,
+}
+
+impl Clipboard {
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Clockwise rotation around the vertical axis by 90 degrees per turn
+    pub fn rotate(&mut self, turns: u8) {
+        for _ in 0..(turns % 4) {
+            for (position, block_info) in self.blocks.iter_mut() {
+                *position = BlockPosition::new(-position.get_z(), position.get_y(), position.get_x());
+                if let Some(block_info) = block_info {
+                    let face = block_info.get_face().map(|f| f.rotate_left());
+                    block_info.set_face(face);
+                }
+            }
+        }
+    }
+
+    /// Mirrors the blocks along the axis: "x", "y" or "z"
+    pub fn flip(&mut self, axis: &str) {
+        for (position, block_info) in self.blocks.iter_mut() {
+            let (x, y, z) = (position.get_x(), position.get_y(), position.get_z());
+            *position = match axis {
+                "x" => BlockPosition::new(-x, y, z),
+                "y" => BlockPosition::new(x, -y, z),
+                _ => BlockPosition::new(x, y, -z),
+            };
+            let Some(block_info) = block_info else {
+                continue;
+            };
+            let face = block_info.get_face().map(|f| match (axis, f) {
+                ("x", BlockFace::East) => BlockFace::West,
+                ("x", BlockFace::West) => BlockFace::East,
+                ("z", BlockFace::North) => BlockFace::South,
+                ("z", BlockFace::South) => BlockFace::North,
+                (_, f) => *f,
+            });
+            block_info.set_face(face);
+        }
+    }
+}
+
+/// Selected area and clipboard of the player
+#[derive(Default)]
+pub struct EditSelection {
+    world_slug: Option<String>,
+    pos1: Option<BlockPosition>,
+    pos2: Option<BlockPosition>,
+    clipboard: Option<Clipboard>,
+}
+
+impl EditSelection {
+    /// Selection in another world is reset
+    fn set_world(&mut self, world_slug: &String) {
+        if self.world_slug.as_ref() != Some(world_slug) {
+            self.pos1 = None;
+            self.pos2 = None;
+            self.world_slug = Some(world_slug.clone());
+        }
+    }
+
+    pub fn set_pos1(&mut self, world_slug: &String, position: BlockPosition) {
+        self.set_world(world_slug);
+        self.pos1 = Some(position);
+    }
+
+    pub fn set_pos2(&mut self, world_slug: &String, position: BlockPosition) {
+        self.set_world(world_slug);
+        self.pos2 = Some(position);
+    }
+
+    /// Returns the world and min/max corners of the selection
+    pub fn get_area(&self) -> Result<(String, BlockPosition, BlockPosition), String> {
+        let (Some(world_slug), Some(pos1), Some(pos2)) = (self.world_slug.as_ref(), self.pos1, self.pos2) else {
+            return Err("&cSelect the area with &4pos1&c and &4pos2&c first".to_string());
+        };
+        let min = BlockPosition::new(
+            pos1.get_x().min(pos2.get_x()),
+            pos1.get_y().min(pos2.get_y()).max(0),
+            pos1.get_z().min(pos2.get_z()),
+        );
+        let max = BlockPosition::new(
+            pos1.get_x().max(pos2.get_x()),
+            pos1.get_y().max(pos2.get_y()).min(get_world_height() - 1),
+            pos1.get_z().max(pos2.get_z()),
+        );
+        if min.get_y() > max.get_y() {
+            return Err("&cSelection is outside of the world height".to_string());
+        }
+        let volume = get_volume(&min, &max);
+        if volume > BULK_EDIT_MAX_BLOCKS {
+            return Err(format!(
+                "&cSelection of &4{}&c blocks is bigger than &4{}",
+                volume, BULK_EDIT_MAX_BLOCKS
+            ));
+        }
+        Ok((world_slug.clone(), min, max))
+    }
+
+    pub fn get_clipboard(&self) -> Option<&Clipboard> {
+        self.clipboard.as_ref()
+    }
+
+    pub fn get_clipboard_mut(&mut self) -> Option<&mut Clipboard> {
+        self.clipboard.as_mut()
+    }
+
+    pub fn set_clipboard(&mut self, clipboard: Clipboard) {
+        self.clipboard = Some(clipboard);
+    }
+}
+
+fn get_world_height() -> i64 {
+    CHUNK_SIZE as i64 * VERTICAL_SECTIONS as i64
+}
+
+pub fn get_volume(min: &BlockPosition, max: &BlockPosition) -> usize {
+    ((max.get_x() - min.get_x() + 1) * (max.get_y() - min.get_y() + 1) * (max.get_z() - min.get_z() + 1)) as usize
+}
+
+pub enum BulkOperation {
+    Set(Option<BlockDataInfo>),
+    // None is the air
+    Replace(Option<BlockIndexType>, Option<BlockDataInfo>),
+    Walls(Option<BlockDataInfo>),
+    // Blocks are copied relative to the origin
+    Copy(BlockPosition),
+    Paste(Clipboard, BlockPosition),
+    // Blocks at their absolute positions with the blocks which must be there before the restoring
+    Restore(Vec<(BlockPosition, Option<BlockDataInfo>, Option<BlockDataInfo>)>),
+}
+
+impl BulkOperation {
+    fn get_name(&self) -> &'static str {
+        match self {
+            BulkOperation::Set(_) => "set",
+            BulkOperation::Replace(..) => "replace",
+            BulkOperation::Walls(_) => "walls",
+            BulkOperation::Copy(_) => "copy",
+            BulkOperation::Paste(..) => "paste",
+            BulkOperation::Restore(_) => "rollback",
+        }
+    }
+}
+
+/// Bulk edit which is applied chunk by chunk over several ticks
+///
+/// Edits of the console have no client and ignore the regions.
+/// Rollbacks ignore the regions too: restored blocks are often inside of the other players' regions
+pub struct BulkEditJob {
+    client: Option<ClientNetwork>,
+    login: Option<String>,
+    world_slug: String,
+    operation: BulkOperation,
+    min: BlockPosition,
+    max: BlockPosition,
+
+    // Chunks left to process
+    chunks: VecDeque<ChunkPosition>,
+    volume: usize,
+
+    // Pasted blocks grouped by chunks
+    pasted: AHashMap<ChunkPosition, Vec<(BlockPosition, Option<BlockDataInfo>)>>,
+    // Restored blocks are skipped if they were changed after the rollback was requested
+    expected: AHashMap<BlockPosition, Option<BlockDataInfo>>,
+    conflicts: Vec<BlockPosition>,
+
+    // Changes of the current tick which are not written to the audit log yet
+    changes: Vec<BlockChange>,
+    changed: usize,
+    // Recorded for the player's undo history; dropped if the edit is too big to be undone
+    history: Option<Vec<BlockChange>>,
+    copied: Vec<(BlockPosition, Option<BlockDataInfo>)>,
+    started: Instant,
+}
+
+impl BulkEditJob {
+    pub fn create(
+        client: Option<ClientNetwork>,
+        login: Option<String>,
+        world_slug: String,
+        operation: BulkOperation,
+        min: BlockPosition,
+        max: BlockPosition,
+    ) -> Self {
+        let history = client.as_ref().map(|_| Default::default());
+        let mut pasted: AHashMap<ChunkPosition, Vec<(BlockPosition, Option<BlockDataInfo>)>> = Default::default();
+        let mut chunks: VecDeque<ChunkPosition> = Default::default();
+        let placed: Option<Vec<(BlockPosition, Option<BlockDataInfo>)>> = match &operation {
+            BulkOperation::Paste(clipboard, origin) => Some(
+                clipboard
+                    .blocks
+                    .iter()
+                    .map(|(offset, block_info)| (get_paste_position(origin, offset), *block_info))
+                    .collect(),
+            ),
+            BulkOperation::Restore(blocks) => Some(blocks.iter().map(|(p, b, _)| (*p, *b)).collect()),
+            _ => None,
+        };
+        let expected = match &operation {
+            BulkOperation::Restore(blocks) => blocks.iter().map(|(p, _, e)| (*p, *e)).collect(),
+            _ => Default::default(),
+        };
+        let volume = match placed {
+            Some(placed) => {
+                let volume = placed.len();
+                for (position, block_info) in placed {
+                    if position.get_y() < 0 || position.get_y() >= get_world_height() {
+                        continue;
+                    }
+                    pasted
+                        .entry(position.get_chunk_position())
+                        .or_default()
+                        .push((position, block_info));
+                }
+                let mut keys: Vec<ChunkPosition> = pasted.keys().cloned().collect();
+                keys.sort_by_key(|c| (c.x, c.z));
+                chunks.extend(keys);
+                volume
+            }
+            None => {
+                let (min_chunk, max_chunk) = (min.get_chunk_position(), max.get_chunk_position());
+                for x in min_chunk.x..=max_chunk.x {
+                    for z in min_chunk.z..=max_chunk.z {
+                        chunks.push_back(ChunkPosition::new(x, z));
+                    }
+                }
+                get_volume(&min, &max)
+            }
+        };
+        Self {
+            client,
+            login,
+            world_slug,
+            operation,
+            min,
+            max,
+            chunks,
+            volume,
+            pasted,
+            expected,
+            conflicts: Default::default(),
+            changes: Default::default(),
+            changed: 0,
+            history,
+            copied: Default::default(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn get_volume(&self) -> usize {
+        self.volume
+    }
+
+    /// Blocks of the chunk which must be changed or copied
+    fn get_chunk_blocks(&self, chunk: &ChunkPosition) -> Vec<BlockPosition> {
+        let size = CHUNK_SIZE as i64;
+        let (min_x, max_x) = (
+            self.min.get_x().max(chunk.x * size),
+            self.max.get_x().min(chunk.x * size + size - 1),
+        );
+        let (min_z, max_z) = (
+            self.min.get_z().max(chunk.z * size),
+            self.max.get_z().min(chunk.z * size + size - 1),
+        );
+        let mut blocks: Vec<BlockPosition> = Default::default();
+        for x in min_x..=max_x {
+            for y in self.min.get_y()..=self.max.get_y() {
+                for z in min_z..=max_z {
+                    blocks.push(BlockPosition::new(x, y, z));
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Returns None if the block must stay unchanged
+    fn get_new_block(
+        &mut self,
+        position: &BlockPosition,
+        old_block_info: &Option<BlockDataInfo>,
+    ) -> Option<Option<BlockDataInfo>> {
+        match &self.operation {
+            BulkOperation::Set(block_info) => Some(*block_info),
+            BulkOperation::Replace(from, to) => match old_block_info.map(|b| b.get_id()) == *from {
+                true => Some(*to),
+                false => None,
+            },
+            BulkOperation::Walls(block_info) => {
+                let wall = position.get_x() == self.min.get_x()
+                    || position.get_x() == self.max.get_x()
+                    || position.get_z() == self.min.get_z()
+                    || position.get_z() == self.max.get_z();
+                match wall {
+                    true => Some(*block_info),
+                    false => None,
+                }
+            }
+            BulkOperation::Copy(origin) => {
+                let offset = position.offset(-origin.get_x(), -origin.get_y(), -origin.get_z());
+                self.copied.push((offset, *old_block_info));
+                None
+            }
+            // Placed blocks are known in advance
+            BulkOperation::Paste(..) | BulkOperation::Restore(_) => None,
+        }
+    }
+
+    /// Returns the count of the visited blocks
+    fn process_chunk(&mut self, world_manager: &WorldManager, chunk: &ChunkPosition) -> usize {
+        let chunks_map = world_manager.get_chunks_map();
+        let regions = world_manager.get_regions();
+
+        let blocks: Vec<(BlockPosition, Option<Option<BlockDataInfo>>)> = match self.pasted.remove(chunk) {
+            Some(pasted) => pasted.into_iter().map(|(p, b)| (p, Some(b))).collect(),
+            None => self.get_chunk_blocks(chunk).into_iter().map(|p| (p, None)).collect(),
+        };
+
+        let visited = blocks.len();
+        let mut changed = false;
+        let restore = matches!(self.operation, BulkOperation::Restore(_));
+        for (position, pasted_block) in blocks {
+            let old_block_info = chunks_map.get_block_info(&position);
+            let new_block_info = match pasted_block {
+                Some(b) => b,
+                None => match self.get_new_block(&position, &old_block_info) {
+                    Some(b) => b,
+                    None => continue,
+                },
+            };
+            if new_block_info == old_block_info {
+                continue;
+            }
+            if let Some(expected) = self.expected.get(&position) {
+                if *expected != old_block_info {
+                    self.conflicts.push(position);
+                    continue;
+                }
+            }
+            if !world_manager.is_inside_border(&position) {
+                continue;
+            }
+            let flag = match new_block_info {
+                Some(_) => RegionFlag::Build,
+                None => RegionFlag::Break,
+            };
+            if let Some(login) = self.login.as_ref().filter(|_| !restore) {
+                if regions.check_action(&position, login, &flag).is_err() {
+                    continue;
+                }
+            }
+            if chunks_map
+                .edit_block_no_light(position.clone(), new_block_info)
+                .is_err()
+            {
+                continue;
+            }
+            self.changes
+                .push(BlockChange::new(position, old_block_info, new_block_info));
+            changed = true;
+        }
+
+        // Whole chunk is sent instead of the separate blocks
+        if changed {
+            chunks_map.relight_chunk(chunk);
+            sync_world_chunk_change(world_manager, chunk);
+        }
+        visited
+    }
+
+    /// Returns true when the job is done
+    fn update(&mut self, world_manager: &mut WorldManager) -> bool {
+        // Unloaded chunks are loaded from the storage in advance
+        for chunk in self.chunks.iter().take(PRELOAD_CHUNKS) {
+            world_manager.get_chunks_map_mut().add_temporary_ticket(
+                chunk.clone(),
+                TICKET_OWNER.to_string(),
+                TICKET_DURATION,
+            );
+        }
+
+        let mut visited = 0;
+        while visited < BULK_EDIT_BLOCKS_PER_TICK {
+            let Some(chunk) = self.chunks.front().cloned() else {
+                return true;
+            };
+            // Chunks outside of the world border are never loaded
+            if !world_manager.get_chunks_map().is_chunk_inside_border(&chunk) {
+                self.chunks.pop_front();
+                continue;
+            }
+            if !world_manager.get_chunks_map().is_chunk_loaded(&chunk) {
+                return false;
+            }
+            visited += self.process_chunk(world_manager, &chunk);
+            world_manager
+                .get_chunks_map_mut()
+                .remove_temporary_ticket(&chunk, TICKET_OWNER.to_string());
+            self.chunks.pop_front();
+        }
+        self.chunks.is_empty()
+    }
+
+    fn send_message(&self, message: String) {
+        match self.client.as_ref() {
+            Some(client) => client.send_console_message(message),
+            None => Console::default().send_console_message(message),
+        }
+    }
+
+    /// Writes the new changes to the audit log and the undo history
+    fn record_changes(&mut self, audit_log: &AuditLog) {
+        let login = self.login.clone().unwrap_or(Console::default().to_string());
+        for change in self.changes.drain(..) {
+            audit_log.record(&login, &self.world_slug, &change);
+            self.changed += 1;
+
+            match self.history.as_mut() {
+                Some(history) if history.len() < EDIT_HISTORY_BLOCKS_LIMIT => history.push(change),
+                Some(_) => self.history = None,
+                None => (),
+            }
+        }
+    }
+
+    fn finish(self) {
+        let name = self.operation.get_name();
+        if let BulkOperation::Copy(_) = self.operation {
+            let clipboard = Clipboard { blocks: self.copied };
+            self.send_message(format!("{} blocks copied", clipboard.len()));
+            if let Some(client) = self.client.as_ref() {
+                client.get_edit_selection_mut().set_clipboard(clipboard);
+            }
+            return;
+        }
+        self.send_message(format!(
+            "Operation {} is done: {} blocks changed (executed:{:.2?})",
+            name,
+            self.changed,
+            self.started.elapsed()
+        ));
+        if !self.conflicts.is_empty() {
+            self.send_message(format!(
+                "&c{} blocks were changed by someone else and are skipped:",
+                self.conflicts.len()
+            ));
+            for position in self.conflicts.iter().take(CONFLICTS_SHOWN) {
+                self.send_message(format!(" - {:?}", position));
+            }
+        }
+        log::info!(
+            target: "worlds",
+            "&e\"{}\"&r bulk {} in &a\"{}\"&r changed {} blocks",
+            self.login.clone().unwrap_or(Console::default().to_string()),
+            name,
+            self.world_slug,
+            self.changed
+        );
+        if let Some(client) = self.client.as_ref() {
+            match self.history {
+                Some(history) => client
+                    .get_edit_history_mut()
+                    .push(EditAction::new(self.world_slug, history)),
+                None => client.send_console_message(format!(
+                    "&cOperation {} changed more than &4{}&c blocks and can't be undone",
+                    name, EDIT_HISTORY_BLOCKS_LIMIT
+                )),
+            }
+        }
+    }
+}
+
+fn get_paste_position(origin: &BlockPosition, offset: &BlockPosition) -> BlockPosition {
+    origin.offset(offset.get_x(), offset.get_y(), offset.get_z())
+}
+
+/// Queue of the bulk edits; jobs are applied one by one
+#[derive(Resource, Default)]
+pub struct BulkEditQueue {
+    jobs: VecDeque<BulkEditJob>,
+}
+
+impl BulkEditQueue {
+    /// Returns the count of jobs before this one
+    pub fn push(&mut self, job: BulkEditJob) -> usize {
+        self.jobs.push_back(job);
+        self.jobs.len() - 1
+    }
+}
+
+pub fn update_bulk_edits(
+    worlds_manager: Res<WorldsManager>,
+    mut queue: ResMut<BulkEditQueue>,
+    audit_log: Res<AuditLog>,
+) {
+    let Some(job) = queue.jobs.front_mut() else {
+        return;
+    };
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&job.world_slug) else {
+        queue.jobs.pop_front();
+        return;
+    };
+    let done = job.update(&mut *world_manager);
+    job.record_changes(&audit_log);
+    if done {
+        drop(world_manager);
+        queue.jobs.pop_front().unwrap().finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clipboard, EditSelection};
+    use common::{
+        blocks::block_info::BlockFace,
+        chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo},
+    };
+
+    #[test]
+    fn test_clipboard_transform() {
+        let block = BlockDataInfo::create(1, Some(BlockFace::North));
+        let mut clipboard = Clipboard {
+            blocks: vec![(BlockPosition::new(1, 0, 2), Some(block))],
+        };
+
+        clipboard.rotate(1);
+        assert_eq!(clipboard.blocks[0].0, BlockPosition::new(-2, 0, 1));
+        assert_eq!(clipboard.blocks[0].1.unwrap().get_face(), Some(&BlockFace::East));
+
+        // Full turn returns the blocks back
+        clipboard.rotate(3);
+        assert_eq!(clipboard.blocks[0].0, BlockPosition::new(1, 0, 2));
+
+        clipboard.flip("z");
+        assert_eq!(clipboard.blocks[0].0, BlockPosition::new(1, 0, -2));
+        assert_eq!(clipboard.blocks[0].1.unwrap().get_face(), Some(&BlockFace::South));
+    }
+
+    #[test]
+    fn test_selection_area() {
+        let world = "default".to_string();
+        let mut selection = EditSelection::default();
+        assert!(selection.get_area().is_err());
+
+        selection.set_pos1(&world, BlockPosition::new(5, -10, 5));
+        selection.set_pos2(&world, BlockPosition::new(0, 10, 0));
+        let (_world, min, max) = selection.get_area().unwrap();
+        assert_eq!(min, BlockPosition::new(0, 0, 0));
+        assert_eq!(max, BlockPosition::new(5, 10, 5));
+
+        // Selection is reset in another world
+        selection.set_pos1(&"other".to_string(), BlockPosition::new(0, 0, 0));
+        assert!(selection.get_area().is_err());
+    }
+}
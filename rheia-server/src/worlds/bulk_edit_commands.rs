@@ -0,0 +1,300 @@
+use crate::client_resources::server_settings::ServerSettings;
+use crate::console::console_sender::ConsoleSenderType;
+use crate::entities::entity::Position;
+use crate::network::client_network::ClientNetwork;
+use bevy_ecs::world::World;
+use common::chunks::block_position::BlockPosition;
+use common::chunks::chunk_data::{BlockDataInfo, BlockIndexType};
+use common::commands::command::{Arg, Command, CommandMatch};
+
+use super::bulk_edit::{BulkEditJob, BulkEditQueue, BulkOperation};
+use super::worlds_manager::WorldsManager;
+
+/// Player who sent the command with his world and current block
+pub(crate) struct EditPlayer {
+    pub(crate) client: ClientNetwork,
+    pub(crate) login: String,
+    pub(crate) world_slug: String,
+    pub(crate) position: BlockPosition,
+}
+
+pub(crate) fn get_edit_player(world: &World, sender: &Box<dyn ConsoleSenderType>) -> Option<EditPlayer> {
+    let Some(client) = sender.as_any().downcast_ref::<ClientNetwork>() else {
+        sender.send_console_message("This command is allowed to be used only for players".to_string());
+        return None;
+    };
+    let login = client.get_client_info()?.get_login().clone();
+    let Some(world_entity) = client.get_world_entity() else {
+        sender.send_console_message(format!("Player \"{}\" is not in the world", login));
+        return None;
+    };
+
+    let worlds_manager = world.resource::<WorldsManager>();
+    let world_manager = worlds_manager.get_world_manager(world_entity.get_world_slug())?;
+    let position = world_manager
+        .get_ecs()
+        .get_entity(world_entity.get_entity())
+        .and_then(|e| e.get::<Position>().cloned())?;
+    Some(EditPlayer {
+        client: client.clone(),
+        login,
+        world_slug: world_entity.get_world_slug().clone(),
+        position: BlockPosition::from_position(&position.to_network()),
+    })
+}
+
+/// Block by its slug; "air" is an empty block
+fn parse_block(server_settings: &ServerSettings, name: &String) -> Result<Option<BlockIndexType>, String> {
+    if name == "air" {
+        return Ok(None);
+    }
+    for (id, slug) in server_settings.get_block_id_map().iter() {
+        if slug == name {
+            return Ok(Some(*id));
+        }
+    }
+    Err(format!("&cblock &4\"{}\"&c not found", name))
+}
+
+fn parse_block_info(world: &World, args: &CommandMatch, arg_name: &str) -> Result<Option<BlockDataInfo>, String> {
+    let server_settings = world.resource::<ServerSettings>();
+    let block_id = parse_block(server_settings, &args.get_arg::<String, _>(arg_name)?)?;
+    Ok(block_id.map(|id| BlockDataInfo::create(id, None)))
+}
+
+fn start_job(world: &mut World, sender: &Box<dyn ConsoleSenderType>, job: BulkEditJob) {
+    let volume = job.get_volume();
+    let queued = world.resource_mut::<BulkEditQueue>().push(job);
+    match queued {
+        0 => sender.send_console_message(format!("Bulk edit of {} blocks started", volume)),
+        _ => sender.send_console_message(format!(
+            "Bulk edit of {} blocks is queued after {} other edits",
+            volume, queued
+        )),
+    }
+}
+
+pub(crate) fn command_parser_pos1() -> Command {
+    Command::new("pos1".to_owned())
+}
+
+pub(crate) fn command_pos1(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    _args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    player
+        .client
+        .get_edit_selection_mut()
+        .set_pos1(&player.world_slug, player.position);
+    sender.send_console_message(format!("First position is set to {:?}", player.position));
+    Ok(())
+}
+
+pub(crate) fn command_parser_pos2() -> Command {
+    Command::new("pos2".to_owned())
+}
+
+pub(crate) fn command_pos2(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    _args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    player
+        .client
+        .get_edit_selection_mut()
+        .set_pos2(&player.world_slug, player.position);
+    sender.send_console_message(format!("Second position is set to {:?}", player.position));
+    Ok(())
+}
+
+pub(crate) fn command_parser_set() -> Command {
+    Command::new("set".to_owned()).arg(Arg::new("block".to_owned()).required(true))
+}
+
+pub(crate) fn command_set(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let block_info = parse_block_info(world, &args, "block")?;
+    let (world_slug, min, max) = player.client.get_edit_selection_mut().get_area()?;
+    let job = BulkEditJob::create(
+        Some(player.client),
+        Some(player.login),
+        world_slug,
+        BulkOperation::Set(block_info),
+        min,
+        max,
+    );
+    start_job(world, &sender, job);
+    Ok(())
+}
+
+pub(crate) fn command_parser_replace() -> Command {
+    Command::new("replace".to_owned())
+        .arg(Arg::new("from".to_owned()).required(true))
+        .arg(Arg::new("to".to_owned()).required(true))
+}
+
+pub(crate) fn command_replace(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let from = parse_block(world.resource::<ServerSettings>(), &args.get_arg::<String, _>("from")?)?;
+    let to = parse_block_info(world, &args, "to")?;
+    let (world_slug, min, max) = player.client.get_edit_selection_mut().get_area()?;
+    let job = BulkEditJob::create(
+        Some(player.client),
+        Some(player.login),
+        world_slug,
+        BulkOperation::Replace(from, to),
+        min,
+        max,
+    );
+    start_job(world, &sender, job);
+    Ok(())
+}
+
+pub(crate) fn command_parser_walls() -> Command {
+    Command::new("walls".to_owned()).arg(Arg::new("block".to_owned()).required(true))
+}
+
+pub(crate) fn command_walls(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let block_info = parse_block_info(world, &args, "block")?;
+    let (world_slug, min, max) = player.client.get_edit_selection_mut().get_area()?;
+    let job = BulkEditJob::create(
+        Some(player.client),
+        Some(player.login),
+        world_slug,
+        BulkOperation::Walls(block_info),
+        min,
+        max,
+    );
+    start_job(world, &sender, job);
+    Ok(())
+}
+
+pub(crate) fn command_parser_copy() -> Command {
+    Command::new("copy".to_owned())
+}
+
+/// Blocks are copied relative to the player's position
+pub(crate) fn command_copy(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    _args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let (world_slug, min, max) = player.client.get_edit_selection_mut().get_area()?;
+    if world_slug != player.world_slug {
+        return Err("&cSelection is in another world".to_string());
+    }
+    let job = BulkEditJob::create(
+        Some(player.client),
+        Some(player.login),
+        world_slug,
+        BulkOperation::Copy(player.position),
+        min,
+        max,
+    );
+    start_job(world, &sender, job);
+    Ok(())
+}
+
+pub(crate) fn command_parser_paste() -> Command {
+    Command::new("paste".to_owned())
+}
+
+pub(crate) fn command_paste(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    _args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let Some(clipboard) = player.client.get_edit_selection_mut().get_clipboard().cloned() else {
+        return Err("&cClipboard is empty; use &4copy&c first".to_string());
+    };
+    let job = BulkEditJob::create(
+        Some(player.client),
+        Some(player.login),
+        player.world_slug,
+        BulkOperation::Paste(clipboard, player.position),
+        player.position,
+        player.position,
+    );
+    start_job(world, &sender, job);
+    Ok(())
+}
+
+pub(crate) fn command_parser_rotate() -> Command {
+    Command::new("rotate".to_owned()).arg(
+        Arg::new("degrees".to_owned())
+            .required(true)
+            .choices(vec!["90", "180", "270"]),
+    )
+}
+
+pub(crate) fn command_rotate(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let degrees = args.get_arg::<u16, _>("degrees")?;
+    let mut selection = player.client.get_edit_selection_mut();
+    let Some(clipboard) = selection.get_clipboard_mut() else {
+        return Err("&cClipboard is empty; use &4copy&c first".to_string());
+    };
+    clipboard.rotate((degrees / 90) as u8);
+    sender.send_console_message(format!("Clipboard is rotated by {} degrees", degrees));
+    Ok(())
+}
+
+pub(crate) fn command_parser_flip() -> Command {
+    Command::new("flip".to_owned()).arg(Arg::new("axis".to_owned()).required(true).choices(vec!["x", "y", "z"]))
+}
+
+pub(crate) fn command_flip(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(player) = get_edit_player(world, &sender) else {
+        return Ok(());
+    };
+    let axis = args.get_arg::<String, _>("axis")?;
+    let mut selection = player.client.get_edit_selection_mut();
+    let Some(clipboard) = selection.get_clipboard_mut() else {
+        return Err("&cClipboard is empty; use &4copy&c first".to_string());
+    };
+    clipboard.flip(&axis);
+    sender.send_console_message(format!("Clipboard is flipped along {}", axis));
+    Ok(())
+}
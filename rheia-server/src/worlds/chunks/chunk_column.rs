@@ -1,6 +1,10 @@
+use clap::ValueEnum;
 use common::chunks::block_position::ChunkBlockPosition;
 use common::chunks::chunk_data::{BlockDataInfo, ChunkData};
 use common::chunks::chunk_position::ChunkPosition;
+use common::chunks::light_propagation::{LightArea, LightBlocks};
+use common::chunks::scheduled_tick::ScheduledTick;
+use common::chunks::stored_entity::StoredEntity;
 use common::world_generator::default::WorldGenerator;
 use common::world_generator::traits::IWorldGenerator;
 use common::worlds_storage::taits::IWorldStorage;
@@ -9,11 +13,53 @@ use network::messages::ServerMessages;
 use parking_lot::RwLock;
 use std::fmt::Display;
 use std::{sync::Arc, time::Duration};
+use strum_macros::Display;
 
 use crate::network::runtime_plugin::RuntimePlugin;
+use crate::{CHUNK_SAVE_RETRY_DELAY, CHUNK_SAVE_RETRY_MAX_DELAY};
 
 use super::chunks_map::StorageLock;
 
+/// What to do with the chunk which data can't be read from the storage
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CorruptChunkPolicy {
+    /// Generate the chunk again by the world generator
+    #[default]
+    Regenerate,
+    /// Leave the chunk without any blocks
+    Empty,
+}
+
+/// Failed saves and loads are retried with exponential backoff
+#[derive(Default)]
+struct Retry {
+    attempts: u32,
+    delay: Duration,
+}
+
+impl Retry {
+    /// Returns true if the previous attempt was failed
+    /// and the next one is not yet due
+    fn is_delayed(&mut self, delta: Duration) -> bool {
+        if self.delay.is_zero() {
+            return false;
+        }
+        self.delay = self.delay.saturating_sub(delta);
+        true
+    }
+
+    /// Returns the number of failed attempts and the delay before the next one
+    fn failed(&mut self) -> (u32, Duration) {
+        self.attempts += 1;
+        let multiplier = 2_u32.saturating_pow(self.attempts - 1);
+        self.delay = CHUNK_SAVE_RETRY_DELAY
+            .saturating_mul(multiplier)
+            .min(CHUNK_SAVE_RETRY_MAX_DELAY);
+        (self.attempts, self.delay)
+    }
+}
+
 pub struct ChunkColumn {
     chunk_position: ChunkPosition,
     world_slug: String,
@@ -21,6 +67,21 @@ pub struct ChunkColumn {
     pub sections: ChunkData,
     despawn_timer: Arc<RwLock<Duration>>,
     loaded: bool,
+
+    // False if the storage state of the chunk is unknown,
+    // so its data must not be written over
+    persistent: bool,
+    save_retry: Arc<RwLock<Retry>>,
+
+    // Set when the storage could not be read, the loading is sent again after the delay
+    load_failed: bool,
+    load_retry: Retry,
+
+    // Pending block updates are saved together with the chunk
+    scheduled_ticks: Vec<ScheduledTick>,
+
+    // Npcs waiting to be spawned into the world after the loading
+    stored_entities: Vec<StoredEntity>,
 }
 
 impl Display for ChunkColumn {
@@ -43,6 +104,12 @@ impl ChunkColumn {
             chunk_position,
             world_slug,
             loaded: false,
+            persistent: true,
+            save_retry: Default::default(),
+            load_failed: false,
+            load_retry: Default::default(),
+            scheduled_ticks: Default::default(),
+            stored_entities: Default::default(),
         }
     }
 
@@ -55,6 +122,79 @@ impl ChunkColumn {
         self.loaded
     }
 
+    #[cfg(test)]
+    pub(crate) fn set_loaded(&mut self) {
+        self.loaded = true;
+    }
+
+    /// If chunk data can be saved into the storage
+    pub(crate) fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    /// Returns true if the previous save was failed
+    /// and the next attempt is not yet due
+    pub(crate) fn is_save_delayed(&self, delta: Duration) -> bool {
+        self.save_retry.write().is_delayed(delta)
+    }
+
+    /// Returns the number of failed attempts and the delay before the next one
+    pub(crate) fn save_failed(&self) -> (u32, Duration) {
+        self.save_retry.write().failed()
+    }
+
+    /// If the storage could not be read and the chunk waits for the next loading
+    pub(crate) fn is_load_failed(&self) -> bool {
+        self.load_failed
+    }
+
+    /// Returns true once the failed chunk must be sent to load again
+    pub(crate) fn is_load_retry_due(&mut self, delta: Duration) -> bool {
+        if !self.load_failed || self.load_retry.is_delayed(delta) {
+            return false;
+        }
+        self.load_failed = false;
+        true
+    }
+
+    pub(crate) fn take_stored_entities(&mut self) -> Vec<StoredEntity> {
+        std::mem::take(&mut self.stored_entities)
+    }
+
+    pub(crate) fn get_scheduled_ticks(&self) -> &Vec<ScheduledTick> {
+        &self.scheduled_ticks
+    }
+
+    /// Schedules the update of the block;
+    /// if the position is already scheduled, the earliest update is kept
+    pub(crate) fn schedule_tick(&mut self, tick: ScheduledTick) {
+        let existing = self
+            .scheduled_ticks
+            .iter_mut()
+            .find(|t| t.get_position() == tick.get_position());
+        match existing {
+            Some(existing) => {
+                if tick.get_delay() < existing.get_delay() {
+                    existing.set_delay(tick.get_delay());
+                }
+            }
+            None => self.scheduled_ticks.push(tick),
+        }
+    }
+
+    /// Advances all scheduled updates by one tick and returns the due ones
+    pub(crate) fn pop_due_ticks(&mut self) -> Vec<ScheduledTick> {
+        let mut due: Vec<ScheduledTick> = Default::default();
+        self.scheduled_ticks.retain_mut(|tick| {
+            if tick.tick() {
+                due.push(tick.clone());
+                return false;
+            }
+            true
+        });
+        due
+    }
+
     pub fn change_block(
         &mut self,
         section: u32,
@@ -80,6 +220,7 @@ impl ChunkColumn {
         return ServerMessages::ChunkSectionInfoEncoded {
             world_slug: self.world_slug.clone(),
             encoded: self.sections.encode_zip(),
+            light: self.sections.get_light().encode_zip(),
             chunk_position: self.chunk_position.clone(),
         };
     }
@@ -90,6 +231,8 @@ pub(crate) fn load_chunk(
     storage: StorageLock,
     chunk_column: Arc<RwLock<ChunkColumn>>,
     loaded_chunks: flume::Sender<ChunkPosition>,
+    corrupt_policy: CorruptChunkPolicy,
+    light_blocks: Arc<LightBlocks>,
 ) {
     rayon::spawn(move || {
         #[cfg(feature = "trace")]
@@ -100,38 +243,144 @@ pub(crate) fn load_chunk(
         }
 
         let mut chunk_column = chunk_column.write();
+        let chunk_position = chunk_column.chunk_position.clone();
 
         // Load from storage
-        let index = match storage.lock().has_chunk_data(&chunk_column.chunk_position) {
-            Ok(i) => i,
-            Err(e) => {
-                log::error!(target: "worlds", "&cChunk load error!");
-                log::error!(target: "worlds", "Error: {}", e);
-                RuntimePlugin::stop();
-                return;
+        let mut corrupted = false;
+        let mut from_storage = false;
+        // Storage errors are not the chunk corruption, the policy must not replace the saved data
+        let index = storage.lock().has_chunk_data(&chunk_position);
+        let encoded = match index {
+            Ok(Some(index)) => match storage.lock().load_chunk_encoded(index) {
+                Ok(encoded) => Some((index, encoded)),
+                Err(e) => return storage_error(&mut chunk_column, &e),
+            },
+            Ok(None) => None,
+            Err(e) => return storage_error(&mut chunk_column, &e),
+        };
+        let sections = match encoded {
+            Some((index, encoded)) => {
+                let data = ChunkData::decode_zip(encoded);
+                match data {
+                    Ok(c) => {
+                        from_storage = true;
+                        Some(c)
+                    }
+                    Err(e) => {
+                        let quarantine = storage.lock().quarantine_chunk(index, &e);
+                        if let Err(quarantine_error) = quarantine {
+                            log::error!(
+                                target: "worlds",
+                                "&cChunk {} quarantine error: {}",
+                                chunk_position,
+                                quarantine_error
+                            );
+
+                            // Broken data is still inside the storage
+                            chunk_column.persistent = false;
+                        }
+                        corrupted_chunk_alert(&chunk_column, &e, corrupt_policy);
+                        corrupted = true;
+                        None
+                    }
+                }
             }
+            None => None,
+        };
+
+        chunk_column.sections = match sections {
+            Some(s) => s,
+            None if corrupted && corrupt_policy == CorruptChunkPolicy::Empty => Default::default(),
+            // Or generate new
+            None => world_generator.read().generate_chunk_data(&chunk_position),
         };
-        if let Some(index) = index {
-            chunk_column.sections = match storage.lock().load_chunk_data(index) {
-                Ok(c) => c,
+
+        if from_storage {
+            let ticks = storage.lock().load_scheduled_ticks(&chunk_position);
+            match ticks {
+                Ok(ticks) => chunk_column.scheduled_ticks = ticks,
                 Err(e) => {
-                    log::error!(target: "worlds", "&cChunk load error!");
-                    log::error!(target: "worlds", "Error: {}", e);
-                    RuntimePlugin::stop();
-                    return;
+                    log::error!(target: "worlds", "&cChunk {} scheduled ticks are lost: {}", chunk_position, e);
                 }
-            };
+            }
         }
-        // Or generate new
-        else {
-            chunk_column.sections = world_generator.read().generate_chunk_data(&chunk_column.chunk_position);
+
+        // Entities are stored apart from the blocks, so they survive the corrupted chunk
+        let entities = storage.lock().load_chunk_entities(&chunk_position);
+        match entities {
+            Ok(entities) => chunk_column.stored_entities = entities,
+            Err(e) => {
+                log::error!(target: "worlds", "&cChunk {} entities load error: {}", chunk_position, e);
+            }
         }
+
+        // Light of the neighbours is added after the loading
+        let mut area = LightArea::new(&light_blocks);
+        area.add_column(chunk_position.clone(), &mut chunk_column.sections);
+        area.light_column(&chunk_position);
+
         chunk_column.loaded = true;
 
         if !cfg!(test) {
-            loaded_chunks
-                .send(chunk_column.chunk_position.clone())
-                .expect("channel poisoned");
+            loaded_chunks.send(chunk_position).expect("channel poisoned");
         }
     })
 }
+
+/// The chunk stays unloaded until the next attempt
+fn storage_error(chunk_column: &mut ChunkColumn, error: &String) {
+    let (attempts, delay) = chunk_column.load_retry.failed();
+    chunk_column.load_failed = true;
+    log::error!(
+        target: "worlds",
+        "&4ALERT&c chunk {} of the world \"{}\" can't be read from the storage! Attempt:{} next in {:.0?}",
+        chunk_column.chunk_position,
+        chunk_column.world_slug,
+        attempts,
+        delay,
+    );
+    log::error!(target: "worlds", "Error: {}", error);
+}
+
+fn corrupted_chunk_alert(chunk_column: &ChunkColumn, error: &String, corrupt_policy: CorruptChunkPolicy) {
+    log::error!(
+        target: "worlds",
+        "&4ALERT&c chunk {} of the world \"{}\" is unreadable; policy:{} saved:{}",
+        chunk_column.chunk_position,
+        chunk_column.world_slug,
+        corrupt_policy,
+        chunk_column.persistent,
+    );
+    log::error!(target: "worlds", "Error: {}", error);
+}
+
+#[cfg(test)]
+mod tests {
+    use common::chunks::chunk_position::ChunkPosition;
+    use std::time::Duration;
+
+    use crate::CHUNK_SAVE_RETRY_DELAY;
+
+    use super::{ChunkColumn, storage_error};
+
+    #[test]
+    fn test_load_retry() {
+        let mut chunk_column = ChunkColumn::new(ChunkPosition::new(0, 0), "default".to_string());
+        assert_eq!(chunk_column.is_load_retry_due(Duration::ZERO), false);
+
+        storage_error(&mut chunk_column, &"test".to_string());
+        assert_eq!(chunk_column.is_load_failed(), true);
+        assert_eq!(chunk_column.is_loaded(), false);
+
+        assert_eq!(chunk_column.is_load_retry_due(CHUNK_SAVE_RETRY_DELAY / 2), false);
+        assert_eq!(chunk_column.is_load_retry_due(CHUNK_SAVE_RETRY_DELAY / 2), false);
+        assert_eq!(chunk_column.is_load_retry_due(Duration::ZERO), true);
+        assert_eq!(chunk_column.is_load_failed(), false);
+
+        // The next failure waits twice longer
+        storage_error(&mut chunk_column, &"test".to_string());
+        assert_eq!(chunk_column.is_load_retry_due(CHUNK_SAVE_RETRY_DELAY), false);
+        assert_eq!(chunk_column.is_load_retry_due(CHUNK_SAVE_RETRY_DELAY), false);
+        assert_eq!(chunk_column.is_load_retry_due(Duration::ZERO), true);
+    }
+}
@@ -1,14 +1,55 @@
 use ahash::AHashMap;
 use bevy::prelude::Entity;
 use common::{chunks::chunk_position::ChunkPosition, utils::vec_remove_item};
+use std::time::Duration;
+use strum_macros::Display;
+
+/// Type of the ticket which keeps the chunk loaded
+/// without any player around
+///
+/// Player tickets are stored separately by their entities
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum ChunkTicketType {
+    /// Forced by the `world forceload` command; saved inside the world storage
+    Forced,
+    /// Area around the world spawn
+    SpawnArea,
+    /// Temporary ticket of the plugin by its slug
+    Temporary(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkTicket {
+    ticket_type: ChunkTicketType,
+
+    // Time left until the ticket is expired
+    expires_in: Option<Duration>,
+}
+
+impl ChunkTicket {
+    pub fn get_ticket_type(&self) -> &ChunkTicketType {
+        &self.ticket_type
+    }
+
+    pub fn get_expires_in(&self) -> Option<&Duration> {
+        self.expires_in.as_ref()
+    }
+}
 
 /// Idia was taken from
 /// https://github.com/feather-rs/feather
 /// feather/common/src/chunk/loading.rs
 #[derive(Default)]
 pub struct ChunksLoadState {
+    // Player tickets
     pub(crate) by_chunk: AHashMap<ChunkPosition, Vec<Entity>>,
     by_entity: AHashMap<Entity, Vec<ChunkPosition>>,
+
+    // Chunk where the player is located
+    centers: AHashMap<Entity, ChunkPosition>,
+
+    // Tickets without any entity
+    static_tickets: AHashMap<ChunkPosition, Vec<ChunkTicket>>,
 }
 
 impl ChunksLoadState {
@@ -32,6 +73,117 @@ impl ChunksLoadState {
             vec_remove_item(&mut entities, entity);
         }
         self.by_entity.remove(&entity);
+        self.centers.remove(&entity);
+    }
+
+    /// Remember the chunk of the player to prioritise chunks loading
+    pub fn set_entity_center(&mut self, entity: Entity, chunk: ChunkPosition) {
+        self.centers.insert(entity, chunk);
+    }
+
+    /// Squared distance to the nearest player watching the chunk
+    ///
+    /// Chunks kept only by static tickets are loaded last
+    pub fn get_load_priority(&self, chunk: &ChunkPosition) -> i64 {
+        let mut priority = i64::MAX;
+        if let Some(entities) = self.by_chunk.get(chunk) {
+            for entity in entities.iter() {
+                if let Some(center) = self.centers.get(entity) {
+                    let (dx, dz) = (chunk.x - center.x, chunk.z - center.z);
+                    priority = priority.min(dx * dx + dz * dz);
+                }
+            }
+        }
+        priority
+    }
+
+    /// Start keeping the chunk without players
+    ///
+    /// Ticket with the same type is replaced, so temporary
+    /// tickets can be prolonged
+    pub fn insert_static_ticket(
+        &mut self,
+        chunk: ChunkPosition,
+        ticket_type: ChunkTicketType,
+        expires_in: Option<Duration>,
+    ) {
+        let tickets = self.static_tickets.entry(chunk).or_default();
+        tickets.retain(|t| t.ticket_type != ticket_type);
+        tickets.push(ChunkTicket {
+            ticket_type,
+            expires_in,
+        });
+    }
+
+    /// Returns false if the chunk didn't have such ticket
+    pub fn remove_static_ticket(&mut self, chunk: &ChunkPosition, ticket_type: &ChunkTicketType) -> bool {
+        let Some(tickets) = self.static_tickets.get_mut(chunk) else {
+            return false;
+        };
+        let len = tickets.len();
+        tickets.retain(|t| t.ticket_type != *ticket_type);
+        let removed = tickets.len() != len;
+        if tickets.len() == 0 {
+            self.static_tickets.remove(chunk);
+        }
+        removed
+    }
+
+    /// Removes the ticket type from all chunks
+    pub fn remove_all_static_tickets(&mut self, ticket_type: &ChunkTicketType) {
+        for (_pos, tickets) in self.static_tickets.iter_mut() {
+            tickets.retain(|t| t.ticket_type != *ticket_type);
+        }
+        self.static_tickets.retain(|_pos, tickets| tickets.len() > 0);
+    }
+
+    /// Decreases the time of the temporary tickets and removes expired
+    pub fn update_static_tickets(&mut self, delta: Duration) {
+        for (chunk, tickets) in self.static_tickets.iter_mut() {
+            tickets.retain_mut(|t| {
+                let Some(expires_in) = t.expires_in.as_mut() else {
+                    return true;
+                };
+                if *expires_in <= delta {
+                    log::trace!(target: "chunks", "Chunk {} ticket {} expired", chunk, t.ticket_type);
+                    return false;
+                }
+                *expires_in -= delta;
+                true
+            });
+        }
+        self.static_tickets.retain(|_pos, tickets| tickets.len() > 0);
+    }
+
+    pub fn get_static_tickets(&self, chunk: &ChunkPosition) -> Option<&Vec<ChunkTicket>> {
+        self.static_tickets.get(chunk)
+    }
+
+    /// Returns all chunks with tickets of the type
+    pub fn get_static_tickets_chunks(&self, ticket_type: &ChunkTicketType) -> Vec<ChunkPosition> {
+        let mut chunks: Vec<ChunkPosition> = Default::default();
+        for (chunk, tickets) in self.static_tickets.iter() {
+            if tickets.iter().any(|t| t.ticket_type == *ticket_type) {
+                chunks.push(chunk.clone());
+            }
+        }
+        chunks
+    }
+
+    /// All chunks that must be loaded
+    pub fn get_ticket_chunks(&self) -> Vec<ChunkPosition> {
+        let mut chunks: Vec<ChunkPosition> = Default::default();
+        for (chunk, players) in self.by_chunk.iter() {
+            if players.len() > 0 {
+                chunks.push(chunk.clone());
+            }
+        }
+        for chunk in self.static_tickets.keys() {
+            if self.num_tickets(chunk) == 0 {
+                chunks.push(chunk.clone());
+            }
+        }
+        chunks
     }
 
     /// The number of players who are watching this chunk
@@ -42,6 +194,11 @@ impl ChunksLoadState {
         }
     }
 
+    /// Chunk has any ticket which keeps it loaded
+    pub fn has_any_ticket(&self, chunk: &ChunkPosition) -> bool {
+        self.num_tickets(chunk) > 0 || self.static_tickets.contains_key(chunk)
+    }
+
     /// Returns all entities that wathing the chunk
     pub fn get_chunk_watchers(&self, chunk: &ChunkPosition) -> Option<&Vec<Entity>> {
         match self.by_chunk.get(chunk) {
@@ -1,25 +1,37 @@
 use ahash::AHashMap;
 use bevy::prelude::Entity;
+use bracket_lib::random::RandomNumberGenerator;
 use common::{
     chunks::{
-        block_position::{BlockPosition, BlockPositionTrait},
-        chunk_data::BlockDataInfo,
+        block_position::{BlockPosition, BlockPositionTrait, ChunkBlockPosition},
+        chunk_data::{BlockDataInfo, ChunkData},
         chunk_position::ChunkPosition,
+        scheduled_tick::ScheduledTick,
     }, utils::{spiral_iterator::SpiralIterator, vec_remove_item}, world_generator::{
         default::{WorldGenerator, WorldGeneratorSettings},
         traits::IWorldGenerator,
-    }, worlds_storage::taits::IWorldStorage, WorldStorageManager, VERTICAL_SECTIONS
+    }, worlds_storage::taits::IWorldStorage, WorldStorageManager, CHUNK_SIZE, VERTICAL_SECTIONS
 };
-use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use common::chunks::light_propagation::{LightArea, LightBlocks};
+use common::chunks::stored_entity::StoredEntity;
+use common::world_border::WorldBorder;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::{sync::Arc, time::Duration};
 
 use crate::{
-    CHUNKS_DESPAWN_TIMER,
-    network::runtime_plugin::RuntimePlugin,
-    worlds::{chunks::chunk_column::load_chunk, world_manager::ChunkChanged},
+    CHUNKS_DESPAWN_TIMER, CHUNKS_LOADING_LIMIT,
+    client_resources::server_settings::ServerSettings,
+    worlds::{
+        chunks::chunk_column::{CorruptChunkPolicy, load_chunk},
+        pathfinding::{PathCache, PathCell, find_path, get_block_path_cell},
+        world_manager::ChunkChanged,
+    },
 };
 
-use super::{chunk_column::ChunkColumn, chunks_load_state::ChunksLoadState};
+use super::{
+    chunk_column::ChunkColumn,
+    chunks_load_state::{ChunkTicket, ChunkTicketType, ChunksLoadState},
+};
 
 pub type MapChunksType = AHashMap<ChunkPosition, Arc<RwLock<ChunkColumn>>>;
 
@@ -42,6 +54,16 @@ pub struct ChunkMap {
     world_generator: Arc<RwLock<WorldGenerator>>,
 
     storage: StorageLock,
+
+    corrupt_chunk_policy: CorruptChunkPolicy,
+
+    // Chunks outside of the border are never loaded
+    border: Option<WorldBorder>,
+
+    light_blocks: Arc<LightBlocks>,
+
+    // Searches of the npcs paths
+    path_cache: Mutex<PathCache>,
 }
 
 impl ChunkMap {
@@ -53,6 +75,37 @@ impl ChunkMap {
 
             world_generator: Arc::new(RwLock::new(WorldGenerator::create(Some(seed), world_settings).unwrap())),
             storage: Arc::new(Mutex::new(storage)),
+            corrupt_chunk_policy: Default::default(),
+            border: None,
+            light_blocks: Default::default(),
+            path_cache: Default::default(),
+        }
+    }
+
+    pub fn set_light_blocks(&mut self, light_blocks: Arc<LightBlocks>) {
+        self.light_blocks = light_blocks;
+    }
+
+    pub fn set_corrupt_chunk_policy(&mut self, corrupt_chunk_policy: CorruptChunkPolicy) {
+        self.corrupt_chunk_policy = corrupt_chunk_policy;
+    }
+
+    pub fn get_border(&self) -> Option<&WorldBorder> {
+        self.border.as_ref()
+    }
+
+    pub fn get_border_mut(&mut self) -> Option<&mut WorldBorder> {
+        self.border.as_mut()
+    }
+
+    pub fn set_border(&mut self, border: Option<WorldBorder>) {
+        self.border = border;
+    }
+
+    pub fn is_chunk_inside_border(&self, chunk_position: &ChunkPosition) -> bool {
+        match self.border.as_ref() {
+            Some(border) => border.contains_chunk(chunk_position),
+            None => true,
         }
     }
 
@@ -60,6 +113,14 @@ impl ChunkMap {
         self.loaded_chunks.1.drain()
     }
 
+    /// Npcs of the loaded chunk which must be spawned into the world
+    pub fn take_stored_entities(&self, chunk_position: &ChunkPosition) -> Vec<StoredEntity> {
+        match self.chunks.get(chunk_position) {
+            Some(c) => c.write().take_stored_entities(),
+            None => Default::default(),
+        }
+    }
+
     pub fn count(&self) -> usize {
         self.chunks.len()
     }
@@ -68,6 +129,10 @@ impl ChunkMap {
         &self.chunks
     }
 
+    pub fn get_storage(&self) -> StorageLock {
+        self.storage.clone()
+    }
+
     pub fn is_chunk_loaded(&self, chunk_position: &ChunkPosition) -> bool {
         match self.chunks.get(&chunk_position) {
             Some(l) => l.read().is_loaded(),
@@ -96,9 +161,13 @@ impl ChunkMap {
 
     /// Create player in the world
     pub fn start_chunks_render(&mut self, entity: Entity, to: &ChunkPosition, chunks_distance: u16) {
+        self.chunks_load_state.set_entity_center(entity, to.clone());
         let iter = SpiralIterator::new(to.x as i64, to.z as i64, chunks_distance as i64);
         for (x, z) in iter {
             let chunk_pos = ChunkPosition::new(x, z);
+            if !self.is_chunk_inside_border(&chunk_pos) {
+                continue;
+            }
             self.chunks_load_state.insert_ticket(chunk_pos, entity.clone());
 
             // Update despawn timer
@@ -133,9 +202,12 @@ impl ChunkMap {
          */
 
     /// Trigered when player is move between chunks
+    /// or changes his render distance
     /// for updating chunks vision
     /// to unload unused chunks
     ///
+    /// `from` and `to` can be the same chunk if only the distance was changed
+    ///
     /// Returns unwatchd chunks
     pub fn update_chunks_render(
         &mut self,
@@ -144,10 +216,7 @@ impl ChunkMap {
         to: &ChunkPosition,
         chunks_distance: u16,
     ) -> ChunkChanged {
-        if from == to {
-            panic!("update_chunks_render from and to must be different chunks positions");
-        }
-
+        self.chunks_load_state.set_entity_center(entity, to.clone());
         let mut old = self.chunks_load_state.get_watching_chunks(&entity).unwrap().clone();
         let mut new: Vec<ChunkPosition> = Default::default();
 
@@ -156,6 +225,11 @@ impl ChunkMap {
         for (x, z) in iter {
             let chunk = ChunkPosition::new(x as i64, z as i64);
 
+            // Chunks outside of the border are abandoned
+            if !self.is_chunk_inside_border(&chunk) {
+                continue;
+            }
+
             // If its new chunk
             if !old.contains(&chunk) {
                 // Start keeping this chunk
@@ -185,6 +259,105 @@ impl ChunkMap {
         }
     }
 
+    /// Loads forced chunks tickets from the world storage
+    pub fn load_forced_chunks(&mut self) -> Result<usize, String> {
+        let forced = self.storage.lock().load_forced_chunks()?;
+        let count = forced.len();
+        for chunk in forced {
+            self.chunks_load_state
+                .insert_static_ticket(chunk, ChunkTicketType::Forced, None);
+        }
+        Ok(count)
+    }
+
+    /// Keep the chunk loaded all the time, even after restart
+    ///
+    /// Returns false if the chunk is already forced
+    pub fn add_forced_chunk(&mut self, chunk: ChunkPosition) -> Result<bool, String> {
+        if self.is_chunk_forced(&chunk) {
+            return Ok(false);
+        }
+        if !self.is_chunk_inside_border(&chunk) {
+            return Err("&cchunk is outside of the world border".to_string());
+        }
+        self.storage.lock().set_chunk_forced(&chunk, true)?;
+        self.insert_static_ticket(chunk, ChunkTicketType::Forced, None);
+        Ok(true)
+    }
+
+    /// Returns false if the chunk wasn't forced
+    pub fn remove_forced_chunk(&mut self, chunk: &ChunkPosition) -> Result<bool, String> {
+        if !self.is_chunk_forced(chunk) {
+            return Ok(false);
+        }
+        self.storage.lock().set_chunk_forced(chunk, false)?;
+        self.chunks_load_state
+            .remove_static_ticket(chunk, &ChunkTicketType::Forced);
+        Ok(true)
+    }
+
+    pub fn is_chunk_forced(&self, chunk: &ChunkPosition) -> bool {
+        match self.chunks_load_state.get_static_tickets(chunk) {
+            Some(tickets) => tickets.iter().any(|t| *t.get_ticket_type() == ChunkTicketType::Forced),
+            None => false,
+        }
+    }
+
+    pub fn get_forced_chunks(&self) -> Vec<ChunkPosition> {
+        self.chunks_load_state
+            .get_static_tickets_chunks(&ChunkTicketType::Forced)
+    }
+
+    /// Keeps chunks around the spawn loaded;
+    /// the previous spawn area is released
+    pub fn set_spawn_area(&mut self, center: &ChunkPosition, chunks_distance: u16) {
+        self.chunks_load_state
+            .remove_all_static_tickets(&ChunkTicketType::SpawnArea);
+        let iter = SpiralIterator::new(center.x, center.z, chunks_distance as i64);
+        for (x, z) in iter {
+            self.insert_static_ticket(ChunkPosition::new(x, z), ChunkTicketType::SpawnArea, None);
+        }
+    }
+
+    /// Keeps the chunk loaded for the plugin until the time runs out
+    ///
+    /// Repeated call with the same owner prolongs the ticket
+    ///
+    /// Returns false if the chunk is outside of the world border
+    pub fn add_temporary_ticket(&mut self, chunk: ChunkPosition, owner: String, duration: Duration) -> bool {
+        self.insert_static_ticket(chunk, ChunkTicketType::Temporary(owner), Some(duration))
+    }
+
+    /// Tickets outside of the world border are refused
+    fn insert_static_ticket(
+        &mut self,
+        chunk: ChunkPosition,
+        ticket_type: ChunkTicketType,
+        expires_in: Option<Duration>,
+    ) -> bool {
+        if !self.is_chunk_inside_border(&chunk) {
+            return false;
+        }
+        self.chunks_load_state
+            .insert_static_ticket(chunk, ticket_type, expires_in);
+
+        // Update despawn timer
+        if let Some(chunk_column) = self.chunks.get(&chunk) {
+            chunk_column.read().set_despawn_timer(Duration::ZERO);
+        }
+        true
+    }
+
+    pub fn remove_temporary_ticket(&mut self, chunk: &ChunkPosition, owner: String) -> bool {
+        self.chunks_load_state
+            .remove_static_ticket(chunk, &ChunkTicketType::Temporary(owner))
+    }
+
+    /// All tickets except players
+    pub fn get_static_tickets(&self, chunk: &ChunkPosition) -> Option<&Vec<ChunkTicket>> {
+        self.chunks_load_state.get_static_tickets(chunk)
+    }
+
     /// Player stop watch the world (despawn or move to another world)
     pub fn stop_chunks_render(&mut self, entity: Entity) {
         self.chunks_load_state.remove_all_entity_tickets(&entity);
@@ -192,10 +365,12 @@ impl ChunkMap {
 
     /// Update chunks: load or despawn
     pub fn update_chunks(&mut self, delta: Duration, world_slug: &String) {
+        self.chunks_load_state.update_static_tickets(delta);
+
         // Update chunks despawn timer
-        // Increase ONLY of noone looking at the chunk
+        // Increase ONLY of noone keeping the chunk
         for (&chunk, chunk_column) in self.chunks.iter_mut() {
-            if self.chunks_load_state.num_tickets(&chunk) == 0 {
+            if !self.chunks_load_state.has_any_ticket(&chunk) {
                 chunk_column.read().increase_despawn_timer(delta);
             }
         }
@@ -203,46 +378,123 @@ impl ChunkMap {
         // Despawn chunks waiting for despawn
         self.chunks.retain(|&chunk, chunk_column| {
             let chunk_column = chunk_column.read();
-            let for_despawn = chunk_column.is_for_despawn(CHUNKS_DESPAWN_TIMER);
-            if for_despawn {
-                log::trace!(target: "chunks", "Chunk {} despawned", chunk);
-
-                let save_chunk_data = self
-                    .storage
-                    .lock()
-                    .save_chunk_data(chunk_column.get_chunk_position(), &chunk_column.sections);
-                if let Err(e) = save_chunk_data {
-                    log::error!(target: "worlds", "&cChunk save error!");
-                    log::error!(target: "worlds", "Error: {}", e);
-                    RuntimePlugin::stop();
-                    panic!();
-                }
+            if !chunk_column.is_for_despawn(CHUNKS_DESPAWN_TIMER) {
+                return true;
             }
-            !for_despawn
-        });
 
-        // Send to load new chunks
-        for (chunk, players) in self.chunks_load_state.by_chunk.iter() {
-            if players.len() == 0 {
-                continue;
+            // Chunk without data must not overwrite the storage
+            if !chunk_column.is_loaded() || !chunk_column.is_persistent() {
+                log::trace!(target: "chunks", "Chunk {} despawned without saving", chunk);
+                return false;
             }
 
-            if !self.chunks.contains_key(&chunk) {
-                let chunk_column = Arc::new(RwLock::new(ChunkColumn::new(chunk.clone(), world_slug.clone())));
+            if chunk_column.is_save_delayed(delta) {
+                return true;
+            }
 
-                log::trace!(target: "chunks", "Send chunk {} to load", chunk);
-                load_chunk(
-                    self.world_generator.clone(),
-                    self.storage.clone(),
-                    chunk_column.clone(),
-                    self.loaded_chunks.0.clone(),
+            if let Err(e) = save_chunk_column(&self.storage, &chunk_column) {
+                // Chunk stays in memory until it's saved
+                let (attempts, delay) = chunk_column.save_failed();
+                log::error!(
+                    target: "worlds",
+                    "&cChunk {} save error! Attempt:{} next in {:.0?}",
+                    chunk, attempts, delay
                 );
-                self.chunks.insert(chunk.clone(), chunk_column);
+                log::error!(target: "worlds", "Error: {}", e);
+                return true;
             }
+            log::trace!(target: "chunks", "Chunk {} despawned", chunk);
+            false
+        });
+
+        // Chunks which could not be read from the storage are loaded again after the delay
+        let retries: Vec<Arc<RwLock<ChunkColumn>>> = self
+            .chunks
+            .values()
+            .filter(|c| match c.try_write() {
+                Some(mut c) => c.is_load_retry_due(delta),
+                // Write lock is held by the loading thread
+                None => false,
+            })
+            .cloned()
+            .collect();
+        for chunk_column in retries {
+            log::trace!(target: "chunks", "Send chunk {} to load again", chunk_column.read().get_chunk_position());
+            load_chunk(
+                self.world_generator.clone(),
+                self.storage.clone(),
+                chunk_column,
+                self.loaded_chunks.0.clone(),
+                self.corrupt_chunk_policy,
+                self.light_blocks.clone(),
+            );
+        }
+
+        // Send to load new chunks, the closest to the players first
+        let in_flight = self.get_loading_count();
+        if in_flight >= CHUNKS_LOADING_LIMIT {
+            return;
+        }
+        for chunk in self.get_load_queue(CHUNKS_LOADING_LIMIT - in_flight) {
+            let chunk_column = Arc::new(RwLock::new(ChunkColumn::new(chunk.clone(), world_slug.clone())));
+
+            log::trace!(target: "chunks", "Send chunk {} to load", chunk);
+            load_chunk(
+                self.world_generator.clone(),
+                self.storage.clone(),
+                chunk_column.clone(),
+                self.loaded_chunks.0.clone(),
+                self.corrupt_chunk_policy,
+                self.light_blocks.clone(),
+            );
+            self.chunks.insert(chunk.clone(), chunk_column);
         }
     }
 
+    /// Number of chunks which are currently loading
+    fn get_loading_count(&self) -> usize {
+        self.chunks
+            .values()
+            .filter(|c| match c.try_read() {
+                Some(c) => !c.is_loaded() && !c.is_load_failed(),
+                // Write lock is held by the loading thread
+                None => true,
+            })
+            .count()
+    }
+
+    /// Ticketed chunks which are not yet sent to load, ordered by distance
+    /// to the nearest player
+    ///
+    /// Queue is built from the current tickets, so chunks that lost all
+    /// their tickets are cancelled before the loading starts.
+    /// Tickets which were left outside of the shrunk border are ignored
+    fn get_load_queue(&self, limit: usize) -> Vec<ChunkPosition> {
+        let mut queue: Vec<(i64, ChunkPosition)> = Default::default();
+        for chunk in self.chunks_load_state.get_ticket_chunks() {
+            if !self.chunks.contains_key(&chunk) && self.is_chunk_inside_border(&chunk) {
+                queue.push((self.chunks_load_state.get_load_priority(&chunk), chunk));
+            }
+        }
+        queue.sort_unstable_by_key(|(priority, _chunk)| *priority);
+        queue.truncate(limit);
+        queue.into_iter().map(|(_priority, chunk)| chunk).collect()
+    }
+
     pub fn edit_block(&self, position: BlockPosition, new_block_info: Option<BlockDataInfo>) -> Result<(), String> {
+        self.edit_block_no_light(position.clone(), new_block_info)?;
+        self.update_light(&position.get_chunk_position(), |area| area.update_block(&position));
+        return Ok(());
+    }
+
+    /// Changes the block without the light update
+    ///
+    /// Used by the mass changes, which call `relight_chunk` once for each changed chunk
+    pub fn edit_block_no_light(
+        &self,
+        position: BlockPosition,
+        new_block_info: Option<BlockDataInfo>,
+    ) -> Result<(), String> {
         let Some(chunk_column) = self.chunks.get(&position.get_chunk_position()) else {
             return Err(format!(
                 "edit_block chunk {} is not found",
@@ -250,6 +502,13 @@ impl ChunkMap {
             ));
         };
 
+        if !chunk_column.read().is_loaded() {
+            return Err(format!(
+                "edit_block chunk {} is not loaded",
+                position.get_chunk_position()
+            ));
+        }
+
         let (section, block_position) = position.get_block_position();
         if section > VERTICAL_SECTIONS as u32 {
             return Err(format!("edit_block section {} is more than", VERTICAL_SECTIONS));
@@ -257,37 +516,247 @@ impl ChunkMap {
         chunk_column
             .write()
             .change_block(section, &block_position, new_block_info);
+        self.path_cache.lock().invalidate_block(&position);
         return Ok(());
     }
 
+    /// Computes the light of the loaded chunk from scratch
+    /// and spreads it into the loaded chunks around it
+    pub fn relight_chunk(&self, chunk_position: &ChunkPosition) {
+        self.update_light(chunk_position, |area| area.light_column(chunk_position));
+    }
+
+    /// Spreads the light between the loaded chunk and its neighbours
+    pub fn spread_loaded_light(&self, chunk_position: &ChunkPosition) {
+        self.update_light(chunk_position, |area| area.spread_borders(chunk_position));
+    }
+
+    /// Runs the light propagation over the loaded chunk and the loaded chunks around it
+    fn update_light<F: FnOnce(&mut LightArea)>(&self, chunk_position: &ChunkPosition, f: F) {
+        let mut columns: Vec<RwLockWriteGuard<ChunkColumn>> = Default::default();
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let position = ChunkPosition::new(chunk_position.x + x, chunk_position.z + z);
+                let Some(chunk_column) = self.chunks.get(&position) else {
+                    continue;
+                };
+                // Write lock is held by the loading thread
+                match chunk_column.try_read() {
+                    Some(c) if c.is_loaded() => (),
+                    _ => continue,
+                }
+                columns.push(chunk_column.write());
+            }
+        }
+
+        let mut area = LightArea::new(&self.light_blocks);
+        for chunk_column in columns.iter_mut() {
+            let chunk_column = &mut **chunk_column;
+            area.add_column(chunk_column.get_chunk_position().clone(), &mut chunk_column.sections);
+        }
+        f(&mut area);
+    }
+
+    /// If the block can be read and changed:
+    /// the chunk is loaded and the position is inside of the world height
+    pub fn is_position_loaded(&self, position: &BlockPosition) -> bool {
+        if position.get_y() < 0 || position.get_y() >= CHUNK_SIZE as i64 * VERTICAL_SECTIONS as i64 {
+            return false;
+        }
+        self.is_chunk_loaded(&position.get_chunk_position())
+    }
+
+    /// Blocks of the chunk even if it's not loaded: from the storage or generated
+    pub fn read_chunk_data(&self, chunk_position: &ChunkPosition) -> Result<ChunkData, String> {
+        if let Some(chunk_column) = self.chunks.get(chunk_position) {
+            // Write lock is held by the loading thread
+            if let Some(chunk_column) = chunk_column.try_read() {
+                if chunk_column.is_loaded() {
+                    return Ok(chunk_column.sections.clone());
+                }
+            }
+        }
+        let storage = self.storage.lock();
+        if let Some(index) = storage.has_chunk_data(chunk_position)? {
+            return storage.load_chunk_data(index);
+        }
+        drop(storage);
+        Ok(self.world_generator.read().generate_chunk_data(chunk_position))
+    }
+
+    /// Returns None if the position is not loaded
+    pub fn get_block_info(&self, position: &BlockPosition) -> Option<BlockDataInfo> {
+        if !self.is_position_loaded(position) {
+            return None;
+        }
+        let chunk_column = self.chunks.get(&position.get_chunk_position())?.read();
+        let (section, block_position) = position.get_block_position();
+        let section_data = chunk_column.sections.get(section as usize)?;
+        section_data.get(&block_position).cloned()
+    }
+
+    pub fn get_path_cell(&self, server_settings: &ServerSettings, position: &BlockPosition) -> PathCell {
+        if !self.is_position_loaded(position) {
+            return PathCell::Unloaded;
+        }
+        get_block_path_cell(server_settings, &self.get_block_info(position))
+    }
+
+    /// Walking path between the feet positions over the loaded blocks
+    ///
+    /// Searches are cached until the blocks around them are changed
+    pub fn find_path(
+        &self,
+        server_settings: &ServerSettings,
+        start: &BlockPosition,
+        goal: &BlockPosition,
+    ) -> Option<Vec<BlockPosition>> {
+        if let Some(search) = self.path_cache.lock().get(start, goal) {
+            return search.get_path().clone();
+        }
+        let search = find_path(start, goal, |p| self.get_path_cell(server_settings, p));
+        let path = search.get_path().clone();
+        self.path_cache.lock().insert(*start, *goal, search);
+        path
+    }
+
+    /// Cached path was blocked by the chunk which is not loaded anymore
+    pub fn forget_path(&self, start: &BlockPosition, goal: &BlockPosition) {
+        self.path_cache.lock().remove(start, goal);
+    }
+
+    /// Searches stopped at the border of the loaded chunk can lead through it now
+    pub fn invalidate_paths(&self, chunk_position: &ChunkPosition) {
+        self.path_cache.lock().invalidate_chunk(chunk_position);
+    }
+
+    /// Schedules the block update after the number of ticks
+    ///
+    /// Update is stored with the chunk, so the chunk must be loaded
+    pub fn schedule_tick(&self, position: BlockPosition, delay: u32) -> Result<(), String> {
+        let Some(chunk_column) = self.chunks.get(&position.get_chunk_position()) else {
+            return Err(format!(
+                "schedule_tick chunk {} is not found",
+                position.get_chunk_position()
+            ));
+        };
+        let mut chunk_column = chunk_column.write();
+        if !chunk_column.is_loaded() {
+            return Err(format!(
+                "schedule_tick chunk {} is not loaded",
+                position.get_chunk_position()
+            ));
+        }
+        chunk_column.schedule_tick(ScheduledTick::create(position, delay.max(1)));
+        Ok(())
+    }
+
+    /// Advances the scheduled updates of all loaded chunks
+    /// and returns positions which must be updated at this tick
+    pub fn pop_due_ticks(&self) -> Vec<BlockPosition> {
+        let mut due: Vec<BlockPosition> = Default::default();
+        for chunk_column in self.chunks.values() {
+            // Chunk is locked by the loading thread
+            let Some(mut chunk_column) = chunk_column.try_write() else {
+                continue;
+            };
+            if !chunk_column.is_loaded() {
+                continue;
+            }
+            for tick in chunk_column.pop_due_ticks() {
+                due.push(tick.get_position().clone());
+            }
+        }
+        due
+    }
+
+    /// Picks random blocks of each non-empty section of the loaded chunks
+    ///
+    /// `count` positions are rolled for each section, empty positions are skipped
+    pub fn pick_random_ticks(
+        &self,
+        count: u32,
+        rng: &mut RandomNumberGenerator,
+    ) -> Vec<(BlockPosition, BlockDataInfo)> {
+        let mut picked: Vec<(BlockPosition, BlockDataInfo)> = Default::default();
+        let volume = CHUNK_SIZE as u16 * CHUNK_SIZE as u16 * CHUNK_SIZE as u16;
+        for (chunk_position, chunk_column) in self.chunks.iter() {
+            let Some(chunk_column) = chunk_column.try_read() else {
+                continue;
+            };
+            if !chunk_column.is_loaded() {
+                continue;
+            }
+            for section in 0..VERTICAL_SECTIONS {
+                let Some(section_data) = chunk_column.sections.get(section) else {
+                    continue;
+                };
+                if section_data.len() == 0 {
+                    continue;
+                }
+                for _ in 0..count {
+                    let block_position = ChunkBlockPosition::delinearize(rng.range(0, volume));
+                    if let Some(block_info) = section_data.get(&block_position) {
+                        let position =
+                            BlockPosition::from_chunk_position(chunk_position, &(section as u32), &block_position);
+                        picked.push((position, block_info.clone()));
+                    }
+                }
+            }
+        }
+        picked
+    }
+
+    /// Saves all loaded chunks
+    ///
+    /// Failed chunks are not interrupting the saving of others
     pub fn save(&mut self) -> Result<(), String> {
-        for (_chunk_position, chunk_column) in self.chunks.iter() {
+        let mut failed = 0;
+        for (chunk_position, chunk_column) in self.chunks.iter() {
             let chunk_column = chunk_column.read();
-            let save_chunk_data = self
-                .storage
-                .lock()
-                .save_chunk_data(chunk_column.get_chunk_position(), &chunk_column.sections);
-            if let Err(e) = save_chunk_data {
-                return Err(e);
+            if !chunk_column.is_loaded() || !chunk_column.is_persistent() {
+                continue;
             }
+            if let Err(e) = save_chunk_column(&self.storage, &chunk_column) {
+                chunk_column.save_failed();
+                log::error!(target: "worlds", "&cChunk {} save error: {}", chunk_position, e);
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            return Err(format!("&c{} chunks were not saved", failed));
         }
         Ok(())
     }
 }
 
+/// Chunk blocks and its scheduled updates are saved together
+fn save_chunk_column(storage: &StorageLock, chunk_column: &ChunkColumn) -> Result<(), String> {
+    let storage = storage.lock();
+    storage.save_chunk_data(chunk_column.get_chunk_position(), &chunk_column.sections)?;
+    storage.save_scheduled_ticks(chunk_column.get_chunk_position(), chunk_column.get_scheduled_ticks())
+}
+
 #[cfg(test)]
 mod tests {
     use bevy::prelude::Entity;
     use common::{
-        WorldStorageManager,
+        VERTICAL_SECTIONS, WorldStorageManager,
+        blocks::block_type::{BlockContent, BlockType},
+        chunks::{
+            block_position::BlockPosition, chunk_data::BlockDataInfo, chunk_light::LightChannel,
+            light_propagation::LightBlocks,
+        },
+        world_border::{WorldBorder, WorldBorderShape},
         world_generator::default::WorldGeneratorSettings,
         worlds_storage::taits::{IWorldStorage, WorldStorageSettings},
     };
-    use std::time::Duration;
+    use parking_lot::RwLock;
+    use std::{sync::Arc, time::Duration};
 
     use crate::CHUNKS_DESPAWN_TIMER;
 
-    use super::{ChunkMap, ChunkPosition};
+    use super::{ChunkColumn, ChunkMap, ChunkPosition};
 
     #[test]
     fn test_tickets_spawn_despawn() {
@@ -331,6 +800,33 @@ mod tests {
         assert_eq!(chunk_map.chunks_load_state.num_tickets(&new_pos), 0);
     }
 
+    #[test]
+    fn test_change_chunks_distance() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+        let entity = Entity::from_raw(0);
+
+        let pos = ChunkPosition::new(0, 0);
+        chunk_map.start_chunks_render(entity, &pos, 4);
+        let far_chunks = chunk_map.chunks_load_state.get_watching_chunks(&entity).unwrap().len();
+
+        // Decrease distance without moving
+        let change = chunk_map.update_chunks_render(entity, &pos, &pos, 2);
+        let near_chunks = chunk_map.chunks_load_state.get_watching_chunks(&entity).unwrap().len();
+        assert_eq!(near_chunks < far_chunks, true);
+        assert_eq!(change.new_chunks.len(), 0);
+        assert_eq!(change.abandoned_chunks.len(), far_chunks - near_chunks);
+        assert_eq!(chunk_map.chunks_load_state.num_tickets(&pos), 1);
+        assert_eq!(chunk_map.chunks_load_state.num_tickets(&ChunkPosition::new(3, 0)), 0);
+
+        // Increase it back
+        let change = chunk_map.update_chunks_render(entity, &pos, &pos, 4);
+        let chunks = chunk_map.chunks_load_state.get_watching_chunks(&entity).unwrap();
+        assert_eq!(chunks.len(), far_chunks);
+        assert_eq!(change.new_chunks.len(), far_chunks - near_chunks);
+        assert_eq!(change.abandoned_chunks.len(), 0);
+    }
+
     #[test]
     fn test_update_chunks() {
         let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
@@ -356,4 +852,160 @@ mod tests {
             "Because despawn_timer is fill - chunk must be unloaded"
         );
     }
+
+    #[test]
+    fn test_temporary_ticket() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+        let world_slug = "default".to_string();
+        let pos = ChunkPosition::new(5, 5);
+
+        chunk_map.add_temporary_ticket(pos.clone(), "test".to_string(), Duration::from_secs(3));
+        chunk_map.update_chunks(Duration::from_secs(1), &world_slug);
+        assert_eq!(chunk_map.chunks.len(), 1, "Temporary ticket must load the chunk");
+
+        chunk_map.update_chunks(Duration::from_secs(1), &world_slug);
+        assert_eq!(chunk_map.get_static_tickets(&pos).unwrap().len(), 1);
+
+        chunk_map.update_chunks(Duration::from_secs(1), &world_slug);
+        assert_eq!(
+            chunk_map.get_static_tickets(&pos).is_none(),
+            true,
+            "Ticket must be expired"
+        );
+
+        chunk_map
+            .get_chunk_column(&pos)
+            .unwrap()
+            .set_despawn_timer(CHUNKS_DESPAWN_TIMER);
+        chunk_map.update_chunks(Duration::from_secs(1), &world_slug);
+        assert_eq!(chunk_map.chunks.len(), 0, "Chunk without tickets must be unloaded");
+    }
+
+    #[test]
+    fn test_load_queue_priority() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+        let entity = Entity::from_raw(0);
+        let pos = ChunkPosition::new(10, -10);
+
+        chunk_map.start_chunks_render(entity, &pos, 4);
+        let queue = chunk_map.get_load_queue(usize::MAX);
+        assert_eq!(
+            queue.len(),
+            chunk_map.chunks_load_state.get_watching_chunks(&entity).unwrap().len()
+        );
+        assert_eq!(queue[0], pos, "Chunk under the player must be loaded first");
+        for i in 1..queue.len() {
+            assert_eq!(pos.get_distance(&queue[i - 1]) <= pos.get_distance(&queue[i]), true);
+        }
+
+        let queue = chunk_map.get_load_queue(3);
+        assert_eq!(queue.len(), 3);
+
+        // Chunks without tickets are not loading
+        chunk_map.stop_chunks_render(entity);
+        assert_eq!(chunk_map.get_load_queue(usize::MAX).len(), 0);
+    }
+
+    #[test]
+    fn test_world_border_tickets() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+        chunk_map.set_border(Some(WorldBorder::create(0.0, 0.0, WorldBorderShape::Square, 32.0)));
+        let entity = Entity::from_raw(0);
+
+        chunk_map.start_chunks_render(entity, &ChunkPosition::new(0, 0), 4);
+        let chunks = chunk_map.chunks_load_state.get_watching_chunks(&entity).unwrap();
+        assert_eq!(chunks.len(), 16, "Only chunks from -2 to 1 are inside");
+        assert_eq!(chunks.contains(&ChunkPosition::new(2, 0)), false);
+
+        assert_eq!(
+            chunk_map.add_temporary_ticket(ChunkPosition::new(5, 5), "test".to_string(), Duration::from_secs(3)),
+            false
+        );
+        assert_eq!(chunk_map.add_forced_chunk(ChunkPosition::new(-3, 0)).is_err(), true);
+
+        // Shrunk border abandons the chunks outside
+        chunk_map.set_border(Some(WorldBorder::create(0.0, 0.0, WorldBorderShape::Square, 16.0)));
+        let pos = ChunkPosition::new(0, 0);
+        let change = chunk_map.update_chunks_render(entity, &pos, &pos, 4);
+        assert_eq!(change.abandoned_chunks.len(), 12);
+        assert_eq!(chunk_map.get_load_queue(usize::MAX).len(), 4);
+    }
+
+    #[test]
+    fn test_scheduled_ticks() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+        let position = BlockPosition::new(1, 10, 1);
+
+        assert_eq!(
+            chunk_map.schedule_tick(position.clone(), 2).is_err(),
+            true,
+            "Chunk is not exists"
+        );
+
+        let mut chunk_column = ChunkColumn::new(ChunkPosition::new(0, 0), "default".to_string());
+        chunk_column.set_loaded();
+        chunk_map
+            .chunks
+            .insert(ChunkPosition::new(0, 0), Arc::new(RwLock::new(chunk_column)));
+
+        chunk_map.schedule_tick(position.clone(), 3).unwrap();
+        // The earliest update is kept
+        chunk_map.schedule_tick(position.clone(), 2).unwrap();
+        chunk_map.schedule_tick(position.clone(), 5).unwrap();
+
+        assert_eq!(chunk_map.pop_due_ticks().len(), 0);
+        assert_eq!(chunk_map.pop_due_ticks(), vec![position]);
+        assert_eq!(chunk_map.pop_due_ticks().len(), 0);
+    }
+
+    #[test]
+    fn test_edit_block_light() {
+        let storage = WorldStorageManager::create("test".to_string(), 1, &WorldStorageSettings::default()).unwrap();
+        let mut chunk_map = ChunkMap::new(1, WorldGeneratorSettings::default(), storage);
+
+        let mut light_blocks = LightBlocks::default();
+        light_blocks.add(1, &BlockType::new(BlockContent::single("lamp.png")).light(10));
+        chunk_map.set_light_blocks(Arc::new(light_blocks));
+
+        for chunk_position in [ChunkPosition::new(0, 0), ChunkPosition::new(1, 0)] {
+            let mut chunk_column = ChunkColumn::new(chunk_position.clone(), "default".to_string());
+            for _ in 0..VERTICAL_SECTIONS {
+                chunk_column.sections.push_section(Default::default());
+            }
+            chunk_column.set_loaded();
+            chunk_map
+                .chunks
+                .insert(chunk_position, Arc::new(RwLock::new(chunk_column)));
+        }
+
+        let get_light = |chunk_map: &ChunkMap| {
+            let chunk_column = chunk_map.get_chunk_column(&ChunkPosition::new(1, 0)).unwrap();
+            let (section, block_position) = BlockPosition::new(17, 10, 1).get_block_position();
+            chunk_column
+                .sections
+                .get_light()
+                .get(LightChannel::Block, section, &block_position)
+        };
+
+        // Light of the lamp spreads into the neighbour chunk
+        let lamp = BlockPosition::new(15, 10, 1);
+        chunk_map
+            .edit_block(lamp.clone(), Some(BlockDataInfo::create(1, None)))
+            .unwrap();
+        assert_eq!(get_light(&chunk_map), 8);
+
+        chunk_map.edit_block(lamp.clone(), None).unwrap();
+        assert_eq!(get_light(&chunk_map), 0);
+
+        chunk_map
+            .edit_block_no_light(lamp.clone(), Some(BlockDataInfo::create(1, None)))
+            .unwrap();
+        assert_eq!(get_light(&chunk_map), 0);
+        chunk_map.relight_chunk(&lamp.get_chunk_position());
+        assert_eq!(get_light(&chunk_map), 8);
+    }
 }
@@ -7,8 +7,14 @@ use crate::{
     entities::{
         EntityComponent,
         entity::{Position, Rotation},
+        entity_tag::EntityTagComponent,
+        skin::EntitySkinComponent,
+    },
+    network::{
+        client_network::ClientNetwork, events::on_player_move::move_player, sync_entities::sync_entity_despawn,
+        sync_players::PlayerSpawnEvent, sync_world_border::send_world_border, sync_world_time::send_world_time,
+        sync_world_weather::send_world_weather,
     },
-    network::{client_network::ClientNetwork, sync_players::PlayerSpawnEvent},
 };
 
 use super::worlds_manager::WorldsManager;
@@ -47,7 +53,12 @@ impl Command for SpawnPlayer {
             };
 
             let bundle = (self.position.clone(), self.rotation, self.client.clone());
-            let world_entity = world_manager.spawn_player(self.position, bundle, self.components.clone());
+            let world_entity = world_manager.spawn_player(
+                self.position,
+                bundle,
+                self.components.clone(),
+                self.client.get_render_distance(),
+            );
 
             self.client.set_world_entity(Some(world_entity.clone()));
 
@@ -60,6 +71,9 @@ impl Command for SpawnPlayer {
 
             self.client
                 .network_send_spawn(&self.position, &self.rotation, &self.components);
+            send_world_time(&*world_manager, &self.client);
+            send_world_weather(&*world_manager, &self.client);
+            send_world_border(&*world_manager, &self.client);
 
             if world_manager
                 .get_chunks_map()
@@ -70,3 +84,84 @@ impl Command for SpawnPlayer {
         });
     }
 }
+
+/// Moves the player to the position of any world
+///
+/// Between worlds the player is despawned from the old world and spawned in the new one;
+/// the client rebuilds its world by SpawnWorld without repeating the resources handshake
+pub struct ChangePlayerWorld {
+    world_slug: String,
+    client: ClientNetwork,
+    position: Position,
+    rotation: Rotation,
+}
+
+impl ChangePlayerWorld {
+    pub fn create(world_slug: String, client: ClientNetwork, position: Position, rotation: Rotation) -> Self {
+        Self {
+            world_slug,
+            client,
+            position,
+            rotation,
+        }
+    }
+}
+
+impl Command for ChangePlayerWorld {
+    fn apply(self, world: &mut World) {
+        let worlds_manager = world.resource::<WorldsManager>();
+        if worlds_manager.get_world_manager(&self.world_slug).is_none() {
+            log::error!(target: "worlds", "&cChangePlayerWorld: world \"{}\" doesn't exists", self.world_slug);
+            return;
+        }
+
+        let mut components: Vec<EntityComponent> = Default::default();
+        if let Some(world_entity) = self.client.get_world_entity() {
+            let Some(mut world_manager) = worlds_manager.get_world_manager_mut(world_entity.get_world_slug()) else {
+                log::error!(
+                    target: "worlds",
+                    "&cChangePlayerWorld: world \"{}\" of the player doesn't exists",
+                    world_entity.get_world_slug()
+                );
+                return;
+            };
+
+            if *world_entity.get_world_slug() == self.world_slug {
+                move_player(&mut *world_manager, &world_entity, self.position, self.rotation);
+                self.client.get_movement_state_mut().reset();
+                self.client.send_teleport(&self.position);
+                return;
+            }
+
+            let Some(entity_ref) = world_manager.get_ecs().get_entity(world_entity.get_entity()) else {
+                log::error!(
+                    target: "worlds",
+                    "&cChangePlayerWorld: player entity not found in \"{}\"",
+                    world_entity.get_world_slug()
+                );
+                return;
+            };
+            if let Some(tag) = entity_ref.get::<EntityTagComponent>() {
+                components.push(EntityComponent::Tag(Some(tag.clone())));
+            }
+            if let Some(skin) = entity_ref.get::<EntitySkinComponent>() {
+                components.push(EntityComponent::Skin(Some(skin.clone())));
+                sync_entity_despawn(&*world_manager, world_entity.get_entity());
+            }
+            world_manager.despawn_player(&world_entity);
+
+            log::info!(
+                target: "worlds",
+                "Player &e\"{}\"&r moved from &a\"{}\"&r to &a\"{}\"",
+                self.client.get_client_info().map_or("-".to_string(), |i| i.get_login().clone()),
+                world_entity.get_world_slug(),
+                self.world_slug
+            );
+        }
+
+        self.client.set_world_entity(None);
+        self.client.clear_sended_chunks();
+        self.client.get_movement_state_mut().reset();
+        SpawnPlayer::create(self.world_slug, self.client, self.position, self.rotation, components).apply(world);
+    }
+}
@@ -3,12 +3,22 @@ use crate::console::console_sender::ConsoleSenderType;
 use crate::entities::entity::{Position, Rotation};
 use crate::launch_settings::LaunchSettings;
 use crate::network::client_network::ClientNetwork;
+use crate::network::clients_container::ClientsContainer;
 use crate::network::events::on_player_move::move_player;
+use crate::worlds::block_ticks::BlockTickHandlers;
+use bevy_ecs::system::Command as _;
 use bevy_ecs::world::World;
 use bracket_lib::random::RandomNumberGenerator;
+use common::chunks::block_position::BlockPosition;
+use common::chunks::chunk_position::ChunkPosition;
 use common::commands::command::{Arg, Command, CommandMatch};
 use common::world_generator::default::WorldGeneratorSettings;
+use std::thread;
 
+use super::audit_log::AuditLog;
+use super::commands::ChangePlayerWorld;
+use super::edit_history::{EDIT_HISTORY_LIMIT, apply_block_changes};
+use super::web_map::WebMapExporter;
 use super::worlds_manager::WorldsManager;
 
 pub(crate) fn command_parser_world() -> Command {
@@ -20,6 +30,39 @@ pub(crate) fn command_parser_world() -> Command {
                 .arg(Arg::new("slug".to_owned()).required(true))
                 .arg(Arg::new("seed".to_owned())),
         )
+        .subcommand(Command::new("map".to_owned()).arg(Arg::new("slug".to_owned()).required(true)))
+        .subcommand(
+            Command::new("setspawn".to_owned())
+                .arg(Arg::new("slug".to_owned()))
+                .arg(Arg::new("x".to_owned()))
+                .arg(Arg::new("y".to_owned()))
+                .arg(Arg::new("z".to_owned())),
+        )
+        .subcommand(
+            Command::new("tp".to_owned())
+                .arg(Arg::new("slug".to_owned()).required(true))
+                .arg(Arg::new("player".to_owned()))
+                .arg(Arg::new("x".to_owned()))
+                .arg(Arg::new("y".to_owned()))
+                .arg(Arg::new("z".to_owned())),
+        )
+        .subcommand(
+            Command::new("forceload".to_owned())
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("add".to_owned())
+                        .arg(Arg::new("slug".to_owned()).required(true))
+                        .arg(Arg::new("x".to_owned()).required(true))
+                        .arg(Arg::new("z".to_owned()).required(true)),
+                )
+                .subcommand(
+                    Command::new("remove".to_owned())
+                        .arg(Arg::new("slug".to_owned()).required(true))
+                        .arg(Arg::new("x".to_owned()).required(true))
+                        .arg(Arg::new("z".to_owned()).required(true)),
+                )
+                .subcommand(Command::new("list".to_owned()).arg(Arg::new("slug".to_owned()).required(true))),
+        )
 }
 
 pub(crate) fn command_world(
@@ -27,11 +70,22 @@ pub(crate) fn command_world(
     sender: Box<dyn ConsoleSenderType>,
     args: CommandMatch,
 ) -> Result<(), String> {
+    if let Some(world_subcommand) = args.subcommand() {
+        match world_subcommand.get_name().as_str() {
+            "tp" => return command_world_tp(world, &sender, world_subcommand),
+            "setspawn" => return command_world_setspawn(world, &sender, world_subcommand),
+            _ => (),
+        }
+    }
+
     let launch_settings = world.get_resource::<LaunchSettings>().unwrap();
     let world_storage_settings = launch_settings.get_world_storage_settings();
+    let mut maps_path = launch_settings.get_server_data_path();
+    maps_path.push("maps");
 
     let server_settings = world.get_resource::<ServerSettings>().unwrap();
     let block_id_map = server_settings.get_block_id_map().clone();
+    let map_colors = server_settings.get_map_colors();
 
     let mut worlds_manager = world.resource_mut::<WorldsManager>();
 
@@ -83,6 +137,46 @@ pub(crate) fn command_world(
                     }
                 }
             }
+            "map" => {
+                let slug = world_subcommand.get_arg::<String, _>("slug")?;
+                let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+                    sender.send_console_message(format!("World \"{}\" not found", slug));
+                    return Ok(());
+                };
+
+                // Map is rendered from the storage, so loaded chunks must be saved first
+                if let Err(e) = world_manager.save() {
+                    sender.send_console_message(format!("World \"{}\" save error: {}", slug, e));
+                    return Ok(());
+                }
+
+                maps_path.push(&slug);
+                let exporter = WebMapExporter::create(
+                    slug.clone(),
+                    world_manager.get_chunks_map().get_storage(),
+                    map_colors,
+                    maps_path.clone(),
+                );
+                sender.send_console_message(format!(
+                    "Web map export of world \"{}\" started into \"{}\"",
+                    slug,
+                    maps_path.display()
+                ));
+                thread::spawn(move || {
+                    let now = std::time::Instant::now();
+                    match exporter.export() {
+                        Ok(count) => {
+                            log::info!(target: "worlds", "Web map of &a\"{}\"&r exported; tiles rendered:{} (executed:{:.2?})", slug, count, now.elapsed())
+                        }
+                        Err(e) => log::error!(target: "worlds", "Web map of &c\"{}\"&r export error: {}", slug, e),
+                    }
+                });
+            }
+            "forceload" => {
+                if let Some(forceload_subcommand) = world_subcommand.subcommand() {
+                    command_world_forceload(&*worlds_manager, &sender, forceload_subcommand)?;
+                }
+            }
             _ => {
                 sender.send_console_message("Error".to_string());
             }
@@ -91,6 +185,176 @@ pub(crate) fn command_world(
     return Ok(());
 }
 
+/// Player name can be omitted by players and coordinates
+/// are the spawn position of the world by default
+fn command_world_tp(world: &mut World, sender: &Box<dyn ConsoleSenderType>, args: &CommandMatch) -> Result<(), String> {
+    let slug = args.get_arg::<String, _>("slug")?;
+    let rest: Vec<String> = ["player", "x", "y", "z"]
+        .iter()
+        .filter_map(|name| args.get_arg::<String, _>(*name).ok())
+        .collect();
+    let (player_name, coordinates) = match rest.len() {
+        0 => (None, None),
+        1 => (Some(rest[0].clone()), None),
+        3 => (None, Some(&rest[0..3])),
+        4 => (Some(rest[0].clone()), Some(&rest[1..4])),
+        _ => return Err("&cUsage: world tp <slug> [player] [x y z]".to_string()),
+    };
+
+    let client = match player_name {
+        Some(name) => match world.resource::<ClientsContainer>().get_by_login(&name) {
+            Some(c) => c.clone(),
+            None => return Err(format!("&cPlayer &4\"{}\"&c not found", name)),
+        },
+        None => match sender.as_any().downcast_ref::<ClientNetwork>() {
+            Some(c) => c.clone(),
+            None => {
+                sender.send_console_message("Player name is required for the console".to_string());
+                return Ok(());
+            }
+        },
+    };
+    let login = client
+        .get_client_info()
+        .map_or("-".to_string(), |i| i.get_login().clone());
+    if client.get_world_entity().is_none() {
+        sender.send_console_message(format!("Player \"{}\" is not in the world", login));
+        return Ok(());
+    }
+
+    let server_settings = world.resource::<ServerSettings>();
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(world_manager) = worlds_manager.get_world_manager(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+    let position = match coordinates {
+        Some(c) => {
+            let [x, y, z] = parse_coordinates(c)?;
+            world_manager.get_safe_teleport_position(server_settings, Position::new(x, y, z))
+        }
+        None => world_manager.get_safe_spawn_position(server_settings),
+    };
+    drop(world_manager);
+
+    ChangePlayerWorld::create(slug.clone(), client, position, Rotation::new(0.0, 0.0)).apply(world);
+    sender.send_console_message(format!("Player \"{}\" teleported to world \"{}\"", login, slug));
+    Ok(())
+}
+
+fn parse_coordinates(values: &[String]) -> Result<[f32; 3], String> {
+    let mut coordinates = [0.0_f32; 3];
+    for (i, value) in values.iter().take(3).enumerate() {
+        coordinates[i] = match value.parse::<f32>() {
+            Ok(v) => v,
+            Err(_) => return Err(format!("&cWrong coordinate &4\"{}\"", value)),
+        };
+    }
+    Ok(coordinates)
+}
+
+/// Players set the spawn of their world at their position;
+/// the console must specify the world and the coordinates
+fn command_world_setspawn(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let rest: Vec<String> = ["slug", "x", "y", "z"]
+        .iter()
+        .filter_map(|name| args.get_arg::<String, _>(*name).ok())
+        .collect();
+    let (slug, spawn) = match rest.len() {
+        0 => {
+            let Some(client) = sender.as_any().downcast_ref::<ClientNetwork>() else {
+                sender.send_console_message("World and coordinates are required for the console".to_string());
+                return Ok(());
+            };
+            let Some(world_entity) = client.get_world_entity() else {
+                return Ok(());
+            };
+            let worlds_manager = world.resource::<WorldsManager>();
+            let world_manager = worlds_manager.get_world_manager(world_entity.get_world_slug()).unwrap();
+            let Some(position) = world_manager
+                .get_ecs()
+                .get_entity(world_entity.get_entity())
+                .and_then(|e| e.get::<Position>().cloned())
+            else {
+                return Ok(());
+            };
+            (
+                world_entity.get_world_slug().clone(),
+                BlockPosition::from_position(&position.to_network()),
+            )
+        }
+        4 => {
+            let [x, y, z] = parse_coordinates(&rest[1..4])?;
+            (
+                rest[0].clone(),
+                BlockPosition::from_position(&Position::new(x, y, z).to_network()),
+            )
+        }
+        _ => return Err("&cUsage: world setspawn [slug x y z]".to_string()),
+    };
+
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+    world_manager.set_spawn(spawn)?;
+    sender.send_console_message(format!("Spawn of world \"{}\" is set to {:?}", slug, spawn));
+    Ok(())
+}
+
+/// Chunks coordinates are used for the forced chunks
+fn command_world_forceload(
+    worlds_manager: &WorldsManager,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let slug = args.get_arg::<String, _>("slug")?;
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+    let chunks_map = world_manager.get_chunks_map_mut();
+
+    match args.get_name().as_str() {
+        "add" => {
+            let chunk = ChunkPosition::new(args.get_arg::<i64, _>("x")?, args.get_arg::<i64, _>("z")?);
+            match chunks_map.add_forced_chunk(chunk.clone()) {
+                Ok(true) => sender.send_console_message(format!("Chunk {} of \"{}\" is forced to load", chunk, slug)),
+                Ok(false) => sender.send_console_message(format!("Chunk {} of \"{}\" is already forced", chunk, slug)),
+                Err(e) => sender.send_console_message(format!("Chunk {} force load error: {}", chunk, e)),
+            }
+        }
+        "remove" => {
+            let chunk = ChunkPosition::new(args.get_arg::<i64, _>("x")?, args.get_arg::<i64, _>("z")?);
+            match chunks_map.remove_forced_chunk(&chunk) {
+                Ok(true) => sender.send_console_message(format!("Chunk {} of \"{}\" is no longer forced", chunk, slug)),
+                Ok(false) => sender.send_console_message(format!("Chunk {} of \"{}\" is not forced", chunk, slug)),
+                Err(e) => sender.send_console_message(format!("Chunk {} force load error: {}", chunk, e)),
+            }
+        }
+        "list" => {
+            let forced = chunks_map.get_forced_chunks();
+            if forced.len() == 0 {
+                sender.send_console_message(format!("World \"{}\" has no forced chunks", slug));
+                return Ok(());
+            }
+            sender.send_console_message(format!("Forced chunks of \"{}\":", slug));
+            for chunk in forced {
+                sender.send_console_message(format!(" - {}", chunk));
+            }
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn command_parser_teleport() -> Command {
     Command::new("tp".to_owned())
         .arg(Arg::new("x".to_owned()).required(true))
@@ -117,7 +381,6 @@ pub(crate) fn command_teleport(
         }
     };
 
-    let position = Position::new(x, y, z);
     let rotation = Rotation::new(0.0, 0.0);
 
     let Some(world_entity) = client.get_world_entity() else {
@@ -131,7 +394,100 @@ pub(crate) fn command_teleport(
     let mut world_manager = worlds_manager
         .get_world_manager_mut(&world_entity.get_world_slug())
         .unwrap();
+    let position = world_manager.get_safe_teleport_position(world.resource::<ServerSettings>(), Position::new(x, y, z));
 
     move_player(&mut *world_manager, &world_entity, position, rotation);
+    client.get_movement_state_mut().reset();
+    client.send_teleport(&position);
     return Ok(());
 }
+
+pub(crate) fn command_parser_undo() -> Command {
+    Command::new("undo".to_owned()).arg(Arg::new("count".to_owned()))
+}
+
+pub(crate) fn command_undo(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    command_edit_history(world, sender, args, true)
+}
+
+pub(crate) fn command_parser_redo() -> Command {
+    Command::new("redo".to_owned()).arg(Arg::new("count".to_owned()))
+}
+
+pub(crate) fn command_redo(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    command_edit_history(world, sender, args, false)
+}
+
+/// Undo reverts the last actions of the player; redo applies them again
+fn command_edit_history(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+    undo: bool,
+) -> Result<(), String> {
+    let count = match args.get_arg::<usize, _>("count") {
+        Ok(c) => c.clamp(1, EDIT_HISTORY_LIMIT),
+        Err(_) => 1,
+    };
+
+    let client = match sender.as_any().downcast_ref::<ClientNetwork>() {
+        Some(c) => c,
+        None => {
+            sender.send_console_message("This command is allowed to be used only for players".to_string());
+            return Ok(());
+        }
+    };
+
+    let worlds_manager = world.resource::<WorldsManager>();
+    let server_settings = world.resource::<ServerSettings>();
+    let block_tick_handlers = world.resource::<BlockTickHandlers>();
+    let audit_log = world.resource::<AuditLog>();
+    let Some(login) = client.get_client_info().map(|i| i.get_login().clone()) else {
+        return Ok(());
+    };
+
+    let mut actions = 0;
+    let mut blocks = 0;
+    for _ in 0..count {
+        let mut history = client.get_edit_history_mut();
+        let action = match undo {
+            true => history.undo(),
+            false => history.redo(),
+        };
+        drop(history);
+        let Some(action) = action else {
+            break;
+        };
+        let action = match undo {
+            true => action.inverse(),
+            false => action,
+        };
+
+        let Some(world_manager) = worlds_manager.get_world_manager(action.get_world_slug()) else {
+            continue;
+        };
+        let applied = apply_block_changes(&*world_manager, server_settings, block_tick_handlers, &action);
+        for change in applied.iter() {
+            audit_log.record(&login, action.get_world_slug(), change);
+        }
+        blocks += applied.len();
+        actions += 1;
+    }
+
+    let message = match (undo, actions) {
+        (true, 0) => "Nothing to undo".to_string(),
+        (false, 0) => "Nothing to redo".to_string(),
+        (true, _) => format!("Undone {} actions ({} blocks changed)", actions, blocks),
+        (false, _) => format!("Redone {} actions ({} blocks changed)", actions, blocks),
+    };
+    sender.send_console_message(message);
+    Ok(())
+}
@@ -1,6 +1,10 @@
 use ahash::AHashMap;
 use bevy::prelude::{Bundle, Component, Entity, EntityRef, EntityWorldMut, QueryState, World};
-use bevy_ecs::{change_detection::Mut, component::Mutable, query::QueryData};
+use bevy_ecs::{
+    change_detection::Mut,
+    component::Mutable,
+    query::{QueryData, With},
+};
 use common::{chunks::chunk_position::ChunkPosition, utils::vec_remove_item};
 
 /// A wrapper around `bevy::prelude::World`
@@ -73,6 +77,12 @@ impl Ecs {
         self.ecs.entity_mut(entity)
     }
 
+    /// All entities which have the component
+    pub fn get_entities_with<T: Component>(&mut self) -> Vec<Entity> {
+        let mut query = self.ecs.query_filtered::<Entity, With<T>>();
+        query.iter(&self.ecs).collect()
+    }
+
     pub fn _query<D: QueryData>(&mut self) -> QueryState<D, ()> {
         self.ecs.query::<D>()
     }
@@ -0,0 +1,214 @@
+use common::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+use std::collections::VecDeque;
+
+use crate::client_resources::server_settings::ServerSettings;
+use crate::network::sync_world_change::sync_world_block_changes;
+
+use super::{
+    block_ticks::{BlockTickHandlers, schedule_block_updates},
+    world_manager::WorldManager,
+};
+
+/// How many actions of the player can be undone
+pub const EDIT_HISTORY_LIMIT: usize = 100;
+
+/// Bulk operations can be huge, so the total count of blocks is also limited
+pub const EDIT_HISTORY_BLOCKS_LIMIT: usize = 1_000_000;
+
+#[derive(Clone, Debug)]
+pub struct BlockChange {
+    position: BlockPosition,
+    old_block_info: Option<BlockDataInfo>,
+    new_block_info: Option<BlockDataInfo>,
+}
+
+impl BlockChange {
+    pub fn new(
+        position: BlockPosition,
+        old_block_info: Option<BlockDataInfo>,
+        new_block_info: Option<BlockDataInfo>,
+    ) -> Self {
+        Self {
+            position,
+            old_block_info,
+            new_block_info,
+        }
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_old_block_info(&self) -> &Option<BlockDataInfo> {
+        &self.old_block_info
+    }
+
+    pub fn get_new_block_info(&self) -> &Option<BlockDataInfo> {
+        &self.new_block_info
+    }
+
+    /// The change which reverts this one
+    pub fn inverse(&self) -> Self {
+        Self::new(self.position.clone(), self.new_block_info, self.old_block_info)
+    }
+}
+
+/// Single edit of the player; can contain many blocks
+#[derive(Clone, Debug)]
+pub struct EditAction {
+    world_slug: String,
+    changes: Vec<BlockChange>,
+}
+
+impl EditAction {
+    pub fn new(world_slug: String, changes: Vec<BlockChange>) -> Self {
+        Self { world_slug, changes }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_changes(&self) -> &Vec<BlockChange> {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Changes are reverted in the reverse order
+    pub fn inverse(&self) -> Self {
+        let changes = self.changes.iter().rev().map(|c| c.inverse()).collect();
+        Self::new(self.world_slug.clone(), changes)
+    }
+}
+
+/// Bounded history of the player's edits
+#[derive(Default)]
+pub struct EditHistory {
+    undo: VecDeque<EditAction>,
+    redo: Vec<EditAction>,
+}
+
+impl EditHistory {
+    /// New action makes the redo history obsolete
+    pub fn push(&mut self, action: EditAction) {
+        if action.len() == 0 {
+            return;
+        }
+        self.redo.clear();
+        self.push_undo(action);
+    }
+
+    fn push_undo(&mut self, action: EditAction) {
+        self.undo.push_back(action);
+        while self.undo.len() > EDIT_HISTORY_LIMIT
+            || (self.undo.len() > 1 && self.get_blocks_count() > EDIT_HISTORY_BLOCKS_LIMIT)
+        {
+            self.undo.pop_front();
+        }
+    }
+
+    fn get_blocks_count(&self) -> usize {
+        self.undo.iter().map(|a| a.len()).sum()
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Returns the action which must be reverted
+    pub fn undo(&mut self) -> Option<EditAction> {
+        let action = self.undo.pop_back()?;
+        self.redo.push(action.clone());
+        Some(action)
+    }
+
+    /// Returns the action which must be applied again
+    pub fn redo(&mut self) -> Option<EditAction> {
+        let action = self.redo.pop()?;
+        self.push_undo(action.clone());
+        Some(action)
+    }
+}
+
+/// Applies the changes to the world and syncs them with the clients
+///
+/// Blocks that were changed by someone else since then are skipped;
+/// returns the changes which were applied
+pub fn apply_block_changes(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    block_tick_handlers: &BlockTickHandlers,
+    action: &EditAction,
+) -> Vec<BlockChange> {
+    let chunks_map = world_manager.get_chunks_map();
+    let mut applied: Vec<BlockChange> = Default::default();
+    for change in action.get_changes() {
+        if !chunks_map.is_position_loaded(&change.position) {
+            continue;
+        }
+        if chunks_map.get_block_info(&change.position) != change.old_block_info {
+            continue;
+        }
+        if chunks_map
+            .edit_block(change.position.clone(), change.new_block_info.clone())
+            .is_err()
+        {
+            continue;
+        }
+        applied.push(change.clone());
+    }
+
+    for change in applied.iter() {
+        schedule_block_updates(chunks_map, server_settings, block_tick_handlers, &change.position);
+    }
+    let blocks: Vec<(BlockPosition, Option<BlockDataInfo>)> =
+        applied.iter().map(|c| (c.position.clone(), c.new_block_info)).collect();
+    sync_world_block_changes(world_manager, &blocks);
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockChange, EDIT_HISTORY_LIMIT, EditAction, EditHistory};
+    use common::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+
+    fn create_action(x: i64) -> EditAction {
+        let change = BlockChange::new(BlockPosition::new(x, 0, 0), None, Some(BlockDataInfo::create(1, None)));
+        EditAction::new("default".to_string(), vec![change])
+    }
+
+    #[test]
+    fn test_edit_history() {
+        let mut history = EditHistory::default();
+        history.push(create_action(1));
+        history.push(create_action(2));
+
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.get_changes()[0].get_position(), &BlockPosition::new(2, 0, 0));
+        assert_eq!(history.redo_len(), 1);
+
+        let inverse = undone.inverse();
+        assert_eq!(inverse.get_changes()[0].new_block_info, None);
+
+        history.redo().unwrap();
+        assert_eq!(history.undo_len(), 2);
+
+        // A new action clears the redo history
+        history.undo().unwrap();
+        history.push(create_action(3));
+        assert_eq!(history.redo_len(), 0);
+        assert!(history.redo().is_none());
+
+        for x in 0..EDIT_HISTORY_LIMIT * 2 {
+            history.push(create_action(x as i64));
+        }
+        assert_eq!(history.undo_len(), EDIT_HISTORY_LIMIT);
+    }
+}
@@ -0,0 +1,173 @@
+use ahash::AHashSet;
+use bevy::prelude::{Entity, Res};
+use bevy::time::Time;
+use common::chunks::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::BlockDataInfo,
+    chunk_position::ChunkPosition,
+};
+
+use crate::{
+    client_resources::server_settings::ServerSettings,
+    entities::{entity::Position, falling_block::FallingBlockComponent},
+    network::{
+        sync_entities::{sync_entity_despawn, sync_entity_move},
+        sync_world_change::sync_world_block_change,
+    },
+};
+
+use super::{
+    block_ticks::{BlockTickHandlers, schedule_block_updates},
+    chunks::chunks_map::ChunkMap,
+    world_manager::WorldManager,
+    worlds_manager::WorldsManager,
+};
+
+/// Blocks per second squared
+pub const FALLING_BLOCK_GRAVITY: f32 = 20.0;
+
+/// Blocks per second
+pub const FALLING_BLOCK_MAX_VELOCITY: f32 = 40.0;
+
+/// Falling blocks pass through the air and fluids
+pub fn is_passable_for_falling(server_settings: &ServerSettings, block_info: &Option<BlockDataInfo>) -> bool {
+    match block_info {
+        Some(b) => server_settings.is_fluid(&b.get_id()),
+        None => true,
+    }
+}
+
+enum FallingStep {
+    // Chunk below is not loaded
+    Frozen,
+    Moved(f32),
+    // None if the block fell out of the world
+    Landed(Option<BlockPosition>),
+}
+
+/// Checks all cells which the block passes during the step
+fn get_falling_step(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    position: &Position,
+    velocity: f32,
+    delta: f32,
+) -> FallingStep {
+    let new_y = position.get_y() - velocity * delta;
+
+    // Block occupies the cell from y - 0.5 to y + 0.5
+    let old_bottom = position.get_y() - 0.5;
+    let new_bottom = new_y - 0.5;
+
+    let mut cell_position = BlockPosition::from_position(&position.to_network());
+    let mut cell = old_bottom.ceil() as i64 - 1;
+    while cell >= new_bottom.floor() as i64 {
+        if cell < 0 {
+            return FallingStep::Landed(None);
+        }
+        cell_position = cell_position.offset(0, cell - cell_position.get_y(), 0);
+        if !chunks_map.is_position_loaded(&cell_position) {
+            return FallingStep::Frozen;
+        }
+        if !is_passable_for_falling(server_settings, &chunks_map.get_block_info(&cell_position)) {
+            return FallingStep::Landed(Some(cell_position.offset(0, 1, 0)));
+        }
+        cell -= 1;
+    }
+    FallingStep::Moved(new_y)
+}
+
+/// Simulates falling blocks of all worlds and turns them back into blocks on landing
+pub fn update_falling_blocks(
+    worlds_manager: Res<WorldsManager>,
+    time: Res<Time>,
+    server_settings: Res<ServerSettings>,
+    handlers: Res<BlockTickHandlers>,
+) {
+    let delta = time.delta_secs();
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let mut world_manager = world.write();
+        let mut changed_chunks: AHashSet<ChunkPosition> = Default::default();
+        for entity in world_manager.get_ecs_mut().get_entities_with::<FallingBlockComponent>() {
+            let (position, falling_block) = {
+                let entity_ref = world_manager.get_ecs().get_entity(entity).unwrap();
+                (
+                    *entity_ref.get::<Position>().unwrap(),
+                    entity_ref.get::<FallingBlockComponent>().unwrap().clone(),
+                )
+            };
+
+            let velocity =
+                (falling_block.get_velocity() + FALLING_BLOCK_GRAVITY * delta).min(FALLING_BLOCK_MAX_VELOCITY);
+            match get_falling_step(
+                world_manager.get_chunks_map(),
+                &server_settings,
+                &position,
+                velocity,
+                delta,
+            ) {
+                FallingStep::Frozen => continue,
+                FallingStep::Moved(new_y) => {
+                    let mut entity_mut = world_manager.get_ecs_mut().entity_mut(entity);
+                    entity_mut.get_mut::<Position>().unwrap().set_y(new_y);
+                    entity_mut
+                        .get_mut::<FallingBlockComponent>()
+                        .unwrap()
+                        .set_velocity(velocity);
+
+                    // Block falls vertically, so the chunk is never changed
+                    sync_entity_move(&world_manager, entity, &None);
+                }
+                FallingStep::Landed(block_position) => {
+                    let placed = land_falling_block(
+                        &mut world_manager,
+                        entity,
+                        &position,
+                        block_position,
+                        falling_block.get_block_info(),
+                        &server_settings,
+                        &handlers,
+                    );
+                    if let Some(block_position) = placed {
+                        changed_chunks.insert(block_position.get_chunk_position());
+                    }
+                }
+            }
+        }
+        for chunk_position in changed_chunks.iter() {
+            world_manager.get_chunks_map().relight_chunk(chunk_position);
+        }
+    }
+}
+
+/// Returns the position of the placed block
+fn land_falling_block(
+    world_manager: &mut WorldManager,
+    entity: Entity,
+    position: &Position,
+    block_position: Option<BlockPosition>,
+    block_info: &BlockDataInfo,
+    server_settings: &ServerSettings,
+    handlers: &BlockTickHandlers,
+) -> Option<BlockPosition> {
+    sync_entity_despawn(world_manager, entity);
+    world_manager
+        .get_ecs_mut()
+        .despawn(entity, Some(position.get_chunk_position()));
+
+    let block_position = block_position?;
+    let chunks_map = world_manager.get_chunks_map();
+
+    // Cell could be occupied while the block was falling
+    if !is_passable_for_falling(server_settings, &chunks_map.get_block_info(&block_position)) {
+        log::debug!(target: "worlds", "Falling block {:?} is destroyed at {:?}", block_info, block_position);
+        return None;
+    }
+    if let Err(e) = chunks_map.edit_block_no_light(block_position.clone(), Some(block_info.clone())) {
+        log::error!(target: "worlds", "&cFalling block landing error: {}", e);
+        return None;
+    }
+    schedule_block_updates(chunks_map, server_settings, handlers, &block_position);
+    sync_world_block_change(world_manager, block_position.clone(), Some(block_info.clone()));
+    Some(block_position)
+}
@@ -0,0 +1,74 @@
+use crate::console::console_sender::ConsoleSenderType;
+use crate::network::clients_container::ClientsContainer;
+use crate::network::sync_players::sync_player_chunks_change;
+use bevy_ecs::world::World;
+use common::commands::command::{Arg, Command, CommandMatch};
+use common::game_rules::GameRule;
+use std::str::FromStr;
+
+use super::world_manager::WorldManager;
+use super::worlds_manager::WorldsManager;
+
+pub(crate) fn command_parser_game_rule() -> Command {
+    let rules: Vec<String> = GameRule::get_all().iter().map(|r| r.to_string()).collect();
+    Command::new("gamerule".to_owned())
+        .arg(Arg::new("slug".to_owned()).required(true))
+        .arg(Arg::new("rule".to_owned()).required(true).choices(rules))
+        .arg(Arg::new("value".to_owned()))
+}
+
+/// Shows the rule without the value
+pub(crate) fn command_game_rule(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let slug = args.get_arg::<String, _>("slug")?;
+    let rule = args.get_arg::<String, _>("rule")?;
+    let Ok(rule) = GameRule::from_str(&rule) else {
+        return Err(format!("&cGame rule &4\"{}\"&c is unknown", rule));
+    };
+
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    let Ok(value) = args.get_arg::<String, _>("value") else {
+        sender.send_console_message(format!(
+            "Game rule \"{}\" of world \"{}\" is {}",
+            rule,
+            slug,
+            world_manager.get_game_rules().get_value(&rule)
+        ));
+        return Ok(());
+    };
+    world_manager.set_game_rule(&rule, &value)?;
+
+    if rule == GameRule::MaxViewDistance {
+        update_view_distance(&mut *world_manager, world.resource::<ClientsContainer>());
+    }
+    sender.send_console_message(format!(
+        "Game rule \"{}\" of world \"{}\" is set to {}",
+        rule,
+        slug,
+        world_manager.get_game_rules().get_value(&rule)
+    ));
+    Ok(())
+}
+
+/// Players inside the world see the chunks according to the new limit
+fn update_view_distance(world_manager: &mut WorldManager, clients: &ClientsContainer) {
+    for (_client_id, client) in clients.iter() {
+        let Some(world_entity) = client.get_world_entity() else {
+            continue;
+        };
+        if world_entity.get_world_slug() != world_manager.get_slug() {
+            continue;
+        }
+        let change = world_manager.change_render_distance(&world_entity, client.get_render_distance());
+        client.send_unload_chunks(world_entity.get_world_slug(), change.abandoned_chunks.clone());
+        sync_player_chunks_change(world_manager, world_entity.get_entity(), &change);
+    }
+}
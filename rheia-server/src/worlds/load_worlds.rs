@@ -19,6 +19,7 @@ pub(crate) fn load_worlds(
     }
 
     let world_storage_settings = launch_settings.get_world_storage_settings();
+    worlds_manager.set_light_blocks(server_settings.get_light_blocks());
 
     if let Err(e) = worlds_manager.scan_worlds(&world_storage_settings, server_settings.get_block_id_map()) {
         log::error!(target: "worlds", "&cWorlds loading error!");
@@ -5,18 +5,65 @@ pub mod load_worlds;
 
 use crate::{
     client_resources::server_settings::rescan_server_settings,
+    launch_settings::LaunchSettings,
     console::commands_executer::{CommandExecuter, CommandsHandler},
+    network::runtime_plugin::RuntimePlugin,
 };
 
 use self::{
-    console_commands::{command_parser_teleport, command_parser_world, command_teleport, command_world},
-    worlds_manager::{WorldsManager, update_world_chunks},
+    audit_commands::{command_log, command_parser_log, command_parser_rollback, command_rollback},
+    audit_log::{AuditLog, flush_audit_log},
+    block_handlers::{register_default_block_handlers, register_falling_blocks},
+    block_ticks::{BlockTickHandlers, update_block_ticks},
+    border::update_world_border,
+    border_commands::{command_border, command_parser_border},
+    bulk_edit::{BulkEditQueue, update_bulk_edits},
+    bulk_edit_commands::{
+        command_copy, command_flip, command_parser_copy, command_parser_flip, command_parser_paste,
+        command_parser_pos1, command_parser_pos2, command_parser_replace, command_parser_rotate, command_parser_set,
+        command_parser_walls, command_paste, command_pos1, command_pos2, command_replace, command_rotate, command_set,
+        command_walls,
+    },
+    console_commands::{
+        command_parser_redo, command_parser_teleport, command_parser_undo, command_parser_world, command_redo,
+        command_teleport, command_undo, command_world,
+    },
+    falling_blocks::update_falling_blocks,
+    game_rule_commands::{command_game_rule, command_parser_game_rule},
+    npc_behaviours::update_npc_behaviours,
+    npc_commands::{command_npc, command_parser_npc},
+    region_commands::{command_parser_region, command_region},
+    time_commands::{command_parser_time, command_time},
+    weather::update_world_weather,
+    weather_commands::{command_parser_weather, command_weather},
+    worlds_manager::{WorldsManager, update_world_chunks, update_world_time},
 };
 
+pub mod audit_commands;
+pub mod audit_log;
+pub mod block_handlers;
+pub mod block_ticks;
+pub mod border;
+pub mod border_commands;
+pub mod bulk_edit;
+pub mod bulk_edit_commands;
 pub mod chunks;
 pub mod console_commands;
 pub mod ecs;
+pub mod edit_history;
+pub mod falling_blocks;
+pub mod game_rule_commands;
+pub mod npc_behaviours;
+pub mod npc_commands;
 pub mod on_chunk_loaded;
+pub mod pathfinding;
+pub mod region_commands;
+pub mod regions;
+pub mod spawn;
+pub mod time_commands;
+pub mod weather;
+pub mod weather_commands;
+pub mod web_map;
 pub mod world_manager;
 pub mod worlds_manager;
 
@@ -33,12 +80,51 @@ impl Plugin for WorldsHandlerPlugin {
         let mut commands_handler = app.world_mut().get_resource_mut::<CommandsHandler>().unwrap();
         commands_handler.add_command_executer(CommandExecuter::new(command_parser_world(), command_world));
         commands_handler.add_command_executer(CommandExecuter::new(command_parser_teleport(), command_teleport));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_region(), command_region));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_undo(), command_undo));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_redo(), command_redo));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_pos1(), command_pos1));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_pos2(), command_pos2));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_set(), command_set));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_replace(), command_replace));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_walls(), command_walls));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_copy(), command_copy));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_paste(), command_paste));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_rotate(), command_rotate));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_flip(), command_flip));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_log(), command_log));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_rollback(), command_rollback));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_time(), command_time));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_weather(), command_weather));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_border(), command_border));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_game_rule(), command_game_rule));
+        commands_handler.add_command_executer(CommandExecuter::new(command_parser_npc(), command_npc));
 
-        let worlds_manager = WorldsManager::default();
+        let launch_settings = app.world().get_resource::<LaunchSettings>().unwrap();
+        let worlds_manager = WorldsManager::new(launch_settings.get_args().corrupt_chunk_policy);
+
+        let mut audit_log_path = launch_settings.get_server_data_path();
+        audit_log_path.push("block_log.db");
+        let audit_log = RuntimePlugin::open_database(audit_log_path, AuditLog::create);
         app.insert_resource(worlds_manager);
+        app.insert_resource(audit_log);
+
+        let mut block_tick_handlers = BlockTickHandlers::default();
+        register_default_block_handlers(&mut block_tick_handlers);
+        app.insert_resource(block_tick_handlers);
+        app.insert_resource(BulkEditQueue::default());
 
         app.add_systems(Startup, load_worlds::load_worlds.after(rescan_server_settings));
+        app.add_systems(Startup, register_falling_blocks.after(rescan_server_settings));
         app.add_systems(Update, update_world_chunks);
+        app.add_systems(Update, update_world_time);
+        app.add_systems(Update, update_world_weather);
+        app.add_systems(Update, update_world_border);
+        app.add_systems(Update, update_block_ticks.after(update_world_chunks));
+        app.add_systems(Update, update_falling_blocks.after(update_block_ticks));
+        app.add_systems(Update, update_npc_behaviours.after(update_world_chunks));
+        app.add_systems(Update, update_bulk_edits.after(update_world_chunks));
+        app.add_systems(Update, flush_audit_log.after(update_bulk_edits));
         app.add_systems(Update, on_chunk_loaded::on_chunk_loaded);
     }
 }
@@ -0,0 +1,283 @@
+use bevy::prelude::{Entity, Res};
+use bevy::time::Time;
+use bracket_lib::random::RandomNumberGenerator;
+use common::chunks::block_position::BlockPosition;
+
+use crate::{
+    client_resources::server_settings::ServerSettings,
+    entities::{
+        entity::{Position, Rotation},
+        npc_behaviour::{NpcBehaviour, NpcBehaviourComponent},
+    },
+    network::{clients_container::ClientsContainer, sync_entities::sync_entity_move},
+};
+
+use super::{
+    chunks::chunks_map::ChunkMap,
+    pathfinding::{PATH_MAX_DROP, is_standable},
+    world_manager::WorldManager,
+    worlds_manager::WorldsManager,
+};
+
+/// Blocks per second
+pub const NPC_WALK_SPEED: f32 = 4.0;
+
+/// Seconds between the wander goals
+pub const NPC_WANDER_MIN_WAIT: f32 = 2.0;
+pub const NPC_WANDER_MAX_WAIT: f32 = 6.0;
+
+/// Seconds at each waypoint of the patrol
+pub const NPC_PATROL_WAIT: f32 = 1.0;
+
+/// Seconds before the failed search is repeated
+pub const NPC_PATH_RETRY: f32 = 2.0;
+
+/// Follower stops at this distance to the target
+pub const NPC_FOLLOW_DISTANCE: f32 = 3.0;
+
+/// Follower searches the new path when the target moves this far from the goal
+pub const NPC_FOLLOW_REPATH_DISTANCE: i64 = 2;
+
+fn get_block_center(position: &BlockPosition) -> Position {
+    Position::new(
+        position.get_x() as f32 + 0.5,
+        position.get_y() as f32,
+        position.get_z() as f32 + 0.5,
+    )
+}
+
+fn is_standable_block(chunks_map: &ChunkMap, server_settings: &ServerSettings, position: &BlockPosition) -> bool {
+    is_standable(position, |p| chunks_map.get_path_cell(server_settings, p))
+}
+
+/// The nearest position below the block where the entity can stand
+fn find_ground(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    position: &BlockPosition,
+    depth: i64,
+) -> Option<BlockPosition> {
+    (0..=depth)
+        .map(|h| position.offset(0, -h, 0))
+        .find(|p| is_standable_block(chunks_map, server_settings, p))
+}
+
+/// Random position around the home where the npc can stand
+fn get_wander_goal(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    home: &BlockPosition,
+    radius: i64,
+    rng: &mut RandomNumberGenerator,
+) -> Option<BlockPosition> {
+    let top = home.offset(rng.range(-radius, radius + 1), radius, rng.range(-radius, radius + 1));
+    find_ground(chunks_map, server_settings, &top, radius * 2)
+}
+
+fn get_goal(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    clients: &ClientsContainer,
+    behaviour: &mut NpcBehaviourComponent,
+    position: &Position,
+    rng: &mut RandomNumberGenerator,
+) -> Option<BlockPosition> {
+    let chunks_map = world_manager.get_chunks_map();
+    match behaviour.get_behaviour().clone() {
+        NpcBehaviour::Idle => None,
+        NpcBehaviour::Wander { radius } => {
+            get_wander_goal(chunks_map, server_settings, behaviour.get_home(), radius, rng)
+        }
+        NpcBehaviour::Follow { login, distance } => {
+            let Some(target_position) = get_target_position(world_manager, clients, &login) else {
+                // Target has left the world
+                behaviour.set_behaviour(NpcBehaviour::Idle);
+                return None;
+            };
+            if get_distance(position, &target_position) <= distance {
+                return None;
+            }
+            // Target could be in the air while jumping
+            let target_block = BlockPosition::from_position(&target_position.to_network());
+            find_ground(chunks_map, server_settings, &target_block, PATH_MAX_DROP)
+        }
+        NpcBehaviour::Patrol { waypoints, next } => waypoints.get(next).copied(),
+        NpcBehaviour::ReturnHome => Some(*behaviour.get_home()),
+    }
+}
+
+/// Player is searched every time, so the entity of the previous session is never followed
+fn get_target_position(world_manager: &WorldManager, clients: &ClientsContainer, login: &String) -> Option<Position> {
+    let world_entity = clients.get_by_login(login)?.get_world_entity()?;
+    if world_entity.get_world_slug() != world_manager.get_slug() {
+        return None;
+    }
+    let entity_ref = world_manager.get_ecs().get_entity(world_entity.get_entity())?;
+    entity_ref.get::<Position>().copied()
+}
+
+fn get_distance(a: &Position, b: &Position) -> f32 {
+    let (dx, dy, dz) = (b.get_x() - a.get_x(), b.get_y() - a.get_y(), b.get_z() - a.get_z());
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+fn on_goal_reached(behaviour: &mut NpcBehaviourComponent, rng: &mut RandomNumberGenerator) {
+    match behaviour.get_behaviour_mut() {
+        NpcBehaviour::Idle => behaviour.stop(0.0),
+        NpcBehaviour::Wander { .. } => behaviour.stop(rng.range(NPC_WANDER_MIN_WAIT, NPC_WANDER_MAX_WAIT)),
+        NpcBehaviour::Follow { .. } => behaviour.stop(0.0),
+        NpcBehaviour::Patrol { waypoints, next } => {
+            *next = (*next + 1) % waypoints.len();
+            behaviour.stop(NPC_PATROL_WAIT);
+        }
+        NpcBehaviour::ReturnHome => behaviour.set_behaviour(NpcBehaviour::Idle),
+    }
+}
+
+fn on_path_failed(behaviour: &mut NpcBehaviourComponent) {
+    // Unreachable waypoint is skipped
+    if let NpcBehaviour::Patrol { waypoints, next } = behaviour.get_behaviour_mut() {
+        *next = (*next + 1) % waypoints.len();
+    }
+    behaviour.stop(NPC_PATH_RETRY);
+}
+
+/// Follower stops at the passed step if the target has moved away from the goal
+fn is_follow_goal_outdated(
+    world_manager: &WorldManager,
+    clients: &ClientsContainer,
+    behaviour: &NpcBehaviourComponent,
+) -> bool {
+    let NpcBehaviour::Follow { login, .. } = behaviour.get_behaviour() else {
+        return false;
+    };
+    let (Some(goal), Some(target_position)) =
+        (behaviour.get_goal(), get_target_position(world_manager, clients, login))
+    else {
+        return true;
+    };
+    let target_block = BlockPosition::from_position(&target_position.to_network());
+    (target_block.get_x() - goal.get_x()).abs() > NPC_FOLLOW_REPATH_DISTANCE
+        || (target_block.get_z() - goal.get_z()).abs() > NPC_FOLLOW_REPATH_DISTANCE
+}
+
+/// Moves the npc towards the next step of the path
+///
+/// Returns the new position and rotation if the npc has moved
+fn walk(
+    world_manager: &WorldManager,
+    server_settings: &ServerSettings,
+    clients: &ClientsContainer,
+    behaviour: &mut NpcBehaviourComponent,
+    position: &Position,
+    delta: f32,
+    rng: &mut RandomNumberGenerator,
+) -> Option<(Position, Rotation)> {
+    let chunks_map = world_manager.get_chunks_map();
+    let step = *behaviour.get_next_step()?;
+
+    // Cached path could lead through the chunk which is not loaded anymore
+    if !is_standable_block(chunks_map, server_settings, &step) {
+        chunks_map.forget_path(behaviour.get_path_start(), behaviour.get_goal().unwrap());
+        behaviour.stop(0.0);
+        return None;
+    }
+
+    let target = get_block_center(&step);
+    let (dx, dy, dz) = (
+        target.get_x() - position.get_x(),
+        target.get_y() - position.get_y(),
+        target.get_z() - position.get_z(),
+    );
+    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+    let travel = NPC_WALK_SPEED * delta;
+
+    let new_position = match travel >= distance {
+        true => {
+            if behaviour.step_passed() {
+                on_goal_reached(behaviour, rng);
+            } else if is_follow_goal_outdated(world_manager, clients, behaviour) {
+                behaviour.stop(0.0);
+            }
+            target
+        }
+        false => Position::new(
+            position.get_x() + dx / distance * travel,
+            position.get_y() + dy / distance * travel,
+            position.get_z() + dz / distance * travel,
+        ),
+    };
+
+    // Npc looks where it walks; the forward direction is -Z
+    let yaw = (-dx).atan2(-dz).to_degrees();
+    Some((new_position, Rotation::new(0.0, yaw)))
+}
+
+fn update_npc(
+    world_manager: &mut WorldManager,
+    entity: Entity,
+    server_settings: &ServerSettings,
+    clients: &ClientsContainer,
+    delta: f32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let (position, mut behaviour) = {
+        let entity_ref = world_manager.get_ecs().get_entity(entity).unwrap();
+        (
+            *entity_ref.get::<Position>().unwrap(),
+            entity_ref.get::<NpcBehaviourComponent>().unwrap().clone(),
+        )
+    };
+
+    if behaviour.get_goal().is_none() && behaviour.update_wait(delta) {
+        if let Some(goal) = get_goal(world_manager, server_settings, clients, &mut behaviour, &position, rng) {
+            // Npc could be stopped in the air while jumping over the gap
+            let chunks_map = world_manager.get_chunks_map();
+            let block = BlockPosition::from_position(&position.to_network());
+            let path = find_ground(chunks_map, server_settings, &block, PATH_MAX_DROP)
+                .and_then(|start| Some((start, chunks_map.find_path(server_settings, &start, &goal)?)));
+            match path {
+                Some((_start, path)) if path.is_empty() => on_goal_reached(&mut behaviour, rng),
+                Some((start, path)) => behaviour.set_path(start, goal, path),
+                None => on_path_failed(&mut behaviour),
+            }
+        }
+    }
+
+    let moved = walk(
+        world_manager,
+        server_settings,
+        clients,
+        &mut behaviour,
+        &position,
+        delta,
+        rng,
+    );
+    *world_manager
+        .get_ecs_mut()
+        .entity_mut(entity)
+        .get_mut::<NpcBehaviourComponent>()
+        .unwrap() = behaviour;
+
+    if let Some((new_position, rotation)) = moved {
+        let chunks_changed = world_manager.npc_move(entity, new_position, rotation);
+        sync_entity_move(world_manager, entity, &chunks_changed);
+    }
+}
+
+/// Walks the npcs of all worlds according to their behaviours
+pub fn update_npc_behaviours(
+    worlds_manager: Res<WorldsManager>,
+    time: Res<Time>,
+    server_settings: Res<ServerSettings>,
+    clients: Res<ClientsContainer>,
+) {
+    let delta = time.delta_secs();
+    let mut rng = RandomNumberGenerator::new();
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let mut world_manager = world.write();
+        for entity in world_manager.get_ecs_mut().get_entities_with::<NpcBehaviourComponent>() {
+            update_npc(&mut world_manager, entity, &server_settings, &clients, delta, &mut rng);
+        }
+    }
+}
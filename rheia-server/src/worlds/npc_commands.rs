@@ -0,0 +1,292 @@
+use crate::client_resources::server_settings::ServerSettings;
+use crate::console::console_sender::ConsoleSenderType;
+use crate::entities::EntityComponent;
+use crate::entities::entity::{Position, Rotation};
+use crate::entities::entity_tag::EntityTagComponent;
+use crate::entities::npc::NpcComponent;
+use crate::entities::npc_behaviour::{NpcBehaviour, NpcBehaviourComponent};
+use crate::entities::skin::EntitySkinComponent;
+use crate::network::client_network::ClientNetwork;
+use crate::network::sync_entities::{sync_entity_despawn, sync_entity_spawn};
+use bevy_ecs::world::World;
+use common::chunks::block_position::{BlockPosition, BlockPositionTrait};
+use common::commands::command::{Arg, Command, CommandMatch};
+use network::messages::{NetworkEntitySkin, NetworkEntityTag};
+
+use super::npc_behaviours::NPC_FOLLOW_DISTANCE;
+use super::time_commands::get_sender_world_slug;
+use super::worlds_manager::WorldsManager;
+
+pub(crate) fn command_parser_npc() -> Command {
+    Command::new("npc".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("spawn".to_owned())
+                .arg(Arg::new("skin".to_owned()).required(true))
+                .arg(Arg::new("name".to_owned())),
+        )
+        .subcommand(
+            Command::new("remove".to_owned())
+                .arg(Arg::new("id".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(Command::new("list".to_owned()).arg(Arg::new("slug".to_owned())))
+        .subcommand(
+            Command::new("wander".to_owned())
+                .arg(Arg::new("id".to_owned()).required(true))
+                .arg(Arg::new("radius".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(Command::new("follow".to_owned()).arg(Arg::new("id".to_owned()).required(true)))
+        .subcommand(Command::new("waypoint".to_owned()).arg(Arg::new("id".to_owned()).required(true)))
+        .subcommand(
+            Command::new("home".to_owned())
+                .arg(Arg::new("id".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(
+            Command::new("stop".to_owned())
+                .arg(Arg::new("id".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+}
+
+/// Skin is "generic" or the slug of the block
+fn parse_skin(server_settings: &ServerSettings, name: &String) -> Result<NetworkEntitySkin, String> {
+    if name == "generic" {
+        return Ok(NetworkEntitySkin::Generic);
+    }
+    for (id, slug) in server_settings.get_block_id_map().iter() {
+        if slug == name {
+            return Ok(NetworkEntitySkin::Block(*id));
+        }
+    }
+    Err(format!(
+        "&cSkin &4\"{}\"&c not found; use \"generic\" or the block slug",
+        name
+    ))
+}
+
+pub(crate) fn command_npc(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(npc_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    match npc_subcommand.get_name().as_str() {
+        "spawn" => command_npc_spawn(world, &sender, npc_subcommand),
+        "remove" => command_npc_remove(world, &sender, npc_subcommand),
+        "list" => command_npc_list(world, &sender, npc_subcommand),
+        "wander" => command_npc_wander(world, &sender, npc_subcommand),
+        "follow" => command_npc_follow(world, &sender, npc_subcommand),
+        "waypoint" => command_npc_waypoint(world, &sender, npc_subcommand),
+        "home" => {
+            let slug = get_sender_world_slug(&sender, npc_subcommand)?;
+            let id = npc_subcommand.get_arg::<u64, _>("id")?;
+            change_npc_behaviour(world, &sender, &slug, id, |b| b.set_behaviour(NpcBehaviour::ReturnHome))
+        }
+        "stop" => {
+            let slug = get_sender_world_slug(&sender, npc_subcommand)?;
+            let id = npc_subcommand.get_arg::<u64, _>("id")?;
+            change_npc_behaviour(world, &sender, &slug, id, |b| b.set_behaviour(NpcBehaviour::Idle))
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+            Ok(())
+        }
+    }
+}
+
+/// Npc appears at the position of the player
+fn command_npc_spawn(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let Some(client) = sender.as_any().downcast_ref::<ClientNetwork>() else {
+        sender.send_console_message("This command is allowed to be used only for players".to_string());
+        return Ok(());
+    };
+    let Some(world_entity) = client.get_world_entity() else {
+        return Ok(());
+    };
+    let skin = parse_skin(world.resource::<ServerSettings>(), &args.get_arg::<String, _>("skin")?)?;
+
+    let mut components = vec![EntityComponent::Skin(Some(EntitySkinComponent::create(skin)))];
+    if let Ok(name) = args.get_arg::<String, _>("name") {
+        let tag = NetworkEntityTag::create(name, 2.1, 32, 3);
+        components.push(EntityComponent::Tag(Some(EntityTagComponent::create(tag))));
+    }
+
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(world_entity.get_world_slug()) else {
+        return Ok(());
+    };
+    let Some((position, rotation)) = world_manager
+        .get_ecs()
+        .get_entity(world_entity.get_entity())
+        .and_then(|e| Some((*e.get::<Position>()?, *e.get::<Rotation>()?)))
+    else {
+        return Ok(());
+    };
+
+    let entity = world_manager.spawn_npc(position, rotation, components)?;
+    sync_entity_spawn(&*world_manager, entity);
+
+    let npc = world_manager.get_ecs().get_entity(entity).unwrap();
+    sender.send_console_message(format!(
+        "Npc #{} spawned at {}",
+        npc.get::<NpcComponent>().unwrap().get_id(),
+        position.to_network()
+    ));
+    Ok(())
+}
+
+fn command_npc_remove(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let id = args.get_arg::<u64, _>("id")?;
+    let slug = get_sender_world_slug(sender, args)?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    let Some(entity) = world_manager.get_npc(id) else {
+        sender.send_console_message(format!("Npc #{} is not found in the loaded chunks", id));
+        return Ok(());
+    };
+    sync_entity_despawn(&*world_manager, entity);
+    world_manager.remove_npc(entity)?;
+    sender.send_console_message(format!("Npc #{} removed", id));
+    Ok(())
+}
+
+/// Only the npcs of the loaded chunks are listed
+fn command_npc_list(world: &mut World, sender: &Box<dyn ConsoleSenderType>, args: &CommandMatch) -> Result<(), String> {
+    let slug = get_sender_world_slug(sender, args)?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    let npcs = world_manager.get_ecs_mut().get_entities_with::<NpcComponent>();
+    if npcs.is_empty() {
+        sender.send_console_message(format!("World \"{}\" has no loaded npcs", slug));
+        return Ok(());
+    }
+    sender.send_console_message(format!("Npcs of \"{}\":", slug));
+    for entity in npcs {
+        let npc = world_manager.get_ecs().get_entity(entity).unwrap();
+        let position = npc.get::<Position>().unwrap();
+        sender.send_console_message(format!(
+            " - #{} at {} chunk {}",
+            npc.get::<NpcComponent>().unwrap().get_id(),
+            position.to_network(),
+            position.get_chunk_position()
+        ));
+    }
+    Ok(())
+}
+
+/// World slug, login and position of the player who sent the command
+fn get_sender_player(world: &World, sender: &Box<dyn ConsoleSenderType>) -> Option<(String, String, Position)> {
+    let Some(client) = sender.as_any().downcast_ref::<ClientNetwork>() else {
+        sender.send_console_message("This command is allowed to be used only for players".to_string());
+        return None;
+    };
+    let world_entity = client.get_world_entity()?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let world_manager = worlds_manager.get_world_manager(world_entity.get_world_slug())?;
+    let position = *world_manager
+        .get_ecs()
+        .get_entity(world_entity.get_entity())?
+        .get::<Position>()?;
+    let login = client.get_client_info()?.get_login().clone();
+    Some((world_entity.get_world_slug().clone(), login, position))
+}
+
+/// Only the npcs of the loaded chunks can be changed
+fn change_npc_behaviour<F: FnOnce(&mut NpcBehaviourComponent)>(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    slug: &String,
+    id: u64,
+    f: F,
+) -> Result<(), String> {
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+    let Some(entity) = world_manager.get_npc(id) else {
+        sender.send_console_message(format!("Npc #{} is not found in the loaded chunks", id));
+        return Ok(());
+    };
+    let mut npc_entity = world_manager.get_ecs_mut().entity_mut(entity);
+    let mut behaviour = npc_entity.get_mut::<NpcBehaviourComponent>().unwrap();
+    f(&mut *behaviour);
+    sender.send_console_message(format!("Npc #{} behaviour: {:?}", id, behaviour.get_behaviour()));
+    Ok(())
+}
+
+/// Npc walks around its home
+fn command_npc_wander(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let slug = get_sender_world_slug(sender, args)?;
+    let id = args.get_arg::<u64, _>("id")?;
+    let radius = args.get_arg::<i64, _>("radius")?;
+    if radius < 1 {
+        return Err("&cRadius must be positive".to_string());
+    }
+    change_npc_behaviour(world, sender, &slug, id, |b| {
+        b.set_behaviour(NpcBehaviour::Wander { radius })
+    })
+}
+
+/// Npc follows the player who sent the command
+fn command_npc_follow(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let id = args.get_arg::<u64, _>("id")?;
+    let Some((slug, login, _position)) = get_sender_player(world, sender) else {
+        return Ok(());
+    };
+    change_npc_behaviour(world, sender, &slug, id, |b| {
+        b.set_behaviour(NpcBehaviour::Follow {
+            login,
+            distance: NPC_FOLLOW_DISTANCE,
+        })
+    })
+}
+
+/// Position of the player is added to the patrol of the npc
+fn command_npc_waypoint(
+    world: &mut World,
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let id = args.get_arg::<u64, _>("id")?;
+    let Some((slug, _login, position)) = get_sender_player(world, sender) else {
+        return Ok(());
+    };
+    let waypoint = BlockPosition::from_position(&position.to_network());
+    change_npc_behaviour(world, sender, &slug, id, |b| match b.get_behaviour_mut() {
+        NpcBehaviour::Patrol { waypoints, .. } => waypoints.push(waypoint),
+        _ => b.set_behaviour(NpcBehaviour::Patrol {
+            waypoints: vec![waypoint],
+            next: 0,
+        }),
+    })
+}
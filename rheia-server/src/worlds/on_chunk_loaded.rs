@@ -4,6 +4,7 @@ use bevy_ecs::system::Res;
 use crate::network::{
     client_network::{ClientNetwork, WorldEntity},
     server::NetworkContainer,
+    sync_entities::sync_entity_spawn,
     sync_players::PlayerSpawnEvent,
 };
 
@@ -17,9 +18,16 @@ pub fn on_chunk_loaded(
     mut player_spawn_events: EventWriter<PlayerSpawnEvent>,
 ) {
     for (_key, world) in worlds_manager.get_worlds().iter() {
-        let w = world.read();
+        let mut w = world.write();
         let loaded_chunks = w.get_chunks_map().drain_loaded_chunks().collect::<Vec<_>>();
         for chunk_position in loaded_chunks {
+            w.get_chunks_map().spread_loaded_light(&chunk_position);
+            w.get_chunks_map().invalidate_paths(&chunk_position);
+
+            for npc in w.spawn_stored_npcs(&chunk_position) {
+                sync_entity_spawn(&*w, npc);
+            }
+
             let world_slug = w.get_slug().clone();
             let ecs = w.get_ecs();
 
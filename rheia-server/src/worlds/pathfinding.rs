@@ -0,0 +1,426 @@
+use ahash::AHashMap;
+use common::{
+    CHUNK_SIZE,
+    chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo, chunk_position::ChunkPosition},
+};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::client_resources::server_settings::ServerSettings;
+
+/// How many blocks the entity can climb without the jump
+pub const PATH_STEP_HEIGHT: i64 = 1;
+
+/// How deep the entity can drop from the edge
+pub const PATH_MAX_DROP: i64 = 3;
+
+/// How many blocks of the gap the entity can jump over
+pub const PATH_JUMP_GAP: i64 = 2;
+
+/// Walking entities occupy two blocks like the players
+pub const PATH_ENTITY_HEIGHT: i64 = 2;
+
+/// The search stops after this number of visited positions
+pub const PATH_MAX_NODES: usize = 4096;
+
+/// Cache is cleared after this number of searches
+pub const MAX_CACHED_PATHS: usize = 256;
+
+const MOVE_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const CLIMB_COST: u32 = 5;
+const DROP_COST: u32 = 2;
+const JUMP_COST: u32 = 10;
+
+const DIRECTIONS: [(i64, i64); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// What the walking entity finds in the block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathCell {
+    // Paths never lead through the chunks which are not loaded
+    Unloaded,
+    Passable,
+    // Entities avoid swimming, but can jump over the water
+    Water,
+    Solid,
+}
+
+pub fn get_block_path_cell(server_settings: &ServerSettings, block_info: &Option<BlockDataInfo>) -> PathCell {
+    let Some(block_info) = block_info else {
+        return PathCell::Passable;
+    };
+    if server_settings.is_fluid(&block_info.get_id()) {
+        return PathCell::Water;
+    }
+    match server_settings.get_block_type(&block_info.get_id()) {
+        Some(block_type) if !block_type.has_collider() => PathCell::Passable,
+        _ => PathCell::Solid,
+    }
+}
+
+/// Result of the search together with the area of the blocks it has read
+///
+/// Nothing outside of the area can change the result
+#[derive(Debug, Clone)]
+pub struct PathSearch {
+    // Feet positions without the start one; None if the goal is unreachable
+    path: Option<Vec<BlockPosition>>,
+    area_min: BlockPosition,
+    area_max: BlockPosition,
+}
+
+impl PathSearch {
+    pub fn get_path(&self) -> &Option<Vec<BlockPosition>> {
+        &self.path
+    }
+
+    pub fn is_inside_area(&self, position: &BlockPosition) -> bool {
+        (self.area_min.get_x()..=self.area_max.get_x()).contains(&position.get_x())
+            && (self.area_min.get_y()..=self.area_max.get_y()).contains(&position.get_y())
+            && (self.area_min.get_z()..=self.area_max.get_z()).contains(&position.get_z())
+    }
+
+    pub fn is_touching_chunk(&self, chunk_position: &ChunkPosition) -> bool {
+        let min_x = chunk_position.x * CHUNK_SIZE as i64;
+        let min_z = chunk_position.z * CHUNK_SIZE as i64;
+        let max_x = min_x + CHUNK_SIZE as i64 - 1;
+        let max_z = min_z + CHUNK_SIZE as i64 - 1;
+        self.area_min.get_x() <= max_x
+            && self.area_max.get_x() >= min_x
+            && self.area_min.get_z() <= max_z
+            && self.area_max.get_z() >= min_z
+    }
+}
+
+struct PathSearcher<F: Fn(&BlockPosition) -> PathCell> {
+    get_cell: F,
+    area: Option<(BlockPosition, BlockPosition)>,
+}
+
+impl<F: Fn(&BlockPosition) -> PathCell> PathSearcher<F> {
+    fn get(&mut self, position: &BlockPosition) -> PathCell {
+        let (min, max) = self.area.get_or_insert((*position, *position));
+        *min = BlockPosition::new(
+            min.get_x().min(position.get_x()),
+            min.get_y().min(position.get_y()),
+            min.get_z().min(position.get_z()),
+        );
+        *max = BlockPosition::new(
+            max.get_x().max(position.get_x()),
+            max.get_y().max(position.get_y()),
+            max.get_z().max(position.get_z()),
+        );
+        (self.get_cell)(position)
+    }
+
+    fn is_clear(&mut self, position: &BlockPosition) -> bool {
+        self.get(position) == PathCell::Passable
+    }
+
+    /// If the whole body of the entity fits at the position
+    fn has_room(&mut self, position: &BlockPosition) -> bool {
+        for h in 0..PATH_ENTITY_HEIGHT {
+            if !self.is_clear(&position.offset(0, h, 0)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_standable(&mut self, position: &BlockPosition) -> bool {
+        self.get(&position.offset(0, -1, 0)) == PathCell::Solid && self.has_room(position)
+    }
+
+    fn get_neighbours(&mut self, position: &BlockPosition) -> Vec<(BlockPosition, u32)> {
+        let mut neighbours: Vec<(BlockPosition, u32)> = Default::default();
+        for (dx, dz) in DIRECTIONS {
+            let next = position.offset(dx, 0, dz);
+
+            // Diagonal moves can't cut the corners
+            if dx != 0 && dz != 0 {
+                if self.is_standable(&next)
+                    && self.has_room(&position.offset(dx, 0, 0))
+                    && self.has_room(&position.offset(0, 0, dz))
+                {
+                    neighbours.push((next, DIAGONAL_COST));
+                }
+                continue;
+            }
+
+            if self.is_standable(&next) {
+                neighbours.push((next, MOVE_COST));
+                continue;
+            }
+
+            // Step up onto the blocks; the entity needs the room above its head
+            if !self.has_room(&next) {
+                for h in 1..=PATH_STEP_HEIGHT {
+                    if !self.is_clear(&position.offset(0, PATH_ENTITY_HEIGHT + h - 1, 0)) {
+                        break;
+                    }
+                    let up = next.offset(0, h, 0);
+                    if self.is_standable(&up) {
+                        neighbours.push((up, MOVE_COST + CLIMB_COST * h as u32));
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            // Drop down from the edge
+            let mut landed = false;
+            for h in 1..=PATH_MAX_DROP {
+                let down = next.offset(0, -h, 0);
+                if !self.is_clear(&down) {
+                    break;
+                }
+                if self.is_standable(&down) {
+                    neighbours.push((down, MOVE_COST + DROP_COST * h as u32));
+                    landed = true;
+                    break;
+                }
+            }
+            if landed {
+                continue;
+            }
+
+            // Jump over the gap at the same height
+            if !self.is_clear(&position.offset(0, PATH_ENTITY_HEIGHT, 0)) {
+                continue;
+            }
+            for gap in 1..=PATH_JUMP_GAP {
+                let over = position.offset(dx * gap, 0, dz * gap);
+                if !self.has_room(&over) || !self.is_clear(&over.offset(0, PATH_ENTITY_HEIGHT, 0)) {
+                    break;
+                }
+                let landing = position.offset(dx * (gap + 1), 0, dz * (gap + 1));
+                if self.is_standable(&landing) {
+                    neighbours.push((landing, MOVE_COST * (gap + 1) as u32 + JUMP_COST));
+                    break;
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+/// Solid block below and the room for the entity
+pub fn is_standable<F: Fn(&BlockPosition) -> PathCell>(position: &BlockPosition, get_cell: F) -> bool {
+    PathSearcher { get_cell, area: None }.is_standable(position)
+}
+
+fn get_heuristic(from: &BlockPosition, to: &BlockPosition) -> u32 {
+    let dx = (from.get_x() - to.get_x()).unsigned_abs() as u32;
+    let dz = (from.get_z() - to.get_z()).unsigned_abs() as u32;
+    DIAGONAL_COST * dx.min(dz) + MOVE_COST * (dx.max(dz) - dx.min(dz))
+}
+
+/// A* search of the walking path between the feet positions
+///
+/// Both positions must be standable: solid block below and the room for the entity
+pub fn find_path<F: Fn(&BlockPosition) -> PathCell>(
+    start: &BlockPosition,
+    goal: &BlockPosition,
+    get_cell: F,
+) -> PathSearch {
+    let mut searcher = PathSearcher { get_cell, area: None };
+    let path = match searcher.is_standable(start) && searcher.is_standable(goal) {
+        true => search(&mut searcher, start, goal),
+        false => None,
+    };
+    let (area_min, area_max) = searcher.area.unwrap();
+    PathSearch {
+        path,
+        area_min,
+        area_max,
+    }
+}
+
+fn search<F: Fn(&BlockPosition) -> PathCell>(
+    searcher: &mut PathSearcher<F>,
+    start: &BlockPosition,
+    goal: &BlockPosition,
+) -> Option<Vec<BlockPosition>> {
+    let mut came_from: AHashMap<BlockPosition, BlockPosition> = Default::default();
+    let mut costs: AHashMap<BlockPosition, u32> = Default::default();
+    let mut open: BinaryHeap<(Reverse<u32>, i64, i64, i64)> = Default::default();
+
+    costs.insert(*start, 0);
+    open.push((
+        Reverse(get_heuristic(start, goal)),
+        start.get_x(),
+        start.get_y(),
+        start.get_z(),
+    ));
+
+    let mut visited = 0;
+    while let Some((_, x, y, z)) = open.pop() {
+        let position = BlockPosition::new(x, y, z);
+        if position == *goal {
+            let mut path: Vec<BlockPosition> = Default::default();
+            let mut current = position;
+            while current != *start {
+                path.push(current);
+                current = came_from[&current];
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        visited += 1;
+        if visited > PATH_MAX_NODES {
+            return None;
+        }
+
+        let cost = costs[&position];
+        for (next, move_cost) in searcher.get_neighbours(&position) {
+            let next_cost = cost + move_cost;
+            if costs.get(&next).is_some_and(|c| *c <= next_cost) {
+                continue;
+            }
+            costs.insert(next, next_cost);
+            came_from.insert(next, position);
+            let priority = next_cost + get_heuristic(&next, goal);
+            open.push((Reverse(priority), next.get_x(), next.get_y(), next.get_z()));
+        }
+    }
+    None
+}
+
+/// Searches are kept until the blocks of their area are changed
+#[derive(Default)]
+pub struct PathCache {
+    searches: AHashMap<(BlockPosition, BlockPosition), PathSearch>,
+}
+
+impl PathCache {
+    pub fn get(&self, start: &BlockPosition, goal: &BlockPosition) -> Option<&PathSearch> {
+        self.searches.get(&(*start, *goal))
+    }
+
+    pub fn insert(&mut self, start: BlockPosition, goal: BlockPosition, search: PathSearch) {
+        if self.searches.len() >= MAX_CACHED_PATHS {
+            self.searches.clear();
+        }
+        self.searches.insert((start, goal), search);
+    }
+
+    pub fn remove(&mut self, start: &BlockPosition, goal: &BlockPosition) {
+        self.searches.remove(&(*start, *goal));
+    }
+
+    /// Changed block can open or close the paths around it
+    pub fn invalidate_block(&mut self, position: &BlockPosition) {
+        self.searches.retain(|_, search| !search.is_inside_area(position));
+    }
+
+    /// Loaded chunk can open the paths which were stopped at its border
+    pub fn invalidate_chunk(&mut self, chunk_position: &ChunkPosition) {
+        self.searches
+            .retain(|_, search| !search.is_touching_chunk(chunk_position));
+    }
+
+    pub fn count(&self) -> usize {
+        self.searches.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathCache, PathCell, find_path};
+    use ahash::AHashMap;
+    use common::chunks::{block_position::BlockPosition, chunk_position::ChunkPosition};
+
+    /// Flat ground at y = 0, everything outside of the area is not loaded
+    fn get_cell(blocks: &AHashMap<BlockPosition, PathCell>, position: &BlockPosition) -> PathCell {
+        if position.get_x().abs() > 10 || position.get_z().abs() > 10 || position.get_y() < 0 {
+            return PathCell::Unloaded;
+        }
+        if let Some(cell) = blocks.get(position) {
+            return *cell;
+        }
+        match position.get_y() {
+            0 => PathCell::Solid,
+            _ => PathCell::Passable,
+        }
+    }
+
+    #[test]
+    fn test_flat_path() {
+        let blocks: AHashMap<BlockPosition, PathCell> = Default::default();
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(5, 1, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        let path = search.get_path().clone().unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(*path.last().unwrap(), BlockPosition::new(5, 1, 0));
+
+        // Goal outside of the loaded area
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(20, 1, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        assert!(search.get_path().is_none());
+    }
+
+    #[test]
+    fn test_step_height() {
+        let mut blocks: AHashMap<BlockPosition, PathCell> = Default::default();
+        blocks.insert(BlockPosition::new(3, 1, 0), PathCell::Solid);
+        blocks.insert(BlockPosition::new(6, 1, 0), PathCell::Solid);
+        blocks.insert(BlockPosition::new(6, 2, 0), PathCell::Solid);
+
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(3, 2, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        assert!(search.get_path().is_some());
+
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(6, 3, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        assert!(search.get_path().is_none());
+    }
+
+    #[test]
+    fn test_water_and_gaps() {
+        // The river across the whole area
+        let mut blocks: AHashMap<BlockPosition, PathCell> = Default::default();
+        for z in -10..=10 {
+            blocks.insert(BlockPosition::new(3, 0, z), PathCell::Water);
+            blocks.insert(BlockPosition::new(3, 1, z), PathCell::Water);
+        }
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(6, 1, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        assert!(search.get_path().is_none());
+
+        // Water below the ground level is jumped over
+        for z in -10..=10 {
+            blocks.insert(BlockPosition::new(3, 1, z), PathCell::Passable);
+        }
+        let search = find_path(&BlockPosition::new(0, 1, 0), &BlockPosition::new(6, 1, 0), |p| {
+            get_cell(&blocks, p)
+        });
+        let path = search.get_path().clone().unwrap();
+        assert!(!path.iter().any(|p| p.get_x() == 3));
+    }
+
+    #[test]
+    fn test_cache_invalidation() {
+        let blocks: AHashMap<BlockPosition, PathCell> = Default::default();
+        let start = BlockPosition::new(0, 1, 0);
+        let goal = BlockPosition::new(3, 1, 0);
+        let mut cache = PathCache::default();
+        cache.insert(start, goal, find_path(&start, &goal, |p| get_cell(&blocks, p)));
+        assert!(cache.get(&start, &goal).is_some());
+
+        cache.invalidate_block(&BlockPosition::new(100, 1, 0));
+        cache.invalidate_chunk(&ChunkPosition::new(5, 5));
+        assert_eq!(cache.count(), 1);
+
+        cache.invalidate_block(&BlockPosition::new(2, 1, 0));
+        assert_eq!(cache.count(), 0);
+
+        cache.insert(start, goal, find_path(&start, &goal, |p| get_cell(&blocks, p)));
+        cache.invalidate_chunk(&ChunkPosition::new(0, 0));
+        assert!(cache.get(&start, &goal).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+use crate::console::console_sender::ConsoleSenderType;
+use crate::network::client_network::ClientNetwork;
+use bevy_ecs::world::World;
+use common::chunks::block_position::BlockPosition;
+use common::commands::command::{Arg, Command, CommandMatch};
+use common::regions::{Region, RegionFlag, RegionShape};
+use std::str::FromStr;
+
+use super::world_manager::WorldManager;
+use super::worlds_manager::WorldsManager;
+
+pub(crate) fn command_parser_region() -> Command {
+    let flags: Vec<String> = RegionFlag::get_all().iter().map(|f| f.to_string()).collect();
+    Command::new("region".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("define".to_owned())
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("cuboid".to_owned())
+                        .arg(Arg::new("slug".to_owned()).required(true))
+                        .arg(Arg::new("name".to_owned()).required(true))
+                        .arg(Arg::new("x1".to_owned()).required(true))
+                        .arg(Arg::new("y1".to_owned()).required(true))
+                        .arg(Arg::new("z1".to_owned()).required(true))
+                        .arg(Arg::new("x2".to_owned()).required(true))
+                        .arg(Arg::new("y2".to_owned()).required(true))
+                        .arg(Arg::new("z2".to_owned()).required(true)),
+                )
+                .subcommand(
+                    Command::new("polygon".to_owned())
+                        .arg(Arg::new("slug".to_owned()).required(true))
+                        .arg(Arg::new("name".to_owned()).required(true))
+                        .arg(Arg::new("min_y".to_owned()).required(true))
+                        .arg(Arg::new("max_y".to_owned()).required(true))
+                        .arg(Arg::new("points".to_owned()).required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("remove".to_owned())
+                .arg(Arg::new("slug".to_owned()).required(true))
+                .arg(Arg::new("name".to_owned()).required(true)),
+        )
+        .subcommand(
+            Command::new("addmember".to_owned())
+                .arg(Arg::new("slug".to_owned()).required(true))
+                .arg(Arg::new("name".to_owned()).required(true))
+                .arg(Arg::new("login".to_owned()).required(true)),
+        )
+        .subcommand(
+            Command::new("flag".to_owned())
+                .arg(Arg::new("slug".to_owned()).required(true))
+                .arg(Arg::new("name".to_owned()).required(true))
+                .arg(Arg::new("flag".to_owned()).required(true).choices(flags))
+                .arg(
+                    Arg::new("value".to_owned())
+                        .required(true)
+                        .choices(vec!["allow", "deny"]),
+                ),
+        )
+        .subcommand(
+            Command::new("info".to_owned())
+                .arg(Arg::new("slug".to_owned()).required(true))
+                .arg(Arg::new("name".to_owned())),
+        )
+}
+
+/// Points of the polygon are passed as "x,z;x,z;x,z"
+fn parse_polygon_points(points: &String) -> Result<Vec<(i64, i64)>, String> {
+    let mut result: Vec<(i64, i64)> = Default::default();
+    for point in points.split(';').filter(|p| p.len() > 0) {
+        let Some((x, z)) = point.split_once(',') else {
+            return Err(format!("&cpoint &4\"{}\"&c must be in format x,z", point));
+        };
+        match (i64::from_str(x.trim()), i64::from_str(z.trim())) {
+            (Ok(x), Ok(z)) => result.push((x, z)),
+            _ => return Err(format!("&cpoint &4\"{}\"&c must contain integers", point)),
+        }
+    }
+    Ok(result)
+}
+
+fn format_shape(shape: &RegionShape) -> String {
+    match shape {
+        RegionShape::Cuboid { min, max } => format!("cuboid from {:?} to {:?}", min, max),
+        RegionShape::Polygon { points, min_y, max_y } => {
+            let points: Vec<String> = points.iter().map(|(x, z)| format!("{},{}", x, z)).collect();
+            format!("polygon {} from y:{} to y:{}", points.join(";"), min_y, max_y)
+        }
+    }
+}
+
+pub(crate) fn command_region(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let worlds_manager = world.resource::<WorldsManager>();
+
+    // Console is allowed to manage all regions
+    let login = match sender.as_any().downcast_ref::<ClientNetwork>() {
+        Some(client) => match client.get_client_info() {
+            Some(info) => Some(info.get_login().clone()),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
+    let Some(region_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    let slug = match region_subcommand.subcommand() {
+        Some(define_subcommand) => define_subcommand.get_arg::<String, _>("slug")?,
+        None => region_subcommand.get_arg::<String, _>("slug")?,
+    };
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    match region_subcommand.get_name().as_str() {
+        "define" => {
+            if let Some(define_subcommand) = region_subcommand.subcommand() {
+                command_region_define(&mut *world_manager, &sender, login, define_subcommand)?;
+            }
+        }
+        "info" => {
+            let regions = world_manager.get_regions();
+            match region_subcommand.get_arg::<String, _>("name").ok() {
+                Some(name) => {
+                    let Some(region) = regions.get_region(&name) else {
+                        sender.send_console_message(format!("Region \"{}\" not found in \"{}\"", name, slug));
+                        return Ok(());
+                    };
+                    sender.send_console_message(format!("Region \"{}\":", region.get_name()));
+                    sender.send_console_message(format!(" - shape: {}", format_shape(region.get_shape())));
+                    sender.send_console_message(format!(" - owners: {}", region.get_owners().join(", ")));
+                    sender.send_console_message(format!(" - members: {}", region.get_members().join(", ")));
+                    for flag in RegionFlag::get_all() {
+                        let value = match region.get_flags().get(&flag).cloned().unwrap_or(false) {
+                            true => "allow",
+                            false => "deny",
+                        };
+                        sender.send_console_message(format!(" - {}: {}", flag, value));
+                    }
+                }
+                None => {
+                    if regions.count() == 0 {
+                        sender.send_console_message(format!("World \"{}\" has no regions", slug));
+                        return Ok(());
+                    }
+                    sender.send_console_message(format!("Regions of \"{}\":", slug));
+                    for region in regions.get_regions().values() {
+                        sender.send_console_message(format!(
+                            " - {} ({})",
+                            region.get_name(),
+                            format_shape(region.get_shape())
+                        ));
+                    }
+                }
+            }
+        }
+        _ => {
+            command_region_manage(&mut *world_manager, &sender, login, region_subcommand)?;
+        }
+    }
+    Ok(())
+}
+
+fn command_region_define(
+    world_manager: &mut WorldManager,
+    sender: &Box<dyn ConsoleSenderType>,
+    login: Option<String>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let name = args.get_arg::<String, _>("name")?;
+    let shape = match args.get_name().as_str() {
+        "cuboid" => RegionShape::cuboid(
+            &BlockPosition::new(
+                args.get_arg::<i64, _>("x1")?,
+                args.get_arg::<i64, _>("y1")?,
+                args.get_arg::<i64, _>("z1")?,
+            ),
+            &BlockPosition::new(
+                args.get_arg::<i64, _>("x2")?,
+                args.get_arg::<i64, _>("y2")?,
+                args.get_arg::<i64, _>("z2")?,
+            ),
+        ),
+        "polygon" => {
+            let points = parse_polygon_points(&args.get_arg::<String, _>("points")?)?;
+            RegionShape::polygon(
+                points,
+                args.get_arg::<i64, _>("min_y")?,
+                args.get_arg::<i64, _>("max_y")?,
+            )?
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+            return Ok(());
+        }
+    };
+
+    let mut region = Region::create(name.clone(), shape);
+    if let Some(login) = login {
+        region.add_owner(login);
+    }
+    match world_manager.get_regions_mut().define(region) {
+        Ok(true) => sender.send_console_message(format!("Region \"{}\" was successfully defined", name)),
+        Ok(false) => sender.send_console_message(format!("Region \"{}\" already exists", name)),
+        Err(e) => sender.send_console_message(format!("Region \"{}\" define error: {}", name, e)),
+    }
+    Ok(())
+}
+
+/// Changes of the existing region are allowed only for its owners
+fn command_region_manage(
+    world_manager: &mut WorldManager,
+    sender: &Box<dyn ConsoleSenderType>,
+    login: Option<String>,
+    args: &CommandMatch,
+) -> Result<(), String> {
+    let name = args.get_arg::<String, _>("name")?;
+    let regions = world_manager.get_regions_mut();
+    let Some(region) = regions.get_region(&name) else {
+        sender.send_console_message(format!("Region \"{}\" not found", name));
+        return Ok(());
+    };
+    if let Some(login) = login {
+        if !region.is_owner(&login) {
+            sender.send_console_message(format!("&cYou are not an owner of region &4\"{}\"", name));
+            return Ok(());
+        }
+    }
+
+    match args.get_name().as_str() {
+        "remove" => match regions.remove(&name) {
+            Ok(_) => sender.send_console_message(format!("Region \"{}\" was removed", name)),
+            Err(e) => sender.send_console_message(format!("Region \"{}\" remove error: {}", name, e)),
+        },
+        "addmember" => {
+            let member = args.get_arg::<String, _>("login")?;
+            if region.is_member(&member) {
+                sender.send_console_message(format!("Player \"{}\" is already a member of \"{}\"", member, name));
+                return Ok(());
+            }
+            match regions.update(&name, |r| {
+                r.add_member(member.clone());
+            }) {
+                Ok(_) => sender.send_console_message(format!("Player \"{}\" was added to \"{}\"", member, name)),
+                Err(e) => sender.send_console_message(format!("Region \"{}\" save error: {}", name, e)),
+            }
+        }
+        "flag" => {
+            let flag = match RegionFlag::from_str(&args.get_arg::<String, _>("flag")?) {
+                Ok(f) => f,
+                Err(_) => return Err("&cunknown region flag".to_string()),
+            };
+            let value = args.get_arg::<String, _>("value")? == "allow";
+            match regions.update(&name, |r| r.set_flag(flag, value)) {
+                Ok(_) => sender.send_console_message(format!("Flag {} of \"{}\" was set to {}", flag, name, value)),
+                Err(e) => sender.send_console_message(format!("Region \"{}\" save error: {}", name, e)),
+            }
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+        }
+    }
+    Ok(())
+}
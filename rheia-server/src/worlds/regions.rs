@@ -0,0 +1,86 @@
+use common::{
+    chunks::block_position::BlockPosition,
+    regions::{Region, RegionFlag},
+    worlds_storage::taits::IWorldStorage,
+};
+use std::collections::BTreeMap;
+
+use super::chunks::chunks_map::StorageLock;
+
+/// Protected regions of the world; every change is saved to the world storage
+pub struct WorldRegions {
+    regions: BTreeMap<String, Region>,
+    storage: StorageLock,
+}
+
+impl WorldRegions {
+    pub fn load(storage: StorageLock) -> Result<Self, String> {
+        let mut regions: BTreeMap<String, Region> = Default::default();
+        for region in storage.lock().load_regions()? {
+            regions.insert(region.get_name().clone(), region);
+        }
+        Ok(Self { regions, storage })
+    }
+
+    pub fn count(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn get_regions(&self) -> &BTreeMap<String, Region> {
+        &self.regions
+    }
+
+    pub fn get_region(&self, name: &String) -> Option<&Region> {
+        self.regions.get(name)
+    }
+
+    /// Returns false if the region with that name already exists
+    pub fn define(&mut self, region: Region) -> Result<bool, String> {
+        if self.regions.contains_key(region.get_name()) {
+            return Ok(false);
+        }
+        self.storage.lock().save_region(&region)?;
+        self.regions.insert(region.get_name().clone(), region);
+        Ok(true)
+    }
+
+    /// Returns false if the region doesn't exist
+    pub fn remove(&mut self, name: &String) -> Result<bool, String> {
+        if !self.regions.contains_key(name) {
+            return Ok(false);
+        }
+        self.storage.lock().delete_region(name)?;
+        self.regions.remove(name);
+        Ok(true)
+    }
+
+    /// Changes the region and saves it
+    pub fn update<F: FnOnce(&mut Region)>(&mut self, name: &String, f: F) -> Result<bool, String> {
+        let Some(region) = self.regions.get_mut(name) else {
+            return Ok(false);
+        };
+        let mut changed = region.clone();
+        f(&mut changed);
+        self.storage.lock().save_region(&changed)?;
+        *region = changed;
+        Ok(true)
+    }
+
+    pub fn get_regions_at(&self, position: &BlockPosition) -> impl Iterator<Item = &Region> {
+        self.regions.values().filter(move |r| r.contains(position))
+    }
+
+    /// Every region which contains the position must allow the action
+    pub fn check_action(&self, position: &BlockPosition, login: &String, flag: &RegionFlag) -> Result<(), String> {
+        for region in self.get_regions_at(position) {
+            if !region.is_allowed(flag, login) {
+                return Err(format!(
+                    "&cYou can't {} inside of region &4\"{}\"",
+                    flag,
+                    region.get_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
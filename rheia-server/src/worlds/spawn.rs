@@ -0,0 +1,158 @@
+use ahash::AHashMap;
+use common::{
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::{BlockDataInfo, ChunkData},
+        chunk_position::ChunkPosition,
+    },
+    utils::spiral_iterator::SpiralIterator,
+};
+
+use crate::client_resources::server_settings::ServerSettings;
+
+use super::chunks::chunks_map::ChunkMap;
+
+/// How far from the requested column the safe spawn is searched, in blocks
+pub const SAFE_SPAWN_SEARCH_RADIUS: i64 = 32;
+
+fn get_block(chunk_data: &ChunkData, position: &BlockPosition) -> Option<BlockDataInfo> {
+    if position.get_y() < 0 {
+        return None;
+    }
+    let (section, block_position) = position.get_block_position();
+    chunk_data.get(section as usize)?.get(&block_position).cloned()
+}
+
+/// Fluids are never a safe ground
+fn is_safe_ground(server_settings: &ServerSettings, block_info: &BlockDataInfo) -> bool {
+    if server_settings.is_fluid(&block_info.get_id()) {
+        return false;
+    }
+    match server_settings.get_block_type(&block_info.get_id()) {
+        Some(block_type) => block_type.has_collider(),
+        None => false,
+    }
+}
+
+/// Highest ground block of the column with two air blocks above it
+///
+/// Returns the position of the player's feet
+pub fn find_column_spawn<F: Fn(&BlockDataInfo) -> bool>(
+    chunk_data: &ChunkData,
+    x: i64,
+    z: i64,
+    is_ground: F,
+) -> Option<BlockPosition> {
+    let height = CHUNK_SIZE as i64 * VERTICAL_SECTIONS as i64;
+    let mut air_above = 0;
+    for y in (0..height).rev() {
+        match get_block(chunk_data, &BlockPosition::new(x, y, z)) {
+            None => air_above += 1,
+            Some(block_info) => {
+                if air_above >= 2 && is_ground(&block_info) {
+                    return Some(BlockPosition::new(x, y + 1, z));
+                }
+                // The highest block is not a ground, so the column is unsafe
+                return None;
+            }
+        }
+    }
+    None
+}
+
+/// If the player can stand at the position
+pub fn is_safe_spawn<F: Fn(&BlockDataInfo) -> bool>(
+    chunk_data: &ChunkData,
+    position: &BlockPosition,
+    is_ground: F,
+) -> bool {
+    let ground = get_block(chunk_data, &position.offset(0, -1, 0));
+    ground.map_or(false, |b| is_ground(&b))
+        && get_block(chunk_data, position).is_none()
+        && get_block(chunk_data, &position.offset(0, 1, 0)).is_none()
+}
+
+/// Safe spawn at the column or the nearest one around it
+///
+/// Chunks are read even if they are not loaded, so the search works for any area
+pub fn find_safe_spawn(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    x: i64,
+    z: i64,
+) -> Result<Option<BlockPosition>, String> {
+    let mut chunks: AHashMap<ChunkPosition, ChunkData> = Default::default();
+    for (column_x, column_z) in SpiralIterator::new(x, z, SAFE_SPAWN_SEARCH_RADIUS) {
+        let chunk_position = BlockPosition::new(column_x, 0, column_z).get_chunk_position();
+        if !chunks.contains_key(&chunk_position) {
+            chunks.insert(chunk_position.clone(), chunks_map.read_chunk_data(&chunk_position)?);
+        }
+        let chunk_data = chunks.get(&chunk_position).unwrap();
+        let spawn = find_column_spawn(chunk_data, column_x, column_z, |b| is_safe_ground(server_settings, b));
+        if spawn.is_some() {
+            return Ok(spawn);
+        }
+    }
+    Ok(None)
+}
+
+/// Keeps the position if it's safe, otherwise searches the surface around it
+pub fn get_safe_position(
+    chunks_map: &ChunkMap,
+    server_settings: &ServerSettings,
+    position: &BlockPosition,
+) -> Result<Option<BlockPosition>, String> {
+    let chunk_data = chunks_map.read_chunk_data(&position.get_chunk_position())?;
+    if is_safe_spawn(&chunk_data, position, |b| is_safe_ground(server_settings, b)) {
+        return Ok(Some(position.clone()));
+    }
+    find_safe_spawn(chunks_map, server_settings, position.get_x(), position.get_z())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_column_spawn, is_safe_spawn};
+    use common::{
+        VERTICAL_SECTIONS,
+        chunks::{
+            block_position::BlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData},
+        },
+    };
+
+    const STONE: u16 = 1;
+    const WATER: u16 = 2;
+
+    fn set_block(chunk_data: &mut ChunkData, position: BlockPosition, id: u16) {
+        let (section, block_position) = position.get_block_position();
+        chunk_data.change_block(section, &block_position, Some(BlockDataInfo::create(id, None)));
+    }
+
+    #[test]
+    fn test_column_spawn() {
+        let mut chunk_data = ChunkData::default();
+        for _ in 0..VERTICAL_SECTIONS {
+            chunk_data.push_section(Default::default());
+        }
+        let is_ground = |b: &BlockDataInfo| b.get_id() == STONE;
+
+        // Ground with a cave under it
+        set_block(&mut chunk_data, BlockPosition::new(0, 10, 0), STONE);
+        set_block(&mut chunk_data, BlockPosition::new(0, 20, 0), STONE);
+        assert_eq!(
+            find_column_spawn(&chunk_data, 0, 0, is_ground),
+            Some(BlockPosition::new(0, 21, 0))
+        );
+        assert!(is_safe_spawn(&chunk_data, &BlockPosition::new(0, 11, 0), is_ground));
+        assert!(!is_safe_spawn(&chunk_data, &BlockPosition::new(0, 12, 0), is_ground));
+
+        // Water on top of the ground
+        set_block(&mut chunk_data, BlockPosition::new(1, 20, 0), STONE);
+        set_block(&mut chunk_data, BlockPosition::new(1, 21, 0), WATER);
+        assert_eq!(find_column_spawn(&chunk_data, 1, 0, is_ground), None);
+
+        // Empty column
+        assert_eq!(find_column_spawn(&chunk_data, 2, 0, is_ground), None);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::console::console_sender::ConsoleSenderType;
+use crate::network::client_network::ClientNetwork;
+use bevy_ecs::world::World;
+use common::commands::command::{Arg, Command, CommandMatch};
+use common::world_time::{MAX_TIME_SPEED, WorldTime};
+
+use super::worlds_manager::WorldsManager;
+
+pub(crate) fn command_parser_time() -> Command {
+    Command::new("time".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("set".to_owned())
+                .arg(Arg::new("value".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(
+            Command::new("speed".to_owned())
+                .arg(Arg::new("factor".to_owned()).required(true))
+                .arg(Arg::new("slug".to_owned())),
+        )
+}
+
+/// Players change the time of their world; the console must specify the world
+pub(crate) fn get_sender_world_slug(
+    sender: &Box<dyn ConsoleSenderType>,
+    args: &CommandMatch,
+) -> Result<String, String> {
+    if let Ok(slug) = args.get_arg::<String, _>("slug") {
+        return Ok(slug);
+    }
+    let world_entity = sender
+        .as_any()
+        .downcast_ref::<ClientNetwork>()
+        .and_then(|c| c.get_world_entity());
+    match world_entity {
+        Some(world_entity) => Ok(world_entity.get_world_slug().clone()),
+        None => Err("&cWorld slug is required".to_string()),
+    }
+}
+
+pub(crate) fn command_time(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(time_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    let slug = get_sender_world_slug(&sender, time_subcommand)?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    match time_subcommand.get_name().as_str() {
+        "set" => {
+            let time = WorldTime::parse_time(&time_subcommand.get_arg::<String, _>("value")?)?;
+            world_manager.set_time(time)?;
+            sender.send_console_message(format!("Time of world \"{}\" is set to {}", slug, time));
+        }
+        "speed" => {
+            let speed = time_subcommand.get_arg::<f32, _>("factor")?;
+            if !(0.0..=MAX_TIME_SPEED).contains(&speed) {
+                return Err(format!("&cSpeed must be from 0 to {}", MAX_TIME_SPEED));
+            }
+            world_manager.set_time_speed(speed)?;
+            match world_manager.get_time().is_frozen() {
+                true => sender.send_console_message(format!("Time of world \"{}\" is frozen", slug)),
+                false => sender.send_console_message(format!("Time speed of world \"{}\" is set to {}", slug, speed)),
+            }
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+        }
+    }
+    Ok(())
+}
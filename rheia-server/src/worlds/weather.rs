@@ -0,0 +1,92 @@
+use bevy::time::Time;
+use bevy_ecs::system::Res;
+use bracket_lib::random::RandomNumberGenerator;
+use common::world_weather::{Weather, WeatherType, WorldWeather};
+use std::time::Duration;
+
+use crate::network::{clients_container::ClientsContainer, sync_world_weather::sync_world_weather};
+
+use super::worlds_manager::WorldsManager;
+
+/// Duration of the random weather in seconds
+const RANDOM_WEATHER_MIN_DURATION: f32 = 120.0;
+const RANDOM_WEATHER_MAX_DURATION: f32 = 600.0;
+
+/// Chances of the random weather in percents
+const RANDOM_WEATHER_CHANCES: [(WeatherType, i32); 4] = [
+    (WeatherType::Clear, 60),
+    (WeatherType::Rain, 25),
+    (WeatherType::Storm, 10),
+    (WeatherType::Snow, 5),
+];
+
+pub fn get_random_weather(rng: &mut RandomNumberGenerator) -> Weather {
+    let mut roll = rng.range(0, 100);
+    let mut weather_type = WeatherType::Clear;
+    for (t, chance) in RANDOM_WEATHER_CHANCES {
+        if roll < chance {
+            weather_type = t;
+            break;
+        }
+        roll -= chance;
+    }
+    Weather::create(weather_type, rng.range(0.3, 1.0))
+}
+
+pub fn get_random_duration(rng: &mut RandomNumberGenerator) -> f32 {
+    rng.range(RANDOM_WEATHER_MIN_DURATION, RANDOM_WEATHER_MAX_DURATION)
+}
+
+/// Returns true if the weather is changed
+///
+/// Without the cycle the finished weather is replaced by the clear sky
+pub fn update_weather(world_weather: &mut WorldWeather, delta: Duration, rng: &mut RandomNumberGenerator) -> bool {
+    if world_weather.is_cycle() && world_weather.get_remaining().is_none() {
+        world_weather.set_weather(world_weather.get_weather().clone(), Some(get_random_duration(rng)));
+    }
+    if !world_weather.advance(delta) {
+        return false;
+    }
+    match world_weather.is_cycle() {
+        true => world_weather.set_weather(get_random_weather(rng), Some(get_random_duration(rng))),
+        false => world_weather.set_weather(Default::default(), None),
+    }
+    true
+}
+
+pub fn update_world_weather(worlds_manager: Res<WorldsManager>, clients: Res<ClientsContainer>, time: Res<Time>) {
+    let mut rng = RandomNumberGenerator::new();
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let mut world_manager = world.write();
+        if world_manager.update_weather(time.delta(), &mut rng) {
+            sync_world_weather(&*world_manager, &*clients);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::update_weather;
+    use bracket_lib::random::RandomNumberGenerator;
+    use common::world_weather::{Weather, WeatherType, WorldWeather};
+    use std::time::Duration;
+
+    #[test]
+    fn test_update_weather() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+
+        let storm = Weather::create(WeatherType::Storm, 1.0);
+        let mut weather = WorldWeather::create(storm.clone(), Some(10.0), false);
+        assert!(!update_weather(&mut weather, Duration::from_secs(5), &mut rng));
+        assert!(update_weather(&mut weather, Duration::from_secs(5), &mut rng));
+        assert_eq!(*weather.get_weather().get_weather_type(), WeatherType::Clear);
+        assert_eq!(weather.get_remaining(), None);
+
+        // The cycle schedules the next change
+        let mut weather = WorldWeather::create(storm, None, true);
+        assert!(!update_weather(&mut weather, Duration::from_secs(1), &mut rng));
+        assert!(weather.get_remaining().is_some());
+        assert!(update_weather(&mut weather, Duration::from_secs(600), &mut rng));
+        assert!(weather.get_remaining().is_some());
+    }
+}
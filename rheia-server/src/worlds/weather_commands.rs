@@ -0,0 +1,83 @@
+use crate::console::console_sender::ConsoleSenderType;
+use bevy_ecs::world::World;
+use bracket_lib::random::RandomNumberGenerator;
+use common::commands::command::{Arg, Command, CommandMatch};
+use common::world_weather::{Weather, WeatherType};
+use std::str::FromStr;
+
+use super::audit_log::parse_duration;
+use super::time_commands::get_sender_world_slug;
+use super::weather::get_random_duration;
+use super::worlds_manager::WorldsManager;
+
+const DEFAULT_WEATHER_INTENSITY: f32 = 0.7;
+
+pub(crate) fn command_parser_weather() -> Command {
+    let weather_types: Vec<String> = WeatherType::get_all().iter().map(|t| t.to_string()).collect();
+    Command::new("weather".to_owned())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("set".to_owned())
+                .arg(Arg::new("type".to_owned()).required(true).choices(weather_types))
+                .arg(Arg::new("duration".to_owned()))
+                .arg(Arg::new("intensity".to_owned()))
+                .arg(Arg::new("slug".to_owned())),
+        )
+        .subcommand(
+            Command::new("cycle".to_owned())
+                .arg(Arg::new("value".to_owned()).required(true).choices(vec!["on", "off"]))
+                .arg(Arg::new("slug".to_owned())),
+        )
+}
+
+/// Weather without the duration lasts until the next random change,
+/// or forever if the cycle of the world is disabled
+pub(crate) fn command_weather(
+    world: &mut World,
+    sender: Box<dyn ConsoleSenderType>,
+    args: CommandMatch,
+) -> Result<(), String> {
+    let Some(weather_subcommand) = args.subcommand() else {
+        return Ok(());
+    };
+    let slug = get_sender_world_slug(&sender, weather_subcommand)?;
+    let worlds_manager = world.resource::<WorldsManager>();
+    let Some(mut world_manager) = worlds_manager.get_world_manager_mut(&slug) else {
+        sender.send_console_message(format!("World \"{}\" not found", slug));
+        return Ok(());
+    };
+
+    match weather_subcommand.get_name().as_str() {
+        "set" => {
+            let weather_type = weather_subcommand.get_arg::<String, _>("type")?;
+            let Ok(weather_type) = WeatherType::from_str(&weather_type) else {
+                return Err(format!("&cWeather &4\"{}\"&c is unknown", weather_type));
+            };
+            let duration = match weather_subcommand.get_arg::<String, _>("duration") {
+                Ok(d) => Some(parse_duration(&d)? as f32),
+                Err(_) => match world_manager.get_weather().is_cycle() {
+                    true => Some(get_random_duration(&mut RandomNumberGenerator::new())),
+                    false => None,
+                },
+            };
+            let intensity = match weather_subcommand.get_arg::<f32, _>("intensity") {
+                Ok(i) => i,
+                Err(_) => DEFAULT_WEATHER_INTENSITY,
+            };
+            world_manager.set_weather(Weather::create(weather_type, intensity), duration)?;
+            sender.send_console_message(format!("Weather of world \"{}\" is set to {}", slug, weather_type));
+        }
+        "cycle" => {
+            let cycle = weather_subcommand.get_arg::<String, _>("value")? == "on";
+            world_manager.set_weather_cycle(cycle)?;
+            match cycle {
+                true => sender.send_console_message(format!("Weather of world \"{}\" changes randomly", slug)),
+                false => sender.send_console_message(format!("Random weather of world \"{}\" is disabled", slug)),
+            }
+        }
+        _ => {
+            sender.send_console_message("Error".to_string());
+        }
+    }
+    Ok(())
+}
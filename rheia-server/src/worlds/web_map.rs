@@ -0,0 +1,371 @@
+use ahash::{AHashMap, AHashSet};
+use common::{
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+    blocks::block_type::BlockColor,
+    chunks::{
+        block_position::ChunkBlockPosition,
+        chunk_data::{BlockIndexType, ChunkData},
+        chunk_position::ChunkPosition,
+    },
+    utils::calculate_hash,
+    worlds_storage::taits::IWorldStorage,
+};
+use image::{
+    Rgba, RgbaImage,
+    imageops::{self, FilterType},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, create_dir_all, remove_file},
+    path::PathBuf,
+};
+
+use super::chunks::chunks_map::StorageLock;
+
+/// Size of the tile side in pixels
+const TILE_SIZE: u32 = 256;
+
+/// Zoom level where one pixel is one block
+pub const MAX_ZOOM: u32 = 4;
+
+const CHUNKS_PER_TILE: i64 = TILE_SIZE as i64 / CHUNK_SIZE as i64;
+
+/// Color for the blocks without map_color
+const DEFAULT_MAP_COLOR: BlockColor = [128, 128, 128];
+
+const MANIFEST_FILE: &str = "manifest.yml";
+
+type TilePosition = (i64, i64);
+
+/// Stores hashes of the stored chunk data from the last export
+/// to render again only changed tiles
+#[derive(Serialize, Deserialize, Default)]
+struct WebMapManifest {
+    // All tiles are rendered again if the map colors were changed
+    #[serde(default)]
+    colors_hash: u64,
+    chunks: Vec<WebMapManifestChunk>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WebMapManifestChunk {
+    x: i64,
+    z: i64,
+    hash: u64,
+}
+
+/// Renders saved chunks of the world into
+/// slippy-map tile pyramid: `{zoom}/{x}/{z}.png`
+pub struct WebMapExporter {
+    world_slug: String,
+    storage: StorageLock,
+    colors: AHashMap<BlockIndexType, BlockColor>,
+    output_path: PathBuf,
+}
+
+impl WebMapExporter {
+    pub fn create(
+        world_slug: String,
+        storage: StorageLock,
+        colors: AHashMap<BlockIndexType, BlockColor>,
+        output_path: PathBuf,
+    ) -> Self {
+        Self {
+            world_slug,
+            storage,
+            colors,
+            output_path,
+        }
+    }
+
+    /// Returns count of the rendered tiles
+    pub fn export(&self) -> Result<usize, String> {
+        if let Err(e) = create_dir_all(&self.output_path) {
+            return Err(format!(
+                "Unable to create dir \"{}\": {}",
+                self.output_path.display(),
+                e
+            ));
+        }
+
+        let mut colors: Vec<(&BlockIndexType, &BlockColor)> = self.colors.iter().collect();
+        colors.sort();
+        let colors_hash = calculate_hash(&colors);
+
+        let old_manifest = self.read_manifest()?;
+        let colors_changed = old_manifest.colors_hash != colors_hash;
+        let mut old_hashes: AHashMap<ChunkPosition, u64> = Default::default();
+        for chunk in old_manifest.chunks.iter() {
+            old_hashes.insert(ChunkPosition::new(chunk.x, chunk.z), chunk.hash);
+        }
+
+        let saved_chunks = self.storage.lock().list_chunks()?;
+        let mut chunk_ids: AHashMap<ChunkPosition, i64> = Default::default();
+
+        let mut manifest = WebMapManifest {
+            colors_hash,
+            ..Default::default()
+        };
+        let mut dirty_tiles: AHashSet<TilePosition> = Default::default();
+
+        // Only the stored data is compared, chunks are rendered only for the changed tiles
+        for (chunk_position, chunk_id) in saved_chunks {
+            chunk_ids.insert(chunk_position, chunk_id);
+
+            let hash = match self.storage.lock().load_chunk_encoded(chunk_id) {
+                Ok(encoded) => calculate_hash(&encoded),
+                Err(e) => {
+                    log::warn!(target: "worlds", "Web map of &e\"{}\"&r chunk {} skipped: {}", self.world_slug, chunk_position, e);
+                    continue;
+                }
+            };
+
+            if old_hashes.remove(&chunk_position) != Some(hash) || colors_changed {
+                dirty_tiles.insert(get_tile_position(&chunk_position));
+            }
+            manifest.chunks.push(WebMapManifestChunk {
+                x: chunk_position.x,
+                z: chunk_position.z,
+                hash,
+            });
+        }
+
+        // Chunks that was removed since the last export
+        for chunk_position in old_hashes.keys() {
+            dirty_tiles.insert(get_tile_position(chunk_position));
+        }
+
+        let mut rendered = 0;
+        for tile in dirty_tiles.iter() {
+            let mut image = RgbaImage::new(TILE_SIZE, TILE_SIZE);
+            for x in 0..CHUNKS_PER_TILE {
+                for z in 0..CHUNKS_PER_TILE {
+                    let chunk_position = ChunkPosition::new(tile.0 * CHUNKS_PER_TILE + x, tile.1 * CHUNKS_PER_TILE + z);
+                    let Some(chunk_id) = chunk_ids.get(&chunk_position) else {
+                        continue;
+                    };
+                    let Some(pixels) = self.render_saved_chunk(&chunk_position, *chunk_id) else {
+                        continue;
+                    };
+                    let chunk_image = RgbaImage::from_raw(CHUNK_SIZE as u32, CHUNK_SIZE as u32, pixels).unwrap();
+                    imageops::replace(&mut image, &chunk_image, x * CHUNK_SIZE as i64, z * CHUNK_SIZE as i64);
+                }
+            }
+            self.save_tile(MAX_ZOOM, tile, &image)?;
+            rendered += 1;
+        }
+
+        // Build lower zoom levels from the tiles of the upper one
+        for zoom in (0..MAX_ZOOM).rev() {
+            let parents: AHashSet<TilePosition> = dirty_tiles
+                .iter()
+                .map(|t| (t.0.div_euclid(2), t.1.div_euclid(2)))
+                .collect();
+
+            for tile in parents.iter() {
+                let mut image = RgbaImage::new(TILE_SIZE, TILE_SIZE);
+                for x in 0..2_i64 {
+                    for z in 0..2_i64 {
+                        let child_path = self.get_tile_path(zoom + 1, &(tile.0 * 2 + x, tile.1 * 2 + z));
+                        if !child_path.exists() {
+                            continue;
+                        }
+                        let child = match image::open(&child_path) {
+                            Ok(i) => i.to_rgba8(),
+                            Err(e) => return Err(format!("Tile \"{}\" read error: {}", child_path.display(), e)),
+                        };
+                        let child = imageops::resize(&child, TILE_SIZE / 2, TILE_SIZE / 2, FilterType::Triangle);
+                        imageops::replace(
+                            &mut image,
+                            &child,
+                            x * (TILE_SIZE / 2) as i64,
+                            z * (TILE_SIZE / 2) as i64,
+                        );
+                    }
+                }
+                self.save_tile(zoom, tile, &image)?;
+                rendered += 1;
+            }
+            dirty_tiles = parents;
+        }
+
+        self.write_viewer()?;
+        self.write_manifest(&manifest)?;
+        Ok(rendered)
+    }
+
+    /// Returns RGBA pixels of the top view of the chunk
+    fn render_saved_chunk(&self, chunk_position: &ChunkPosition, chunk_id: i64) -> Option<Vec<u8>> {
+        let chunk_data = match self.storage.lock().load_chunk_data(chunk_id) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!(target: "worlds", "Web map of &e\"{}\"&r chunk {} skipped: {}", self.world_slug, chunk_position, e);
+                return None;
+            }
+        };
+        Some(render_chunk(&chunk_data, &self.colors))
+    }
+
+    fn get_tile_path(&self, zoom: u32, tile: &TilePosition) -> PathBuf {
+        let mut path = self.output_path.clone();
+        path.push(zoom.to_string());
+        path.push(tile.0.to_string());
+        path.push(format!("{}.png", tile.1));
+        path
+    }
+
+    fn save_tile(&self, zoom: u32, tile: &TilePosition, image: &RgbaImage) -> Result<(), String> {
+        let path = self.get_tile_path(zoom, tile);
+
+        // Empty tiles are not stored at all
+        if image.pixels().all(|p| p[3] == 0) {
+            if path.exists() {
+                if let Err(e) = remove_file(&path) {
+                    return Err(format!("Tile \"{}\" remove error: {}", path.display(), e));
+                }
+            }
+            return Ok(());
+        }
+
+        if let Err(e) = create_dir_all(path.parent().unwrap()) {
+            return Err(format!("Unable to create dir for tile \"{}\": {}", path.display(), e));
+        }
+        if let Err(e) = image.save(&path) {
+            return Err(format!("Tile \"{}\" save error: {}", path.display(), e));
+        }
+        Ok(())
+    }
+
+    fn read_manifest(&self) -> Result<WebMapManifest, String> {
+        let mut path = self.output_path.clone();
+        path.push(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let manifest = match std::fs::read_to_string(&path) {
+            Ok(d) => d,
+            Err(e) => return Err(format!("Manifest \"{}\" read error: {}", path.display(), e)),
+        };
+        match serde_yaml::from_str(&manifest) {
+            Ok(m) => Ok(m),
+            Err(e) => Err(format!("Manifest \"{}\" yaml parse error: {}", path.display(), e)),
+        }
+    }
+
+    fn write_manifest(&self, manifest: &WebMapManifest) -> Result<(), String> {
+        let mut path = self.output_path.clone();
+        path.push(MANIFEST_FILE);
+        let file = match File::create(&path) {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Manifest \"{}\" create error: {}", path.display(), e)),
+        };
+        if let Err(e) = serde_yaml::to_writer(file, manifest) {
+            return Err(format!("Manifest \"{}\" write error: {}", path.display(), e));
+        }
+        Ok(())
+    }
+
+    fn write_viewer(&self) -> Result<(), String> {
+        let mut path = self.output_path.clone();
+        path.push("index.html");
+        let html = VIEWER_HTML
+            .replace("{world_slug}", &self.world_slug)
+            .replace("{max_zoom}", &MAX_ZOOM.to_string())
+            .replace("{tile_size}", &TILE_SIZE.to_string());
+        if let Err(e) = std::fs::write(&path, html) {
+            return Err(format!("Viewer \"{}\" write error: {}", path.display(), e));
+        }
+        Ok(())
+    }
+}
+
+fn get_tile_position(chunk_position: &ChunkPosition) -> TilePosition {
+    (
+        chunk_position.x.div_euclid(CHUNKS_PER_TILE),
+        chunk_position.z.div_euclid(CHUNKS_PER_TILE),
+    )
+}
+
+/// Top block color of each column, shaded by its height
+fn render_chunk(chunk_data: &ChunkData, colors: &AHashMap<BlockIndexType, BlockColor>) -> Vec<u8> {
+    let size = CHUNK_SIZE as usize;
+    let max_height = (VERTICAL_SECTIONS * size) as f32;
+    let mut pixels = vec![0_u8; size * size * 4];
+
+    for x in 0..CHUNK_SIZE {
+        for z in 0..CHUNK_SIZE {
+            'column: for section in (0..VERTICAL_SECTIONS).rev() {
+                let Some(section_data) = chunk_data.get(section) else {
+                    continue;
+                };
+                for y in (0..CHUNK_SIZE).rev() {
+                    let Some(block_info) = section_data.get(&ChunkBlockPosition::new(x, y, z)) else {
+                        continue;
+                    };
+                    let color = match colors.get(&block_info.get_id()) {
+                        Some(c) => c,
+                        None => &DEFAULT_MAP_COLOR,
+                    };
+                    let height = (section * size + y as usize) as f32;
+                    let shade = 0.6 + 0.4 * (height / max_height);
+
+                    let i = (z as usize * size + x as usize) * 4;
+                    pixels[i] = (color[0] as f32 * shade) as u8;
+                    pixels[i + 1] = (color[1] as f32 * shade) as u8;
+                    pixels[i + 2] = (color[2] as f32 * shade) as u8;
+                    pixels[i + 3] = 255;
+                    break 'column;
+                }
+            }
+        }
+    }
+    pixels
+}
+
+const VIEWER_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>{world_slug}</title>
+  <link rel="stylesheet" href="https://unpkg.com/leaflet@1.9.4/dist/leaflet.css">
+  <script src="https://unpkg.com/leaflet@1.9.4/dist/leaflet.js"></script>
+  <style>html, body, #map { height: 100%; margin: 0; background: #000; }</style>
+</head>
+<body>
+  <div id="map"></div>
+  <script>
+    // One map unit is one block; one pixel is one block at the max zoom
+    const factor = Math.pow(2, {max_zoom});
+    const crs = L.extend({}, L.CRS.Simple, {
+      transformation: new L.Transformation(1 / factor, 0, 1 / factor, 0),
+    });
+    const map = L.map('map', { crs: crs, minZoom: 0, maxZoom: {max_zoom} + 2 }).setView([0, 0], {max_zoom});
+    L.tileLayer('{z}/{x}/{y}.png', {
+      tileSize: {tile_size},
+      minZoom: 0,
+      maxNativeZoom: {max_zoom},
+      maxZoom: {max_zoom} + 2,
+      noWrap: true,
+    }).addTo(map);
+    map.on('mousemove', (e) => {
+      document.title = '{world_slug} x:' + Math.floor(e.latlng.lng) + ' z:' + Math.floor(e.latlng.lat);
+    });
+  </script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use common::chunks::chunk_position::ChunkPosition;
+
+    use super::get_tile_position;
+
+    #[test]
+    fn test_tile_position() {
+        assert_eq!(get_tile_position(&ChunkPosition::new(0, 15)), (0, 0));
+        assert_eq!(get_tile_position(&ChunkPosition::new(16, 0)), (1, 0));
+        assert_eq!(get_tile_position(&ChunkPosition::new(-1, -16)), (-1, -1));
+        assert_eq!(get_tile_position(&ChunkPosition::new(-17, 0)), (-2, 0));
+    }
+}
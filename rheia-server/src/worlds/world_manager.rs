@@ -1,20 +1,40 @@
 use super::ecs::Ecs;
-use crate::CHUNKS_DISTANCE;
+use crate::MIN_CHUNKS_DISTANCE;
+use crate::SPAWN_AREA_DISTANCE;
+use crate::client_resources::server_settings::ServerSettings;
 use crate::entities::EntityComponent;
 use crate::entities::entity::{Position, Rotation};
-use crate::network::client_network::WorldEntity;
+use crate::entities::falling_block::FallingBlockComponent;
+use crate::entities::npc::{NpcComponent, npc_from_stored, npc_to_stored};
+use crate::entities::npc_behaviour::{NpcBehaviour, NpcBehaviourComponent};
+use crate::entities::skin::EntitySkinComponent;
+use crate::network::client_network::{ClientNetwork, WorldEntity};
 use crate::worlds::chunks::chunks_map::ChunkMap;
+use crate::worlds::regions::WorldRegions;
+use crate::worlds::spawn::get_safe_position;
+use crate::worlds::weather::update_weather;
+use ahash::AHashMap;
+use bevy::prelude::Entity;
 use bevy_ecs::bundle::Bundle;
+use bracket_lib::random::RandomNumberGenerator;
 use common::WorldStorageManager;
-use common::chunks::block_position::BlockPositionTrait;
-use common::chunks::chunk_data::BlockIndexType;
+use common::chunks::block_position::{BlockPosition, BlockPositionTrait};
+use common::chunks::chunk_data::{BlockDataInfo, BlockIndexType};
 use common::chunks::chunk_position::ChunkPosition;
+use common::chunks::stored_entity::StoredEntity;
+use common::game_rules::{GameRule, GameRules};
+use common::world_border::WorldBorder;
 use common::world_generator::default::WorldGeneratorSettings;
+use common::world_time::WorldTime;
+use common::world_weather::{Weather, WorldWeather};
 use common::worlds_storage::taits::{IWorldStorage, WorldStorageSettings};
-use network::messages::ServerMessages;
+use network::messages::{NetworkEntitySkin, ServerMessages};
 use std::collections::BTreeMap;
 use std::time::Duration;
 
+/// How often the players receive the world time
+const WORLD_TIME_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct ChunkChanged {
     pub old_chunk: ChunkPosition,
     pub new_chunk: ChunkPosition,
@@ -26,6 +46,33 @@ pub struct WorldManager {
     slug: String,
     ecs: Ecs,
     chunks_map: ChunkMap,
+    regions: WorldRegions,
+
+    // Where the players appear; the area around it is kept loaded
+    spawn: BlockPosition,
+
+    time: WorldTime,
+    time_sync_timer: Duration,
+
+    weather: WorldWeather,
+    weather_changed: bool,
+
+    border_changed: bool,
+
+    game_rules: GameRules,
+
+    // Ids of the npcs are never reused
+    last_npc_id: u64,
+    // Npcs of the loaded chunks by their ids
+    npcs: AHashMap<u64, Entity>,
+}
+
+fn get_block_center(position: &BlockPosition) -> Position {
+    Position::new(
+        position.get_x() as f32 + 0.5,
+        position.get_y() as f32,
+        position.get_z() as f32 + 0.5,
+    )
 }
 
 impl WorldManager {
@@ -43,10 +90,62 @@ impl WorldManager {
         if let Err(e) = WorldStorageManager::validate_block_id_map(slug.clone(), world_storage_settings, block_id_map) {
             return Err(e);
         }
+        let mut chunks_map = ChunkMap::new(seed, world_settings, storage);
+        let forced = match chunks_map.load_forced_chunks() {
+            Ok(c) => c,
+            Err(e) => return Err(format!("World \"{}\" forced chunks load error: {}", slug, e)),
+        };
+        if forced > 0 {
+            log::info!(target: "worlds", "World &a\"{}\"&r forced chunks: &e{}", slug, forced);
+        }
+        let spawn = match chunks_map.get_storage().lock().load_spawn_position() {
+            Ok(p) => p.unwrap_or(BlockPosition::new(0, 80, 0)),
+            Err(e) => return Err(format!("World \"{}\" spawn load error: {}", slug, e)),
+        };
+        chunks_map.set_spawn_area(&spawn.get_chunk_position(), SPAWN_AREA_DISTANCE);
+
+        let time = match chunks_map.get_storage().lock().load_world_time() {
+            Ok(t) => t.unwrap_or_default(),
+            Err(e) => return Err(format!("World \"{}\" time load error: {}", slug, e)),
+        };
+        let weather = match chunks_map.get_storage().lock().load_world_weather() {
+            Ok(w) => w.unwrap_or_default(),
+            Err(e) => return Err(format!("World \"{}\" weather load error: {}", slug, e)),
+        };
+        let border = match chunks_map.get_storage().lock().load_world_border() {
+            Ok(b) => b,
+            Err(e) => return Err(format!("World \"{}\" border load error: {}", slug, e)),
+        };
+        chunks_map.set_border(border);
+        let game_rules = match chunks_map.get_storage().lock().load_game_rules() {
+            Ok(r) => r,
+            Err(e) => return Err(format!("World \"{}\" game rules load error: {}", slug, e)),
+        };
+
+        let last_npc_id = match chunks_map.get_storage().lock().load_last_entity_id() {
+            Ok(id) => id,
+            Err(e) => return Err(format!("World \"{}\" entity id load error: {}", slug, e)),
+        };
+
+        let regions = match WorldRegions::load(chunks_map.get_storage()) {
+            Ok(r) => r,
+            Err(e) => return Err(format!("World \"{}\" regions load error: {}", slug, e)),
+        };
+
         Ok(WorldManager {
             slug: slug,
             ecs: Ecs::new(),
-            chunks_map: ChunkMap::new(seed, world_settings, storage),
+            chunks_map,
+            regions,
+            spawn,
+            time,
+            time_sync_timer: Default::default(),
+            weather,
+            weather_changed: false,
+            border_changed: false,
+            game_rules,
+            last_npc_id,
+            npcs: Default::default(),
         })
     }
 
@@ -66,10 +165,175 @@ impl WorldManager {
         &mut self.chunks_map
     }
 
+    pub fn get_regions(&self) -> &WorldRegions {
+        &self.regions
+    }
+
+    pub fn get_regions_mut(&mut self) -> &mut WorldRegions {
+        &mut self.regions
+    }
+
     pub fn get_slug(&self) -> &String {
         &self.slug
     }
 
+    pub fn get_spawn(&self) -> &BlockPosition {
+        &self.spawn
+    }
+
+    /// Saves the spawn and moves the spawn area to it
+    pub fn set_spawn(&mut self, spawn: BlockPosition) -> Result<(), String> {
+        self.chunks_map.get_storage().lock().save_spawn_position(&spawn)?;
+        self.chunks_map
+            .set_spawn_area(&spawn.get_chunk_position(), SPAWN_AREA_DISTANCE);
+        self.spawn = spawn;
+        Ok(())
+    }
+
+    /// Where the players appear in this world
+    pub fn get_spawn_position(&self) -> Position {
+        get_block_center(&self.spawn)
+    }
+
+    /// Spawn position or the nearest surface if the spawn is blocked
+    pub fn get_safe_spawn_position(&self, server_settings: &ServerSettings) -> Position {
+        match get_safe_position(&self.chunks_map, server_settings, &self.spawn) {
+            Ok(Some(p)) => get_block_center(&p),
+            Ok(None) => self.get_spawn_position(),
+            Err(e) => {
+                log::error!(target: "worlds", "World &e\"{}\"&r safe spawn error: {}", self.slug, e);
+                self.get_spawn_position()
+            }
+        }
+    }
+
+    /// Players can't see unloaded areas, so they are moved
+    /// to the nearest surface if the position is blocked
+    pub fn get_safe_teleport_position(&self, server_settings: &ServerSettings, position: Position) -> Position {
+        if self.chunks_map.is_chunk_loaded(&position.get_chunk_position()) {
+            return position;
+        }
+        let block_position = BlockPosition::from_position(&position.to_network());
+        match get_safe_position(&self.chunks_map, server_settings, &block_position) {
+            Ok(Some(p)) if p == block_position => position,
+            Ok(Some(p)) => get_block_center(&p),
+            Ok(None) => position,
+            Err(e) => {
+                log::error!(target: "worlds", "World &e\"{}\"&r safe teleport error: {}", self.slug, e);
+                position
+            }
+        }
+    }
+
+    pub fn get_time(&self) -> &WorldTime {
+        &self.time
+    }
+
+    /// Saves the time; players receive it with the next tick
+    pub fn set_time(&mut self, time: f64) -> Result<(), String> {
+        self.time.set_time(time);
+        self.chunks_map.get_storage().lock().save_world_time(&self.time)?;
+        self.time_sync_timer = WORLD_TIME_SYNC_INTERVAL;
+        Ok(())
+    }
+
+    /// Zero speed freezes the time
+    pub fn set_time_speed(&mut self, speed: f32) -> Result<(), String> {
+        self.time.set_speed(speed);
+        self.chunks_map.get_storage().lock().save_world_time(&self.time)?;
+        self.time_sync_timer = WORLD_TIME_SYNC_INTERVAL;
+        Ok(())
+    }
+
+    /// Returns true when the time must be sent to the players
+    pub fn update_time(&mut self, delta: Duration) -> bool {
+        self.time.advance(delta);
+        self.time_sync_timer += delta;
+        if self.time_sync_timer < WORLD_TIME_SYNC_INTERVAL {
+            return false;
+        }
+        self.time_sync_timer = Duration::ZERO;
+        true
+    }
+
+    pub fn get_weather(&self) -> &WorldWeather {
+        &self.weather
+    }
+
+    /// Saves the weather; players receive it with the next tick
+    pub fn set_weather(&mut self, weather: Weather, duration: Option<f32>) -> Result<(), String> {
+        self.weather.set_weather(weather, duration);
+        self.chunks_map.get_storage().lock().save_world_weather(&self.weather)?;
+        self.weather_changed = true;
+        Ok(())
+    }
+
+    /// Disabled cycle keeps the weather until it's over
+    pub fn set_weather_cycle(&mut self, cycle: bool) -> Result<(), String> {
+        self.weather.set_cycle(cycle);
+        self.chunks_map.get_storage().lock().save_world_weather(&self.weather)?;
+        Ok(())
+    }
+
+    /// Returns true when the weather must be sent to the players
+    pub fn update_weather(&mut self, delta: Duration, rng: &mut RandomNumberGenerator) -> bool {
+        let changed = update_weather(&mut self.weather, delta, rng);
+        changed || std::mem::take(&mut self.weather_changed)
+    }
+
+    /// None if the world is infinite
+    pub fn get_border(&self) -> Option<&WorldBorder> {
+        self.chunks_map.get_border()
+    }
+
+    /// Saves the border; players receive it with the next tick
+    pub fn set_border(&mut self, border: Option<WorldBorder>) -> Result<(), String> {
+        self.chunks_map
+            .get_storage()
+            .lock()
+            .save_world_border(border.as_ref())?;
+        self.chunks_map.set_border(border);
+        self.border_changed = true;
+        Ok(())
+    }
+
+    pub fn is_inside_border(&self, position: &BlockPosition) -> bool {
+        match self.get_border() {
+            Some(border) => border.contains(position.get_x() as f32 + 0.5, position.get_z() as f32 + 0.5),
+            None => true,
+        }
+    }
+
+    /// Returns true when the border must be sent to the players
+    ///
+    /// Players receive only the changes; they animate the resize by themselves
+    pub fn update_border(&mut self, delta: Duration) -> bool {
+        if let Some(border) = self.chunks_map.get_border_mut() {
+            border.advance(delta);
+        }
+        std::mem::take(&mut self.border_changed)
+    }
+
+    pub fn get_game_rules(&self) -> &GameRules {
+        &self.game_rules
+    }
+
+    /// Value is parsed according to the type of the rule
+    pub fn set_game_rule(&mut self, rule: &GameRule, value: &str) -> Result<(), String> {
+        let mut game_rules = self.game_rules.clone();
+        game_rules.set_value(rule, value)?;
+        self.chunks_map.get_storage().lock().save_game_rules(&game_rules)?;
+        self.game_rules = game_rules;
+        Ok(())
+    }
+
+    /// Render distance of the player limited by the world rules
+    pub fn get_view_distance(&self, render_distance: u16) -> u16 {
+        render_distance
+            .min(self.game_rules.get_max_view_distance())
+            .max(MIN_CHUNKS_DISTANCE)
+    }
+
     pub fn get_chunks_count(&self) -> usize {
         self.get_chunks_map().count()
     }
@@ -79,31 +343,205 @@ impl WorldManager {
         position: Position,
         bundle: B,
         components: Vec<EntityComponent>,
+        chunks_distance: u16,
     ) -> WorldEntity {
+        let chunks_distance = self.get_view_distance(chunks_distance);
         let entity = self.get_ecs_mut().spawn(bundle, position.get_chunk_position());
+        self.insert_components(entity, components);
 
+        self.get_chunks_map_mut()
+            .start_chunks_render(entity, &position.get_chunk_position(), chunks_distance);
+
+        WorldEntity::new(self.slug.clone(), entity)
+    }
+
+    fn insert_components(&mut self, entity: Entity, components: Vec<EntityComponent>) {
         let mut entity_ecs = self.get_ecs_mut().entity_mut(entity);
-        if components.len() > 0 {
-            for component in components {
-                match component {
-                    EntityComponent::Tag(c) => {
-                        if let Some(c) = c {
-                            entity_ecs.insert(c);
-                        }
+        for component in components {
+            match component {
+                EntityComponent::Tag(c) => {
+                    if let Some(c) = c {
+                        entity_ecs.insert(c);
                     }
-                    EntityComponent::Skin(c) => {
-                        if let Some(c) = c {
-                            entity_ecs.insert(c);
-                        }
+                }
+                EntityComponent::Skin(c) => {
+                    if let Some(c) = c {
+                        entity_ecs.insert(c);
                     }
                 }
             }
         }
+    }
 
-        self.get_chunks_map_mut()
-            .start_chunks_render(entity, &position.get_chunk_position(), CHUNKS_DISTANCE);
+    /// Spawns the server-owned entity, which is saved with the chunk it is in
+    ///
+    /// Skin is required to stream the npc to the players
+    pub fn spawn_npc(
+        &mut self,
+        position: Position,
+        rotation: Rotation,
+        components: Vec<EntityComponent>,
+    ) -> Result<Entity, String> {
+        let id = self.last_npc_id + 1;
+        self.chunks_map.get_storage().lock().save_last_entity_id(id)?;
+        self.last_npc_id = id;
 
-        WorldEntity::new(self.slug.clone(), entity)
+        let entity = self.insert_npc(id, position, rotation, components);
+        self.save_chunk_npcs(&position.get_chunk_position())?;
+        Ok(entity)
+    }
+
+    fn insert_npc(
+        &mut self,
+        id: u64,
+        position: Position,
+        rotation: Rotation,
+        components: Vec<EntityComponent>,
+    ) -> Entity {
+        let home = BlockPosition::from_position(&position.to_network());
+        let bundle = (
+            position,
+            rotation,
+            NpcComponent::create(id),
+            NpcBehaviourComponent::create(NpcBehaviour::Idle, home),
+        );
+        let entity = self.get_ecs_mut().spawn(bundle, position.get_chunk_position());
+        self.insert_components(entity, components);
+        self.npcs.insert(id, entity);
+        entity
+    }
+
+    /// Spawns the npcs saved with the chunk after it was loaded
+    pub fn spawn_stored_npcs(&mut self, chunk_position: &ChunkPosition) -> Vec<Entity> {
+        let mut spawned: Vec<Entity> = Default::default();
+        for stored in self.chunks_map.take_stored_entities(chunk_position) {
+            // Npc stays in the world while its chunk is not saved
+            if self.get_npc(stored.get_id()).is_some() {
+                continue;
+            }
+            match npc_from_stored(&stored) {
+                Ok((position, rotation, components)) => {
+                    spawned.push(self.insert_npc(stored.get_id(), position, rotation, components));
+                }
+                Err(e) => log::error!(target: "worlds", "World &e\"{}\"&r {}", self.slug, e),
+            }
+        }
+        spawned
+    }
+
+    pub fn get_npc(&self, id: u64) -> Option<Entity> {
+        self.npcs.get(&id).copied()
+    }
+
+    /// Despawn must be sent to the players before the removal
+    pub fn remove_npc(&mut self, entity: Entity) -> Result<(), String> {
+        let (id, chunk_position) = match self.ecs.get_entity(entity) {
+            Some(e) => (
+                e.get::<NpcComponent>().map(|n| n.get_id()),
+                e.get::<Position>().map(|p| p.get_chunk_position()),
+            ),
+            None => (None, None),
+        };
+        if let Some(id) = id {
+            self.npcs.remove(&id);
+        }
+        self.ecs.despawn(entity, chunk_position);
+        if let Some(chunk_position) = chunk_position {
+            self.save_chunk_npcs(&chunk_position)?;
+        }
+        Ok(())
+    }
+
+    /// Moves the npc; stored npcs of both chunks are updated if it has changed the chunk
+    pub fn npc_move(&mut self, entity: Entity, position: Position, rotation: Rotation) -> Option<ChunkChanged> {
+        let mut npc_entity = self.ecs.entity_mut(entity);
+        let mut old_position = npc_entity.get_mut::<Position>().unwrap();
+        let old_chunk = old_position.get_chunk_position();
+        *old_position = position;
+        *npc_entity.get_mut::<Rotation>().unwrap() = rotation;
+
+        let new_chunk = position.get_chunk_position();
+        if old_chunk == new_chunk {
+            return None;
+        }
+        self.ecs.entity_moved_chunk(&entity, &old_chunk, &new_chunk);
+        for chunk_position in [&old_chunk, &new_chunk] {
+            if let Err(e) = self.save_chunk_npcs(chunk_position) {
+                log::error!(target: "worlds", "&cChunk {} npcs save error: {}", chunk_position, e);
+            }
+        }
+        Some(ChunkChanged {
+            old_chunk,
+            new_chunk,
+            abandoned_chunks: Default::default(),
+            new_chunks: Default::default(),
+        })
+    }
+
+    /// Replaces the stored npcs of the chunk with the npcs inside of it
+    fn save_chunk_npcs(&self, chunk_position: &ChunkPosition) -> Result<(), String> {
+        let npcs: Vec<StoredEntity> = self
+            .ecs
+            .get_chunk_entities(chunk_position)
+            .unwrap()
+            .iter()
+            .filter_map(npc_to_stored)
+            .collect();
+        self.chunks_map
+            .get_storage()
+            .lock()
+            .save_chunk_entities(chunk_position, &npcs)
+    }
+
+    fn get_npc_chunks(&mut self) -> Vec<ChunkPosition> {
+        let mut chunks: Vec<ChunkPosition> = Default::default();
+        for entity in self.ecs.get_entities_with::<NpcComponent>() {
+            let entity_ref = self.ecs.get_entity(entity).unwrap();
+            let chunk_position = entity_ref.get::<Position>().unwrap().get_chunk_position();
+            if !chunks.contains(&chunk_position) {
+                chunks.push(chunk_position);
+            }
+        }
+        chunks
+    }
+
+    /// Npcs of the unloaded chunks are saved and removed from the world
+    fn despawn_unloaded_npcs(&mut self) {
+        for chunk_position in self.get_npc_chunks() {
+            if self.chunks_map.get_chunks().contains_key(&chunk_position) {
+                continue;
+            }
+            if let Err(e) = self.save_chunk_npcs(&chunk_position) {
+                log::error!(target: "worlds", "&cChunk {} npcs save error: {}", chunk_position, e);
+                continue;
+            }
+            let npcs: Vec<(Entity, u64)> = self
+                .ecs
+                .get_chunk_entities(&chunk_position)
+                .unwrap()
+                .iter()
+                .filter_map(|e| Some((e.id(), e.get::<NpcComponent>()?.get_id())))
+                .collect();
+            for (entity, id) in npcs {
+                self.npcs.remove(&id);
+                self.ecs.despawn(entity, Some(chunk_position));
+            }
+        }
+    }
+
+    /// Spawns the entity of the block in the center of its position
+    ///
+    /// The block itself must be removed from the world
+    pub fn spawn_falling_block(&mut self, position: &BlockPosition, block_info: BlockDataInfo) -> Entity {
+        let p = position.get_position();
+        let position = Position::new(p.x + 0.5, p.y + 0.5, p.z + 0.5);
+        let bundle = (
+            position,
+            Rotation::default(),
+            EntitySkinComponent::create(NetworkEntitySkin::Block(block_info.get_id())),
+            FallingBlockComponent::create(block_info),
+        );
+        self.get_ecs_mut().spawn(bundle, position.get_chunk_position())
     }
 
     /// Records the player's movement and updates his position in ECS.
@@ -117,7 +555,10 @@ impl WorldManager {
     ) -> Option<ChunkChanged> {
         let mut changed_chunks: Option<ChunkChanged> = None;
 
+        let max_view_distance = self.game_rules.get_max_view_distance();
         let mut player_entity = self.ecs.entity_mut(world_entity.get_entity());
+        let chunks_distance = player_entity.get::<ClientNetwork>().unwrap().get_render_distance();
+        let chunks_distance = chunks_distance.min(max_view_distance).max(MIN_CHUNKS_DISTANCE);
         let mut old_position = player_entity.get_mut::<Position>().unwrap();
 
         let old_chunk = old_position.get_chunk_position();
@@ -128,7 +569,7 @@ impl WorldManager {
                 world_entity.get_entity(),
                 &old_chunk,
                 &new_chunk,
-                CHUNKS_DISTANCE,
+                chunks_distance,
             );
             changed_chunks = Some(chunks);
         }
@@ -143,7 +584,26 @@ impl WorldManager {
         changed_chunks
     }
 
+    /// Updates the player's chunks vision after his render distance was changed
+    pub fn change_render_distance(&mut self, world_entity: &WorldEntity, chunks_distance: u16) -> ChunkChanged {
+        let chunks_distance = self.get_view_distance(chunks_distance);
+        let player_entity = self.ecs.get_entity(world_entity.get_entity()).unwrap();
+        let chunk = player_entity.get::<Position>().unwrap().get_chunk_position();
+        self.chunks_map
+            .update_chunks_render(world_entity.get_entity(), &chunk, &chunk, chunks_distance)
+    }
+
     pub fn save(&mut self) -> Result<(), String> {
+        self.chunks_map.get_storage().lock().save_world_time(&self.time)?;
+        self.chunks_map.get_storage().lock().save_world_weather(&self.weather)?;
+        self.chunks_map
+            .get_storage()
+            .lock()
+            .save_world_border(self.chunks_map.get_border())?;
+        self.chunks_map.get_storage().lock().save_game_rules(&self.game_rules)?;
+        for chunk_position in self.get_npc_chunks() {
+            self.save_chunk_npcs(&chunk_position)?;
+        }
         self.chunks_map.save()?;
         log::info!(target: "worlds", "World &a\"{}\"&r saved", self.slug);
         Ok(())
@@ -165,6 +625,7 @@ impl WorldManager {
     pub fn update_chunks(&mut self, delta: Duration) {
         let world_slug = self.get_slug().clone();
         self.chunks_map.update_chunks(delta, &world_slug);
+        self.despawn_unloaded_npcs();
     }
 
     pub fn get_network_chunk_bytes(&self, chunk_position: &ChunkPosition) -> Option<ServerMessages> {
@@ -6,13 +6,15 @@ use bevy::time::Time;
 use bevy_ecs::system::Res;
 use common::{
     WorldStorageManager,
-    chunks::chunk_data::BlockIndexType,
+    chunks::{chunk_data::BlockIndexType, light_propagation::LightBlocks},
     world_generator::default::WorldGeneratorSettings,
     worlds_storage::taits::{IWorldStorage, WorldStorageSettings},
 };
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use super::world_manager::WorldManager;
+use crate::network::{clients_container::ClientsContainer, sync_world_time::sync_world_time};
+
+use super::{chunks::chunk_column::CorruptChunkPolicy, world_manager::WorldManager};
 
 type WorldsType = HashMap<String, Arc<RwLock<WorldManager>>>;
 
@@ -20,17 +22,24 @@ type WorldsType = HashMap<String, Arc<RwLock<WorldManager>>>;
 #[derive(Resource)]
 pub struct WorldsManager {
     worlds: WorldsType,
+    corrupt_chunk_policy: CorruptChunkPolicy,
+    light_blocks: Arc<LightBlocks>,
 }
 
-impl Default for WorldsManager {
-    fn default() -> Self {
+impl WorldsManager {
+    pub fn new(corrupt_chunk_policy: CorruptChunkPolicy) -> Self {
         WorldsManager {
             worlds: Default::default(),
+            corrupt_chunk_policy,
+            light_blocks: Default::default(),
         }
     }
-}
 
-impl WorldsManager {
+    /// Must be set before the worlds are created
+    pub fn set_light_blocks(&mut self, light_blocks: LightBlocks) {
+        self.light_blocks = Arc::new(light_blocks);
+    }
+
     pub fn scan_worlds(
         &mut self,
         world_storage_settings: &WorldStorageSettings,
@@ -62,10 +71,13 @@ impl WorldsManager {
     }
 
     pub fn save_all(&self) -> Result<(), String> {
-        for (_world_slug, world) in self.worlds.iter() {
-            world.write().save()?;
+        let mut result = Ok(());
+        for (world_slug, world) in self.worlds.iter() {
+            if let Err(e) = world.write().save() {
+                result = Err(format!("&cWorld &4\"{}\"&c save error: {}", world_slug, e));
+            }
         }
-        Ok(())
+        result
     }
 
     pub fn create_world(
@@ -79,10 +91,15 @@ impl WorldsManager {
         if self.worlds.contains_key(&slug) {
             return Err(format!("&cWorld with slug &4\"{}\"&c already exists", slug));
         }
-        let world = match WorldManager::new(slug.clone(), seed, world_settings, world_storage_settings, block_id_map) {
-            Ok(w) => w,
-            Err(e) => return Err(format!("&cWorld &4\"{}\"&c error: {}", slug, e)),
-        };
+        let mut world =
+            match WorldManager::new(slug.clone(), seed, world_settings, world_storage_settings, block_id_map) {
+                Ok(w) => w,
+                Err(e) => return Err(format!("&cWorld &4\"{}\"&c error: {}", slug, e)),
+            };
+        world
+            .get_chunks_map_mut()
+            .set_corrupt_chunk_policy(self.corrupt_chunk_policy);
+        world.get_chunks_map_mut().set_light_blocks(self.light_blocks.clone());
         self.worlds.insert(slug, Arc::new(RwLock::new(world)));
         Ok(())
     }
@@ -115,3 +132,12 @@ pub fn update_world_chunks(worlds_manager: Res<WorldsManager>, time: Res<Time>)
         world.write().update_chunks(time.delta());
     }
 }
+
+pub fn update_world_time(worlds_manager: Res<WorldsManager>, clients: Res<ClientsContainer>, time: Res<Time>) {
+    for (_key, world) in worlds_manager.get_worlds().iter() {
+        let mut world_manager = world.write();
+        if world_manager.update_time(time.delta()) {
+            sync_world_time(&*world_manager, &*clients);
+        }
+    }
+}